@@ -0,0 +1,131 @@
+//the board view, which shows a list's top-level items as cards in columns instead of as a list
+//it's only another way of drawing the same TodoList: a kanban list's columns are the statuses it names (see
+//ListKind::Kanban), and any other list gets a column per status, so moving a card always changes the item's status
+//the settings here are per column, keyed by column name, and saved with the list
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use ::serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ListView {
+    List,
+    Board,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct BoardSettings {
+    #[serde(default)]
+    wip_limits: BTreeMap<String, usize>, //the most cards a column takes; columns without one take any number
+    #[serde(default)]
+    collapsed: BTreeSet<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BoardError {
+    UnknownColumn(String),
+    MissingItem(usize),
+    ColumnFull { column: String, limit: usize },
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::UnknownColumn(column) => write!(f, "the board has no column called \"{column}\""),
+            BoardError::MissingItem(id) => write!(f, "there is no item with id {id} on the board"),
+            BoardError::ColumnFull { column, limit } => write!(f, "{column} already has its limit of {limit} cards"),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+impl ListView {
+    pub const ALL: [ListView; 2] = [ListView::List, ListView::Board];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ListView::List => "List",
+            ListView::Board => "Board",
+        }
+    }
+}
+
+impl BoardSettings {
+    pub fn wip_limit(&self, column: &str) -> Option<usize> {
+        self.wip_limits.get(column).copied()
+    }
+
+    //None (or a limit of 0) takes the limit off
+    pub fn set_wip_limit(&mut self, column: &str, limit: Option<usize>) {
+        match limit.filter(|x| *x > 0) {
+            Some(limit) => self.wip_limits.insert(String::from(column), limit),
+            None => self.wip_limits.remove(column),
+        };
+    }
+
+    pub fn is_collapsed(&self, column: &str) -> bool {
+        self.collapsed.contains(column)
+    }
+
+    pub fn set_collapsed(&mut self, column: &str, collapsed: bool) {
+        if collapsed {
+            self.collapsed.insert(String::from(column));
+        } else {
+            self.collapsed.remove(column);
+        }
+    }
+
+    //keeps a column's settings when whatever it comes from is renamed
+    pub fn rename_column(&mut self, name: &str, new_name: &str) {
+        if let Some(limit) = self.wip_limits.remove(name) {
+            self.wip_limits.insert(String::from(new_name), limit);
+        }
+        if self.collapsed.remove(name) {
+            self.collapsed.insert(String::from(new_name));
+        }
+    }
+
+    pub fn remove_column(&mut self, name: &str) {
+        self.wip_limits.remove(name);
+        self.collapsed.remove(name);
+    }
+
+    //drops the settings of every column that isn't one of columns, for when the columns are replaced
+    pub fn retain_columns(&mut self, columns: &[String]) {
+        self.wip_limits.retain(|name, _| columns.contains(name));
+        self.collapsed.retain(|name| columns.contains(name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_settings_follow_renames() {
+        let mut board = BoardSettings::default();
+        board.set_wip_limit("Doing", Some(3));
+        board.set_collapsed("Doing", true);
+        board.rename_column("Doing", "In progress");
+        assert_eq!(board.wip_limit("Doing"), None);
+        assert_eq!(board.wip_limit("In progress"), Some(3));
+        assert!(board.is_collapsed("In progress"));
+
+        board.set_wip_limit("In progress", Some(0));
+        assert_eq!(board.wip_limit("In progress"), None);
+    }
+
+    #[test]
+    fn replacing_columns_drops_settings_for_the_old_ones() {
+        let mut board = BoardSettings::default();
+        board.set_wip_limit("Doing", Some(3));
+        board.set_wip_limit("Review", Some(2));
+        board.set_collapsed("Done", true);
+        board.retain_columns(&[String::from("Review"), String::from("Shipped")]);
+        assert_eq!(board.wip_limit("Doing"), None);
+        assert_eq!(board.wip_limit("Review"), Some(2));
+        assert!(!board.is_collapsed("Done"));
+    }
+}
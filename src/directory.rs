@@ -0,0 +1,146 @@
+//a folder of list files (like lists/) used as the workspace
+//indexing only reads file names, and a list is only read from disk when it's opened,
+//so a folder with hundreds of lists opens straight away
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{list_file_path, TodoList};
+
+pub struct ListDirectory {
+    root: PathBuf,
+    paths: Vec<PathBuf>, //every *.json file directly in root, sorted
+}
+
+impl ListDirectory {
+    //creates the folder if it doesn't exist yet
+    pub fn open(root: &Path) -> io::Result<ListDirectory> {
+        fs::create_dir_all(root)?;
+        let mut directory = ListDirectory { root: root.to_path_buf(), paths: Vec::new() };
+        directory.refresh()?;
+        Ok(directory)
+    }
+
+    //picks up files added, renamed or removed outside the app
+    pub fn refresh(&mut self) -> io::Result<()> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|x| x == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        self.paths = paths;
+        Ok(())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    //what the sidebar calls the list, which is its file name without the extension
+    pub fn name(path: &Path) -> String {
+        path.file_stem().map(|x| x.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+
+    pub fn load(&self, path: &Path) -> io::Result<TodoList> {
+        TodoList::from_file(&path.to_path_buf())
+    }
+
+    //makes an empty list file called name, which is also the list's title
+    pub fn create(&mut self, name: &str) -> io::Result<PathBuf> {
+        let path = self.free_path(name)?;
+        TodoList::new(String::from(name.trim())).save(&path)?;
+        self.insert_path(path.clone());
+        Ok(path)
+    }
+
+    //only the file is renamed; the title inside it is left alone
+    //renaming a list to the name it already has does nothing
+    pub fn rename(&mut self, path: &Path, new_name: &str) -> io::Result<PathBuf> {
+        if !new_name.trim().is_empty() && list_file_path(&self.root, new_name) == path {
+            return Ok(path.to_path_buf());
+        }
+        let new_path = self.free_path(new_name)?;
+        fs::rename(path, &new_path)?;
+        self.paths.retain(|x| x != path);
+        self.insert_path(new_path.clone());
+        Ok(new_path)
+    }
+
+    pub fn delete(&mut self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)?;
+        self.paths.retain(|x| x != path);
+        Ok(())
+    }
+
+    //where a list called name would go, as long as nothing is there already
+    fn free_path(&self, name: &str) -> io::Result<PathBuf> {
+        if name.trim().is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "lists need a name"));
+        }
+        let path = list_file_path(&self.root, name);
+        if path.exists() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("there is already a list called \"{}\"", Self::name(&path))));
+        }
+        Ok(path)
+    }
+
+    fn insert_path(&mut self, path: PathBuf) {
+        let index = self.paths.binary_search(&path).unwrap_or_else(|x| x);
+        self.paths.insert(index, path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_list_files_without_reading_them() {
+        let dir = tempfile::tempdir().expect("indexes_list_files_without_reading_them() test panicked; temporary directory creation failed.");
+        for index in 0..300 {
+            //not valid lists, which only matters once one is opened
+            fs::write(dir.path().join(format!("list {index:03}.json")), "not read yet").unwrap();
+        }
+        fs::write(dir.path().join("notes.txt"), "not a list").unwrap();
+        fs::create_dir(dir.path().join("folder.json")).unwrap();
+
+        let directory = ListDirectory::open(dir.path()).unwrap();
+        assert_eq!(directory.paths().len(), 300);
+        assert_eq!(ListDirectory::name(&directory.paths()[0]), "list 000");
+        assert!(directory.load(&directory.paths()[0]).is_err());
+    }
+
+    #[test]
+    fn creates_renames_and_deletes_lists() {
+        let dir = tempfile::tempdir().expect("creates_renames_and_deletes_lists() test panicked; temporary directory creation failed.");
+        let root = dir.path().join("lists");
+        let mut directory = ListDirectory::open(&root).unwrap();
+        assert!(directory.paths().is_empty());
+
+        let groceries = directory.create("Groceries").unwrap();
+        let chores = directory.create("Chores").unwrap();
+        assert_eq!(directory.create("Chores").unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(directory.paths(), [chores.clone(), groceries.clone()]);
+        assert_eq!(directory.load(&groceries).unwrap().title, "Groceries");
+
+        let shopping = directory.rename(&groceries, "Shopping").unwrap();
+        assert!(!groceries.exists());
+        assert_eq!(directory.rename(&shopping, "Shopping").unwrap(), shopping);
+        assert!(shopping.exists());
+        assert_eq!(directory.rename(&shopping, "Chores").unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        directory.delete(&chores).unwrap();
+        assert_eq!(directory.paths(), [shopping]);
+
+        fs::write(root.join("Added elsewhere.json"), "{}").unwrap();
+        directory.refresh().unwrap();
+        assert_eq!(directory.paths().len(), 2);
+    }
+}
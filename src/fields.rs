@@ -0,0 +1,226 @@
+//custom fields a list can add to its items, such as a ticket number, a cost or a link
+//the list holds the schema (a FieldDef per field) and each item holds values keyed by field name
+
+use std::fmt;
+
+use ::serde::{Serialize, Deserialize};
+
+use chrono::*;
+
+pub const FIELD_DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum FieldKind {
+    Text,
+    Number,
+    Date,
+    Choice(Vec<String>), //the allowed values, in the order they're offered
+    Bool,
+    Url,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct FieldDef {
+    pub name: String,
+    pub kind: FieldKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum FieldValue {
+    Text(String),
+    Number(f64),
+    Date(NaiveDate),
+    Choice(String),
+    Bool(bool),
+    Url(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FieldError {
+    EmptyName,
+    DuplicateField(String),
+    UnknownField(String),
+    MissingItem(usize),
+    NoChoices,
+    WrongKind { field: String },
+    NotANumber(String),
+    InvalidDate(String),
+    NotAChoice { field: String, value: String },
+    InvalidUrl(String),
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::EmptyName => write!(f, "fields need a name"),
+            FieldError::DuplicateField(name) => write!(f, "there is already a field called \"{name}\""),
+            FieldError::UnknownField(name) => write!(f, "this list has no field called \"{name}\""),
+            FieldError::MissingItem(id) => write!(f, "there is no item with id {id} in this list"),
+            FieldError::NoChoices => write!(f, "a choice field needs at least one choice"),
+            FieldError::WrongKind { field } => write!(f, "that isn't the right kind of value for {field}"),
+            FieldError::NotANumber(value) => write!(f, "\"{value}\" isn't a number"),
+            FieldError::InvalidDate(value) => write!(f, "\"{value}\" isn't a date like 2024-05-22"),
+            FieldError::NotAChoice { field, value } => write!(f, "\"{value}\" isn't one of the choices for {field}"),
+            FieldError::InvalidUrl(value) => write!(f, "\"{value}\" isn't a web address starting with http:// or https://"),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+impl FieldKind {
+    //labels for picking a kind in the UI, in the same order as the variants
+    pub const LABELS: [&'static str; 6] = ["Text", "Number", "Date", "Choice", "Yes/no", "URL"];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FieldKind::Text => Self::LABELS[0],
+            FieldKind::Number => Self::LABELS[1],
+            FieldKind::Date => Self::LABELS[2],
+            FieldKind::Choice(_) => Self::LABELS[3],
+            FieldKind::Bool => Self::LABELS[4],
+            FieldKind::Url => Self::LABELS[5],
+        }
+    }
+
+    //the reverse of label; choices are only used for Choice
+    pub fn from_label(label: &str, choices: Vec<String>) -> Option<FieldKind> {
+        match label {
+            "Text" => Some(FieldKind::Text),
+            "Number" => Some(FieldKind::Number),
+            "Date" => Some(FieldKind::Date),
+            "Choice" => Some(FieldKind::Choice(choices)),
+            "Yes/no" => Some(FieldKind::Bool),
+            "URL" => Some(FieldKind::Url),
+            _ => None,
+        }
+    }
+}
+
+impl FieldDef {
+    pub fn new(name: &str, kind: FieldKind) -> FieldDef {
+        FieldDef { name: String::from(name.trim()), kind }
+    }
+
+    //reads a value typed into a text box; blank input means the field is left empty
+    //bools accept true/false, yes/no and 1/0
+    pub fn parse(&self, input: &str) -> Result<Option<FieldValue>, FieldError> {
+        let text = input.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let value = match &self.kind {
+            FieldKind::Text => FieldValue::Text(String::from(text)),
+            FieldKind::Number => FieldValue::Number(text.parse::<f64>().ok().filter(|x| x.is_finite())
+                .ok_or_else(|| FieldError::NotANumber(String::from(text)))?),
+            FieldKind::Date => FieldValue::Date(NaiveDate::parse_from_str(text, FIELD_DATE_FORMAT)
+                .map_err(|_| FieldError::InvalidDate(String::from(text)))?),
+            FieldKind::Choice(_) => FieldValue::Choice(String::from(text)),
+            FieldKind::Bool => match text.to_lowercase().as_str() {
+                "true" | "yes" | "1" => FieldValue::Bool(true),
+                "false" | "no" | "0" => FieldValue::Bool(false),
+                _ => return Err(FieldError::WrongKind { field: self.name.clone() }),
+            },
+            FieldKind::Url => FieldValue::Url(String::from(text)),
+        };
+        self.validate(&value)?;
+        Ok(Some(value))
+    }
+
+    //checks that the value is the kind this field holds, and for numbers, choices and URLs that it's an allowed value
+    pub fn validate(&self, value: &FieldValue) -> Result<(), FieldError> {
+        match (&self.kind, value) {
+            (FieldKind::Text, FieldValue::Text(_))
+            | (FieldKind::Date, FieldValue::Date(_))
+            | (FieldKind::Bool, FieldValue::Bool(_)) => Ok(()),
+            //NaN and infinity can't be saved, serde_json writes them as null
+            (FieldKind::Number, FieldValue::Number(number)) => {
+                if number.is_finite() {
+                    Ok(())
+                } else {
+                    Err(FieldError::NotANumber(number.to_string()))
+                }
+            }
+            (FieldKind::Choice(choices), FieldValue::Choice(choice)) => {
+                if choices.contains(choice) {
+                    Ok(())
+                } else {
+                    Err(FieldError::NotAChoice { field: self.name.clone(), value: choice.clone() })
+                }
+            }
+            (FieldKind::Url, FieldValue::Url(url)) => {
+                let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"));
+                if rest.is_some_and(|x| !x.is_empty() && !x.contains(char::is_whitespace)) {
+                    Ok(())
+                } else {
+                    Err(FieldError::InvalidUrl(url.clone()))
+                }
+            }
+            _ => Err(FieldError::WrongKind { field: self.name.clone() }),
+        }
+    }
+}
+
+//written so that FieldDef::parse reads it back
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Text(text) | FieldValue::Choice(text) | FieldValue::Url(text) => write!(f, "{text}"),
+            FieldValue::Number(number) => write!(f, "{number}"),
+            FieldValue::Date(date) => write!(f, "{}", date.format(FIELD_DATE_FORMAT)),
+            FieldValue::Bool(value) => write!(f, "{}", if *value { "yes" } else { "no" }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_each_kind() {
+        let number = FieldDef::new("Cost", FieldKind::Number);
+        assert_eq!(number.parse(" 12.5 "), Ok(Some(FieldValue::Number(12.5))));
+        assert_eq!(number.parse("twelve"), Err(FieldError::NotANumber(String::from("twelve"))));
+        assert_eq!(number.parse(""), Ok(None));
+
+        let date = FieldDef::new("Ordered", FieldKind::Date);
+        assert_eq!(date.parse("2024-05-22"), Ok(Some(FieldValue::Date(NaiveDate::from_ymd_opt(2024, 5, 22).unwrap()))));
+        assert!(date.parse("22/05/2024").is_err());
+
+        let done = FieldDef::new("Billed", FieldKind::Bool);
+        assert_eq!(done.parse("Yes"), Ok(Some(FieldValue::Bool(true))));
+        assert!(done.parse("maybe").is_err());
+    }
+
+    #[test]
+    fn validates_choices_and_urls() {
+        let size = FieldDef::new("Size", FieldKind::Choice(vec![String::from("S"), String::from("L")]));
+        assert!(size.parse("S").is_ok());
+        assert_eq!(size.parse("M"), Err(FieldError::NotAChoice { field: String::from("Size"), value: String::from("M") }));
+
+        let link = FieldDef::new("Link", FieldKind::Url);
+        assert!(link.parse("https://example.com/ticket/1").is_ok());
+        assert!(link.parse("example.com").is_err());
+        assert!(link.parse("http://").is_err());
+        assert!(link.validate(&FieldValue::Text(String::from("https://example.com"))).is_err());
+
+        let cost = FieldDef::new("Cost", FieldKind::Number);
+        assert!(cost.validate(&FieldValue::Number(-2.5)).is_ok());
+        assert_eq!(cost.validate(&FieldValue::Number(f64::NAN)), Err(FieldError::NotANumber(String::from("NaN"))));
+        assert!(cost.validate(&FieldValue::Number(f64::INFINITY)).is_err());
+        assert!(cost.parse("inf").is_err());
+    }
+
+    #[test]
+    fn values_display_as_parseable_text() {
+        let fields = [
+            (FieldDef::new("Cost", FieldKind::Number), FieldValue::Number(3.0)),
+            (FieldDef::new("Billed", FieldKind::Bool), FieldValue::Bool(false)),
+            (FieldDef::new("Ordered", FieldKind::Date), FieldValue::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())),
+        ];
+        for (def, value) in fields {
+            assert_eq!(def.parse(&value.to_string()), Ok(Some(value)));
+        }
+    }
+}
@@ -0,0 +1,688 @@
+//undo/redo for changes to a TodoList
+//every change is a Command, and applying a Command hands back the Command that reverses it,
+//so undoing and redoing are both just applying whatever is on top of the right stack
+
+use std::collections::HashMap;
+use std::mem;
+use std::path::PathBuf;
+
+use chrono::*;
+
+use crate::{CompletionEvent, DetachedItem, TodoList, TodoListItem};
+use crate::board::BoardError;
+use crate::fields::{FieldDef, FieldValue};
+use crate::kinds::ListKind;
+use crate::statuses::{StatusChange, StatusDef};
+
+const HISTORY_LIMIT: usize = 200;
+
+#[derive(Clone)]
+pub enum Command {
+    Insert(DetachedItem), //puts an item (new or removed earlier) into the list at its position
+    Remove { id: usize }, //removes for good, see Trash for the usual way of deleting
+    Trash { id: usize, at: DateTime<Local> },
+    Restore { id: usize }, //takes an item back out of the trash
+    Archive { id: usize, at: DateTime<Local> },
+    Unarchive { id: usize },
+    ChangeName { id: usize, name: String },
+    ChangeDescription { id: usize, description: String },
+    ChangeDue { id: usize, due: Option<DateTime<Local>> },
+    UpdateItem(TodoListItem), //replaces every field of the item with the same id, except its subtasks
+    SetCompleted { id: usize, completed: bool, at: DateTime<Local> },
+    SetStatus { id: usize, status: String, at: DateTime<Local> }, //to a status of the other category it completes or reopens too
+    Rename { title: String },
+    Clear { at: DateTime<Local> }, //moves every item to the trash
+    RenameField { name: String, new_name: String },
+    RemoveField { name: String }, //along with every item's value for it
+    RestoreField(RemovedField),
+    RenameStatus { name: String, new_name: String },
+    MoveStatus { name: String, index: usize },
+    RemoveStatus { name: String, at: DateTime<Local> }, //items in it go to the default status of its category
+    RestoreStatus(RemovedStatus),
+    RestoreCompletion(Vec<CompletionState>), //what SetCompleted and SetStatus hand back, see CompletionState
+    InFile { path: PathBuf, command: Box<Command> }, //applies the command to the list saved at path instead, and saves it
+    Batch(Vec<Command>),
+}
+
+//the parts of an item that completing or reopening it can change, so undoing that leaves the rest of the item (its
+//dependencies, a daily list's carried days, edits made since) alone
+//dates are only kept when the change moved them, which is when a recurring item went on to its next occurrence
+#[derive(Clone)]
+pub struct CompletionState {
+    id: usize,
+    completed: bool,
+    completed_at: Option<DateTime<Local>>,
+    status: Option<String>,
+    completion_history: Vec<DateTime<Local>>,
+    skipped_occurrences: u32,
+    completion_log: Vec<CompletionEvent>,
+    status_log: Vec<StatusChange>,
+    dates: Option<ItemDates>,
+}
+
+type ItemDates = (Option<DateTime<Local>>, Option<DateTime<Local>>); //(due, start)
+
+//a custom field taken out of a list, with every item's value for it, so it can be put back as it was
+#[derive(Clone)]
+pub struct RemovedField {
+    field: FieldDef,
+    index: usize,
+    values: Vec<(usize, FieldValue)>, //(item, value), trashed and archived items included
+}
+
+//a status taken out of a list, with its place among the statuses and on the board and the items that were in it
+#[derive(Clone)]
+pub struct RemovedStatus {
+    status: StatusDef,
+    index: usize,
+    column: Option<usize>, //its place among a kanban list's columns, if it was one
+    wip_limit: Option<usize>,
+    collapsed: bool,
+    items: Vec<usize>, //trashed and archived items included
+    at: DateTime<Local>, //when it was removed, which is when the items' moves out of it were logged
+}
+
+impl RemovedField {
+    fn of(list: &TodoList, name: &str) -> Option<RemovedField> {
+        let index = list.fields.iter().position(|x| x.name == name)?;
+        let values = list.all_stored_items().into_iter()
+            .filter_map(|x| Some((x.id, x.field_values.get(name)?.clone())))
+            .collect();
+        Some(RemovedField { field: list.fields[index].clone(), index, values })
+    }
+}
+
+impl RemovedStatus {
+    fn of(list: &TodoList, name: &str, at: DateTime<Local>) -> Option<RemovedStatus> {
+        let index = list.statuses.iter().position(|x| x.name == name)?;
+        let items = list.all_stored_items().into_iter()
+            .filter(|x| x.status.as_deref() == Some(name))
+            .map(|x| x.id)
+            .collect();
+        Some(RemovedStatus {
+            status: list.statuses[index].clone(),
+            index,
+            column: list.columns().iter().position(|x| x == name),
+            wip_limit: list.board.wip_limit(name),
+            collapsed: list.board.is_collapsed(name),
+            items,
+            at,
+        })
+    }
+}
+
+impl Command {
+    //appends a new top-level item, or a subtask of parent_id
+    pub fn add(list: &mut TodoList, parent_id: Option<usize>, name: String, description: String) -> Command {
+        Command::Insert(DetachedItem::new(list.new_item(name, description), parent_id))
+    }
+
+    //copies the items, with their subtasks, to the end of destination with fresh ids there; subtasks of picked items
+    //come along with them rather than being copied twice
+    pub fn copy_items(source: &TodoList, ids: &[usize], destination: &mut TodoList) -> Command {
+        let items = source.transfer_roots(ids).into_iter()
+            .filter_map(|id| source.get_item_by_id(id).cloned())
+            .collect();
+        let commands = destination.adopt_items(items).into_iter()
+            .map(|item| Command::Insert(DetachedItem::new(item, None)))
+            .collect();
+        Command::Batch(commands)
+    }
+
+    //takes the items out of source once they've been copied somewhere else with copy_items, which makes a move
+    pub fn remove_items(source: &TodoList, ids: &[usize]) -> Command {
+        Command::Batch(source.transfer_roots(ids).into_iter().map(|id| Command::Remove { id }).collect())
+    }
+
+    //moves a card on the board view to another column; every column is a status (a kanban list's columns are some of
+    //its statuses), so this is the same SetStatus the status menu makes
+    //a column that's at its WIP limit doesn't take any more cards
+    pub fn move_to_board_column(list: &TodoList, id: usize, column: &str, at: DateTime<Local>) -> Result<Command, BoardError> {
+        if !list.board_columns().contains(&column) {
+            return Err(BoardError::UnknownColumn(String::from(column)));
+        }
+        let item = list.get_item_by_id(id).ok_or(BoardError::MissingItem(id))?;
+        if let Some(limit) = list.board().wip_limit(column) {
+            if list.board_column_of(item) != Some(column) && list.board_cards(column).len() >= limit {
+                return Err(BoardError::ColumnFull { column: String::from(column), limit });
+            }
+        }
+        Ok(Command::SetStatus { id, status: String::from(column), at })
+    }
+
+    //makes the change and returns the command that undoes it, or None if nothing could be changed
+    //(usually because the item it refers to isn't in the list)
+    pub fn apply(self, list: &mut TodoList) -> Option<Command> {
+        match self {
+            Command::Insert(detached) => {
+                let id = detached.item.id;
+                list.attach_item(detached).then_some(Command::Remove { id })
+            }
+            Command::Remove { id } => list.detach_item(id).map(Command::Insert),
+            Command::Trash { id, at } => list.trash_item(id, at).then_some(Command::Restore { id }),
+            Command::Restore { id } => {
+                let at = list.trash().iter().find(|x| x.item().id == id)?.deleted_at;
+                list.restore_from_trash(id).then_some(Command::Trash { id, at })
+            }
+            Command::Archive { id, at } => list.archive_item(id, at).then_some(Command::Unarchive { id }),
+            Command::Unarchive { id } => {
+                let at = list.archive().iter().find(|x| x.item().id == id)?.archived_at;
+                list.unarchive(id).then_some(Command::Archive { id, at })
+            }
+            Command::ChangeName { id, name } => {
+                let item = list.get_item_mut_by_id(id)?;
+                let old = mem::replace(&mut item.name, name);
+                Some(Command::ChangeName { id, name: old })
+            }
+            Command::ChangeDescription { id, description } => {
+                let item = list.get_item_mut_by_id(id)?;
+                let old = mem::replace(&mut item.description, description);
+                Some(Command::ChangeDescription { id, description: old })
+            }
+            Command::ChangeDue { id, due } => {
+                let item = list.get_item_mut_by_id(id)?;
+                let old = mem::replace(&mut item.due, due);
+                Some(Command::ChangeDue { id, due: old })
+            }
+            Command::UpdateItem(mut new) => {
+                let item = list.get_item_mut_by_id(new.id)?;
+                new.subtasks = mem::take(&mut item.subtasks);
+                //old is left with no subtasks, which is fine since they're ignored when it's applied
+                let old = mem::replace(item, new);
+                Some(Command::UpdateItem(old))
+            }
+            Command::SetCompleted { id, completed, at } => {
+                let before = completion_snapshot(list, id)?;
+                list.set_completed_at(id, completed, at);
+                Some(Command::RestoreCompletion(keep_moved_dates(list, before)))
+            }
+            Command::SetStatus { id, status, at } => {
+                let before = completion_snapshot(list, id)?;
+                list.set_status(id, &status, at).ok()?;
+                Some(Command::RestoreCompletion(keep_moved_dates(list, before)))
+            }
+            Command::RestoreCompletion(states) => {
+                let replaced: Vec<CompletionState> = states.into_iter().filter_map(|x| x.restore(list)).collect();
+                if replaced.is_empty() {
+                    return None;
+                }
+                Some(Command::RestoreCompletion(replaced))
+            }
+            Command::Rename { title } => {
+                let old = mem::replace(&mut list.title, title);
+                Some(Command::Rename { title: old })
+            }
+            Command::Clear { at } => {
+                let commands = list.items.iter().map(|x| Command::Trash { id: x.id, at }).collect();
+                Command::Batch(commands).apply(list)
+            }
+            Command::RenameField { name, new_name } => {
+                list.rename_field(&name, &new_name).ok()?;
+                Some(Command::RenameField { name: String::from(new_name.trim()), new_name: name })
+            }
+            Command::RemoveField { name } => {
+                let removed = RemovedField::of(list, &name)?;
+                list.remove_field(&name);
+                Some(Command::RestoreField(removed))
+            }
+            Command::RestoreField(removed) => {
+                let name = removed.field.name.clone();
+                if list.field(&name).is_some() {
+                    return None;
+                }
+                list.fields.insert(removed.index.min(list.fields.len()), removed.field);
+                let values: HashMap<usize, FieldValue> = removed.values.into_iter().collect();
+                list.for_each_stored_item_mut(&mut |item| {
+                    if let Some(value) = values.get(&item.id) {
+                        item.field_values.insert(name.clone(), value.clone());
+                    }
+                });
+                Some(Command::RemoveField { name })
+            }
+            Command::RenameStatus { name, new_name } => {
+                list.rename_status(&name, &new_name).ok()?;
+                Some(Command::RenameStatus { name: String::from(new_name.trim()), new_name: name })
+            }
+            Command::MoveStatus { name, index } => {
+                let from = list.statuses.iter().position(|x| x.name == name)?;
+                list.move_status(&name, index);
+                Some(Command::MoveStatus { name, index: from })
+            }
+            Command::RemoveStatus { name, at } => {
+                let removed = RemovedStatus::of(list, &name, at)?;
+                list.remove_status(&name, at).ok()?;
+                Some(Command::RestoreStatus(removed))
+            }
+            Command::RestoreStatus(removed) => {
+                let name = removed.status.name.clone();
+                if list.status(&name).is_some() {
+                    return None;
+                }
+                let done = removed.status.done;
+                list.statuses.insert(removed.index.min(list.statuses.len()), removed.status);
+                if let (Some(column), ListKind::Kanban { columns }) = (removed.column, &mut list.kind) {
+                    columns.insert(column.min(columns.len()), name.clone());
+                }
+                list.board.set_wip_limit(&name, removed.wip_limit);
+                list.board.set_collapsed(&name, removed.collapsed);
+                //items go back unless they've been completed or reopened since, and the move remove_status logged
+                //is taken back off their log
+                list.for_each_stored_item_mut(&mut |item| {
+                    if removed.items.contains(&item.id) && item.completed == done {
+                        item.status = Some(name.clone());
+                        if item.status_log.last().is_some_and(|x| x.from == name) {
+                            item.status_log.pop();
+                        }
+                    }
+                });
+                Some(Command::RemoveStatus { name, at: removed.at })
+            }
+            Command::InFile { path, command } => {
+                let mut other = TodoList::from_file(&path).ok()?;
+                let inverse = command.apply(&mut other)?;
+                other.save(&path).ok()?;
+                Some(Command::InFile { path, command: Box::new(inverse) })
+            }
+            Command::Batch(commands) => {
+                //undoing has to happen in the opposite order
+                let mut inverses: Vec<Command> = commands.into_iter().filter_map(|x| x.apply(list)).collect();
+                if inverses.is_empty() {
+                    return None;
+                }
+                inverses.reverse();
+                Some(Command::Batch(inverses))
+            }
+        }
+    }
+}
+
+impl CompletionState {
+    fn of(item: &TodoListItem) -> CompletionState {
+        CompletionState {
+            id: item.id,
+            completed: item.completed,
+            completed_at: item.completed_at,
+            status: item.status.clone(),
+            completion_history: item.completion_history.clone(),
+            skipped_occurrences: item.skipped_occurrences,
+            completion_log: item.completion_log.clone(),
+            status_log: item.status_log.clone(),
+            dates: Some((item.due, item.start)),
+        }
+    }
+
+    //puts the state back on its item, and returns the state it replaced so it can be put back in turn
+    //completed and status are always taken together, so they still match afterwards
+    fn restore(self, list: &mut TodoList) -> Option<CompletionState> {
+        let item = list.get_item_mut_by_id(self.id)?;
+        Some(CompletionState {
+            id: self.id,
+            completed: mem::replace(&mut item.completed, self.completed),
+            completed_at: mem::replace(&mut item.completed_at, self.completed_at),
+            status: mem::replace(&mut item.status, self.status),
+            completion_history: mem::replace(&mut item.completion_history, self.completion_history),
+            skipped_occurrences: mem::replace(&mut item.skipped_occurrences, self.skipped_occurrences),
+            completion_log: mem::replace(&mut item.completion_log, self.completion_log),
+            status_log: mem::replace(&mut item.status_log, self.status_log),
+            dates: self.dates.map(|(due, start)| (mem::replace(&mut item.due, due), mem::replace(&mut item.start, start))),
+        })
+    }
+}
+
+//completing can also change the item's subtasks (recurring items reopen them) and its parents (auto_complete_parents),
+//so all of those are saved to be put back on undo
+fn completion_snapshot(list: &TodoList, id: usize) -> Option<Vec<CompletionState>> {
+    let item = list.get_item_by_id(id)?;
+    let mut affected: Vec<usize> = Vec::new();
+    let mut subtree = Vec::new();
+    item.collect_with_subtasks(&mut subtree);
+    affected.extend(subtree.iter().map(|x| x.id));
+    let mut child_id = id;
+    while let Some(parent_id) = list.parent_id(child_id) {
+        affected.push(parent_id);
+        child_id = parent_id;
+    }
+
+    Some(affected.iter()
+        .filter_map(|x| list.get_item_by_id(*x))
+        .map(CompletionState::of)
+        .collect())
+}
+
+//drops the dates of every state whose item still has them, so undoing doesn't put back dates changed since by
+//something else
+fn keep_moved_dates(list: &TodoList, mut states: Vec<CompletionState>) -> Vec<CompletionState> {
+    for state in &mut states {
+        if let Some(item) = list.get_item_by_id(state.id) {
+            if state.dates == Some((item.due, item.start)) {
+                state.dates = None;
+            }
+        }
+    }
+    states
+}
+
+#[derive(Default)]
+pub struct History {
+    undo_stack: Vec<Command>,
+    redo_stack: Vec<Command>,
+    changed: bool, //something was applied, undone or redone since take_changed was last called
+}
+
+impl History {
+    //returns false if the command didn't change anything, in which case it isn't recorded
+    pub fn apply(&mut self, list: &mut TodoList, command: Command) -> bool {
+        match command.apply(list) {
+            Some(inverse) => {
+                self.changed = true;
+                self.undo_stack.push(inverse);
+                if self.undo_stack.len() > HISTORY_LIMIT {
+                    self.undo_stack.remove(0);
+                }
+                self.redo_stack.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn undo(&mut self, list: &mut TodoList) -> bool {
+        let undone = Self::swap(&mut self.undo_stack, &mut self.redo_stack, list);
+        self.changed |= undone;
+        undone
+    }
+
+    pub fn redo(&mut self, list: &mut TodoList) -> bool {
+        let redone = Self::swap(&mut self.redo_stack, &mut self.undo_stack, list);
+        self.changed |= redone;
+        redone
+    }
+
+    //whether the list has been changed through this history since the last call, for tracking unsaved changes
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    //applies the top of one stack and pushes its inverse onto the other
+    fn swap(from: &mut Vec<Command>, to: &mut Vec<Command>, list: &mut TodoList) -> bool {
+        //commands whose items have since disappeared some other way are skipped
+        while let Some(command) = from.pop() {
+            if let Some(inverse) = command.apply(list) {
+                to.push(inverse);
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    //for changes made straight on the list rather than through a Command, usually so the list's own error can be shown;
+    //undo is the command that puts things back as they were
+    pub fn record(&mut self, undo: Command) {
+        self.changed = true;
+        self.undo_stack.push(undo);
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    //call this when switching to a different list, since commands refer to items by id
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fields::FieldKind;
+    use crate::statuses;
+
+    fn make_list() -> (TodoList, History) {
+        let mut list = TodoList::new(String::from("test list"));
+        list.add(String::from("First"), String::new());
+        list.add(String::from("Second"), String::new());
+        (list, History::default())
+    }
+
+    fn names(list: &TodoList) -> Vec<String> {
+        list.items.iter().map(|x| x.name.clone()).collect()
+    }
+
+    #[test]
+    fn undoes_and_redoes_adding() {
+        let (mut list, mut history) = make_list();
+        let command = Command::add(&mut list, None, String::from("Third"), String::new());
+        history.apply(&mut list, command);
+        assert_eq!(list.items.len(), 3);
+
+        assert!(history.undo(&mut list));
+        assert_eq!(list.items.len(), 2);
+        assert!(history.redo(&mut list));
+        assert_eq!(names(&list), vec!["First", "Second", "Third"]);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn undoing_a_removal_restores_position_subtasks_and_blockers() {
+        let (mut list, mut history) = make_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        list.add_dependency(1, child).unwrap();
+
+        history.apply(&mut list, Command::Remove { id: 0 });
+        assert_eq!(names(&list), vec!["Second"]);
+        assert!(list.get_item_by_id(1).unwrap().blocked_by().is_empty());
+
+        history.undo(&mut list);
+        assert_eq!(names(&list), vec!["First", "Second"]);
+        assert_eq!(list.parent_id(child), Some(0));
+        assert!(list.is_blocked(1));
+    }
+
+    #[test]
+    fn undoes_renames_and_edits() {
+        let (mut list, mut history) = make_list();
+        history.apply(&mut list, Command::Rename { title: String::from("Renamed") });
+        history.apply(&mut list, Command::ChangeName { id: 1, name: String::from("Changed") });
+        history.apply(&mut list, Command::ChangeDescription { id: 1, description: String::from("Details") });
+
+        history.undo(&mut list);
+        assert_eq!(list.items[1].description, String::new());
+        history.undo(&mut list);
+        assert_eq!(list.items[1].name, String::from("Second"));
+        history.undo(&mut list);
+        assert_eq!(list.title, String::from("test list"));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn undoing_an_update_keeps_subtasks() {
+        let (mut list, mut history) = make_list();
+        let mut edited = list.items[0].clone();
+        edited.name = String::from("Edited");
+        history.apply(&mut list, Command::UpdateItem(edited));
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+
+        history.undo(&mut list);
+        assert_eq!(list.items[0].name, String::from("First"));
+        assert_eq!(list.parent_id(child), Some(0));
+    }
+
+    #[test]
+    fn undoes_completion_including_auto_completed_parents() {
+        let (mut list, mut history) = make_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        list.auto_complete_parents = true;
+
+        history.apply(&mut list, Command::SetCompleted { id: child, completed: true, at: Local::now() });
+        assert!(list.items[0].completed);
+
+        history.undo(&mut list);
+        assert!(!list.items[0].completed);
+        assert!(!list.get_item_by_id(child).unwrap().completed);
+        assert!(list.items[0].completed_at().is_none());
+
+        history.redo(&mut list);
+        assert!(list.items[0].completed);
+    }
+
+    #[test]
+    fn undoing_completion_keeps_changes_made_since() {
+        let (mut list, mut history) = make_list();
+        let now = Local::now();
+        let due = now - Duration::hours(1);
+        list.items[0].due = Some(due);
+        list.items[0].recurrence = Some(crate::Recurrence::daily());
+
+        history.apply(&mut list, Command::SetCompleted { id: 0, completed: true, at: now });
+        history.apply(&mut list, Command::SetCompleted { id: 1, completed: true, at: now });
+        let next_due = list.items[0].due;
+        assert_ne!(next_due, Some(due));
+        //neither of these goes through history
+        list.add_dependency(0, 1).unwrap();
+        list.items[1].due = Some(due);
+        list.items[1].carried_days = 2;
+
+        history.undo(&mut list);
+        history.undo(&mut list);
+        assert_eq!(list.items[0].due, Some(due));
+        assert!(list.items[0].completion_history.is_empty());
+        assert!(list.items[0].blocked_by().contains(&1));
+        assert!(!list.items[1].completed);
+        assert_eq!(list.items[1].due, Some(due));
+        assert_eq!(list.items[1].carried_days(), 2);
+
+        history.redo(&mut list);
+        assert_eq!(list.items[0].due, next_due);
+        assert_eq!(list.items[0].completion_history.len(), 1);
+        assert!(list.items[0].blocked_by().contains(&1));
+    }
+
+    #[test]
+    fn undoes_status_changes() {
+        let (mut list, mut history) = make_list();
+        history.apply(&mut list, Command::SetStatus { id: 0, status: String::from("Won't do"), at: Local::now() });
+        assert!(list.items[0].completed);
+        assert!(!history.apply(&mut list, Command::SetStatus { id: 0, status: String::from("Nope"), at: Local::now() }));
+
+        history.undo(&mut list);
+        assert!(!list.items[0].completed);
+        assert_eq!(list.status_of(&list.items[0]).unwrap().name, "To do");
+        assert!(list.items[0].status_log().is_empty());
+    }
+
+    #[test]
+    fn undoes_clearing_the_list() {
+        let (mut list, mut history) = make_list();
+        history.apply(&mut list, Command::Clear { at: Local::now() });
+        assert!(list.items.is_empty());
+        assert_eq!(list.trash().len(), 2);
+
+        history.undo(&mut list);
+        assert_eq!(names(&list), vec!["First", "Second"]);
+    }
+
+    #[test]
+    fn commands_on_missing_items_are_not_recorded() {
+        let (mut list, mut history) = make_list();
+        assert!(!history.apply(&mut list, Command::Remove { id: 99 }));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn undoes_trashing() {
+        let (mut list, mut history) = make_list();
+        history.apply(&mut list, Command::Trash { id: 0, at: Local::now() });
+        assert_eq!(list.trash().len(), 1);
+
+        history.undo(&mut list);
+        assert_eq!(names(&list), vec!["First", "Second"]);
+        assert!(list.trash().is_empty());
+
+        history.redo(&mut list);
+        assert_eq!(names(&list), vec!["Second"]);
+        assert_eq!(list.trash().len(), 1);
+    }
+
+    #[test]
+    fn undoes_archiving() {
+        let (mut list, mut history) = make_list();
+        list.set_completed(1, true);
+        history.apply(&mut list, Command::Archive { id: 1, at: Local::now() });
+        assert_eq!(names(&list), vec!["First"]);
+
+        history.undo(&mut list);
+        assert_eq!(names(&list), vec!["First", "Second"]);
+        assert!(list.archive().is_empty());
+    }
+
+    #[test]
+    fn undoes_field_changes_with_their_values() {
+        let (mut list, mut history) = make_list();
+        list.add_field(FieldDef::new("Cost", FieldKind::Number)).unwrap();
+        list.add_field(FieldDef::new("Ticket", FieldKind::Text)).unwrap();
+        list.set_field_value(0, "Cost", Some(FieldValue::Number(5.0))).unwrap();
+        list.trash_item(0, Local::now());
+
+        history.apply(&mut list, Command::RenameField { name: String::from("Cost"), new_name: String::from(" Price ") });
+        history.apply(&mut list, Command::RemoveField { name: String::from("Price") });
+        assert_eq!(list.fields().len(), 1);
+
+        history.undo(&mut list);
+        history.undo(&mut list);
+        let field_names: Vec<&str> = list.fields().iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(field_names, vec!["Cost", "Ticket"]);
+        list.restore_from_trash(0);
+        assert_eq!(list.items[0].field_value("Cost"), Some(&FieldValue::Number(5.0)));
+    }
+
+    #[test]
+    fn undoes_status_removal_moves_and_renames() {
+        let (mut list, mut history) = make_list();
+        let now = Local::now();
+        list.set_kind(ListKind::Kanban { columns: vec![String::from("To do"), String::from("Waiting"), String::from("Done")] }, now);
+        list.board_mut().set_wip_limit("Waiting", Some(2));
+        list.set_status(1, "Waiting", now).unwrap();
+
+        history.apply(&mut list, Command::MoveStatus { name: String::from("Done"), index: 0 });
+        history.apply(&mut list, Command::RenameStatus { name: String::from("Waiting"), new_name: String::from("Blocked") });
+        history.apply(&mut list, Command::RemoveStatus { name: String::from("Blocked"), at: now });
+        assert_eq!(list.status_of(&list.items[1]).unwrap().name, "To do");
+        assert_eq!(list.columns().len(), 2);
+
+        history.undo(&mut list);
+        history.undo(&mut list);
+        history.undo(&mut list);
+        assert_eq!(list.statuses(), statuses::default_statuses().as_slice());
+        assert_eq!(list.columns(), ["To do", "Waiting", "Done"]);
+        assert_eq!(list.board().wip_limit("Waiting"), Some(2));
+        assert_eq!(list.status_of(&list.items[1]).unwrap().name, "Waiting");
+        assert_eq!(list.items[1].status_log().len(), 1);
+
+        history.redo(&mut list);
+        history.redo(&mut list);
+        history.redo(&mut list);
+        assert!(list.status("Blocked").is_none());
+        assert_eq!(list.statuses()[0].name, "Done");
+    }
+
+    #[test]
+    fn reports_changes_once() {
+        let (mut list, mut history) = make_list();
+        assert!(!history.take_changed());
+        history.apply(&mut list, Command::Remove { id: 0 });
+        assert!(history.take_changed());
+        assert!(!history.take_changed());
+        history.undo(&mut list);
+        assert!(history.take_changed());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,203 @@
+//what sort of list a TodoList is, which changes how it behaves and how it's drawn
+//    Standard    an ordinary list
+//    Checklist   a one-shot list that archives itself once every item is done (TodoList::archived_at)
+//    Daily       each day at reset_at, done items are unticked and the rest roll over to the new day
+//    Kanban      items move through columns instead of being ticked off; each column is one of the list's statuses
+//    Habit       things to do every day: each day at reset_at everything is unticked, and each item keeps a streak
+
+use std::collections::{BTreeMap, HashSet};
+
+use ::serde::{Serialize, Deserialize};
+
+use chrono::*;
+
+use crate::{CompletionChange, CompletionEvent};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub enum ListKind {
+    #[default]
+    Standard,
+    Checklist,
+    Daily { reset_at: NaiveTime },
+    Kanban { columns: Vec<String> }, //in order, at least MIN_COLUMNS of them
+    Habit { reset_at: NaiveTime },
+}
+
+pub const RESET_TIME_FORMAT: &str = "%H:%M";
+
+//somewhere for open items and somewhere for finished ones
+pub const MIN_COLUMNS: usize = 2;
+
+pub fn default_reset_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(4, 0, 0).unwrap()
+}
+
+pub fn default_columns() -> Vec<String> {
+    vec![String::from("To do"), String::from("In progress"), String::from("Done")]
+}
+
+impl ListKind {
+    //labels for picking a kind in the UI, in the same order as the variants
+    pub const LABELS: [&'static str; 5] = ["Standard", "Checklist", "Daily", "Kanban", "Habit"];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ListKind::Standard => Self::LABELS[0],
+            ListKind::Checklist => Self::LABELS[1],
+            ListKind::Daily { .. } => Self::LABELS[2],
+            ListKind::Kanban { .. } => Self::LABELS[3],
+            ListKind::Habit { .. } => Self::LABELS[4],
+        }
+    }
+
+    //the reverse of label, with the default reset time and columns
+    pub fn from_label(label: &str) -> Option<ListKind> {
+        match label {
+            "Standard" => Some(ListKind::Standard),
+            "Checklist" => Some(ListKind::Checklist),
+            "Daily" => Some(ListKind::Daily { reset_at: default_reset_time() }),
+            "Kanban" => Some(ListKind::Kanban { columns: default_columns() }),
+            "Habit" => Some(ListKind::Habit { reset_at: default_reset_time() }),
+            _ => None,
+        }
+    }
+
+    //when a new day starts, for the kinds that have days
+    pub fn reset_time(&self) -> Option<NaiveTime> {
+        match self {
+            ListKind::Daily { reset_at } | ListKind::Habit { reset_at } => Some(*reset_at),
+            _ => None,
+        }
+    }
+
+    //the same kind of list with a different reset time; kinds without one are left alone
+    pub fn with_reset_time(&self, reset_at: NaiveTime) -> ListKind {
+        match self {
+            ListKind::Daily { .. } => ListKind::Daily { reset_at },
+            ListKind::Habit { .. } => ListKind::Habit { reset_at },
+            other => other.clone(),
+        }
+    }
+
+    //fixes what a hand-edited file can get wrong, which is a kanban list with blank or repeated columns or fewer than
+    //MIN_COLUMNS of them, in which case it gets the default columns; returns true if anything changed
+    pub fn repair(&mut self) -> bool {
+        let ListKind::Kanban { columns } = self else {
+            return false;
+        };
+        let before = columns.len();
+        let mut seen = HashSet::new();
+        columns.retain(|x| !x.trim().is_empty() && seen.insert(x.clone()));
+        if columns.len() < MIN_COLUMNS {
+            *columns = default_columns();
+            return true;
+        }
+        columns.len() != before
+    }
+}
+
+//the latest time the list should have been reset by, which is today's reset time if that's passed, else yesterday's
+pub fn last_reset_time(reset_at: NaiveTime, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let today = now.date_naive();
+    let date = if now.time() >= reset_at { today } else { today.pred_opt()? };
+    Local.from_local_datetime(&date.and_time(reset_at)).earliest()
+}
+
+//where a due date left behind by a daily list's reset goes: the same time of day on the new day, which runs from
+//reset_time up to the next reset, so times before the reset time land on the date after
+pub fn carry_due_date(due: DateTime<Local>, reset_time: DateTime<Local>) -> DateTime<Local> {
+    let date = reset_time.date_naive();
+    let date = if due.time() < reset_time.time() { date.succ_opt().unwrap_or(date) } else { date };
+    Local.from_local_datetime(&date.and_time(due.time())).earliest().unwrap_or(reset_time)
+}
+
+//how many days in a row an item was done, going by its completion log; a day counts if the item was ticked when it ended
+//today only counts once it's done, and until then it doesn't break the streak
+pub fn streak(log: &[CompletionEvent], reset_at: NaiveTime, now: DateTime<Local>) -> usize {
+    let mut done_on: BTreeMap<NaiveDate, bool> = BTreeMap::new();
+    for event in log {
+        if let Some(day) = last_reset_time(reset_at, event.at) {
+            done_on.insert(day.date_naive(), event.change == CompletionChange::Completed);
+        }
+    }
+    let Some(today) = last_reset_time(reset_at, now).map(|x| x.date_naive()) else {
+        return 0;
+    };
+    let mut day = if done_on.get(&today) == Some(&true) { Some(today) } else { today.pred_opt() };
+    let mut streak = 0;
+    while let Some(date) = day.filter(|x| done_on.get(x) == Some(&true)) {
+        streak += 1;
+        day = date.pred_opt();
+    }
+    streak
+}
+
+//comma separated column names, with blanks and repeats dropped; None if that leaves fewer than MIN_COLUMNS
+pub fn parse_columns(input: &str) -> Option<Vec<String>> {
+    let mut columns: Vec<String> = Vec::new();
+    for column in input.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+        if !columns.iter().any(|x| x == column) {
+            columns.push(String::from(column));
+        }
+    }
+    (columns.len() >= MIN_COLUMNS).then_some(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_last_reset_time() {
+        let reset_at = default_reset_time();
+        let before = Local.with_ymd_and_hms(2024, 5, 22, 3, 0, 0).unwrap();
+        let after = Local.with_ymd_and_hms(2024, 5, 22, 9, 0, 0).unwrap();
+        assert_eq!(last_reset_time(reset_at, before), Local.with_ymd_and_hms(2024, 5, 21, 4, 0, 0).single());
+        assert_eq!(last_reset_time(reset_at, after), Local.with_ymd_and_hms(2024, 5, 22, 4, 0, 0).single());
+    }
+
+    #[test]
+    fn counts_streaks_of_days_done() {
+        let reset_at = default_reset_time();
+        let at = |day, hour| Local.with_ymd_and_hms(2024, 5, day, hour, 0, 0).unwrap();
+        let event = |change, at| CompletionEvent { change, at };
+        let log = vec![
+            event(CompletionChange::Completed, at(19, 20)),
+            //ticked again after the 20th's reset at 4:00, then unticked, so the 20th is missed
+            event(CompletionChange::Completed, at(20, 9)),
+            event(CompletionChange::Reopened, at(20, 10)),
+            event(CompletionChange::Completed, at(21, 9)),
+            //still the 21st, since the day only ends at 4:00
+            event(CompletionChange::Reopened, at(22, 4)),
+            event(CompletionChange::Completed, at(22, 8)),
+        ];
+        assert_eq!(streak(&log, reset_at, at(22, 12)), 2);
+        //the 23rd isn't done yet, which doesn't break the streak until it's over
+        assert_eq!(streak(&log, reset_at, at(23, 12)), 2);
+        assert_eq!(streak(&log, reset_at, at(24, 12)), 0);
+        assert_eq!(streak(&[], reset_at, at(22, 12)), 0);
+    }
+
+    #[test]
+    fn repairs_kanban_columns() {
+        let mut kind = ListKind::Kanban { columns: Vec::new() };
+        assert!(kind.repair());
+        assert_eq!(kind, ListKind::Kanban { columns: default_columns() });
+        let mut kind = ListKind::Kanban { columns: vec![String::from("Doing"), String::from(" "), String::from("Done"), String::from("Doing")] };
+        assert!(kind.repair());
+        assert_eq!(kind, ListKind::Kanban { columns: vec![String::from("Doing"), String::from("Done")] });
+        assert!(!kind.repair());
+        //one column would have nowhere to put finished items
+        let mut kind = ListKind::Kanban { columns: vec![String::from("Doing"), String::from("Doing")] };
+        assert!(kind.repair());
+        assert_eq!(kind, ListKind::Kanban { columns: default_columns() });
+        assert!(!ListKind::Standard.repair());
+    }
+
+    #[test]
+    fn parses_columns() {
+        assert_eq!(parse_columns(" Backlog, Doing ,,Doing, Done"), Some(vec![String::from("Backlog"), String::from("Doing"), String::from("Done")]));
+        assert_eq!(parse_columns(" , "), None);
+        assert_eq!(parse_columns("Doing, Doing"), None);
+    }
+}
@@ -3,8 +3,8 @@ use std::fs;
 use std::fs::File;
 use std::io;
 use std::io::BufReader;
-use std::path::PathBuf;
-use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use ::serde::{Serialize, Deserialize};
 
@@ -13,15 +13,125 @@ use chrono::*;
 use egui::*;
 use eframe::egui;
 
+#[allow(clippy::single_component_path_imports)]
 use rfd; //RustyFileDialog
 
 
+pub mod history;
+pub mod recurrence;
+pub mod fields;
+pub mod templates;
+pub mod query;
+pub mod search;
+pub mod directory;
+pub mod views;
+pub mod quick_add;
+pub mod kinds;
+pub mod statuses;
+pub mod board;
+use history::{Command, History};
+use fields::{FieldDef, FieldError, FieldKind, FieldValue};
+use query::Query;
+use directory::ListDirectory;
+use views::SmartView;
+use kinds::ListKind;
+use statuses::{StatusChange, StatusDef, StatusError};
+use board::{BoardError, BoardSettings, ListView};
+use recurrence::{Recurrence, RecurrenceError};
+
 #[derive(Serialize, Deserialize, Default)]
 pub struct TodoUi {
+    //the list being shown; its path, dirty flag and history are kept in the fields below while it's active
+    //open lists are saved with the app through remembered_lists, which reads lists with a file from it again on start
+    #[serde(skip)]
     pub loaded_list: Option<TodoList>,
-    pub all_lists: Vec<TodoList>,
+    #[serde(skip)]
+    pub all_lists: Vec<OpenList>, //every other open list, in sidebar order
+    #[serde(skip)]
+    active_index: usize, //where loaded_list goes in the sidebar, among all_lists
+    #[serde(skip)]
     pub current_path: Option<PathBuf>,
+    #[serde(skip)]
+    dirty: bool, //loaded_list has changes that haven't been saved to current_path
+    #[serde(default)]
+    remembered_lists: Vec<RememberedList>, //the open lists in sidebar order, written down when the app saves
+    #[serde(default)]
+    selected_list: Option<usize>, //index into remembered_lists of the list that was showing
+    //the app used to save the list being shown whole under these names; they're read once, then saved as above
+    #[serde(default, rename = "loaded_list", skip_serializing)]
+    legacy_loaded_list: Option<TodoList>,
+    #[serde(default, rename = "current_path", skip_serializing)]
+    legacy_current_path: Option<PathBuf>,
     pub text_inputs: HashMap<String, String>, //maybe I can roll this into the general storage?
+    #[serde(default)]
+    pub saved_queries: BTreeMap<String, String>, //name -> query text
+    #[serde(default)]
+    pub workspace_dir: Option<PathBuf>, //folder of list files shown in the sidebar, LISTS_DIR if not set
+    #[serde(default)]
+    pub templates_dir: Option<PathBuf>, //folder templates are kept in, TEMPLATES_DIR in the workspace folder if not set
+    #[serde(default)]
+    pub smart_view: Option<SmartView>, //shown instead of loaded_list while set
+    #[serde(default)]
+    pub completed_view_days: Option<u32>, //for the Completed view, views::DEFAULT_COMPLETED_DAYS if not set
+    #[serde(skip)]
+    priority_input: Priority,
+    #[serde(skip)]
+    tag_filter: BTreeSet<String>, //only items with all of these tags are shown
+    #[serde(skip)]
+    error_message: Option<String>, //shown above the list until dismissed
+    #[serde(skip)]
+    history: History, //undo/redo for loaded_list
+    #[serde(skip)]
+    directory_lists: Option<Vec<(PathBuf, TodoList)>>, //lists read from the workspace folder, None until something needs them
+    #[serde(skip)]
+    search_directory: bool, //whether the search also looks through directory_lists
+    #[serde(skip)]
+    view_editing: Option<(ListSource, usize)>, //the item being edited in place in the smart view
+    #[serde(skip)]
+    list_directory: Option<ListDirectory>, //index of workspace_dir, made on the first frame
+    #[serde(skip)]
+    transfer_ids: BTreeSet<usize>, //items picked in the move/copy dialog
+    #[serde(skip)]
+    transfer_copy: bool, //whether the move/copy dialog copies rather than moves
+    #[serde(skip)]
+    editing_item: Option<usize>, //the item the Edit Item popup is open for, None while it's closed
+    #[serde(skip)]
+    subtask_parent: Option<usize>, //the item the Add Subtask popup adds under, None while it's closed
+}
+
+//an open list as the app saves it; a list with a file is read from it again on start, but one with changes that
+//haven't been saved, or no file at all, is kept whole so nothing is lost
+#[derive(Serialize, Deserialize)]
+struct RememberedList {
+    path: Option<PathBuf>,
+    #[serde(default)]
+    unsaved: Option<TodoList>,
+}
+
+//a list that's open in the workspace but not the one being shown
+pub struct OpenList {
+    pub list: TodoList,
+    pub path: Option<PathBuf>,
+    pub dirty: bool,
+    history: History,
+}
+
+//things clicked in the folder section of the sidebar
+enum FolderAction {
+    Open(PathBuf),
+    Create(String),
+    Rename(PathBuf, String),
+    Delete(PathBuf),
+    Refresh,
+    ChangeRoot(PathBuf),
+}
+
+//which list a search result or an item in a smart view came from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ListSource {
+    Loaded,
+    Open(usize), //index into all_lists
+    Directory(usize), //index into directory_lists
 }
 
 impl TodoUi {
@@ -52,534 +162,5578 @@ impl TodoUi {
         cc.egui_ctx.set_fonts(fonts);
 
         //persistence
-        if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+        let mut app: TodoUi = cc.storage.and_then(|storage| eframe::get_value(storage, eframe::APP_KEY)).unwrap_or_default();
+        app.reopen_lists();
+        app
+    }
+
+    //writes down the open lists, and which one is showing, for the app to save
+    fn remember_open_lists(&mut self) {
+        if self.history.take_changed() {
+            self.dirty = true;
+        }
+        let remember = |list: &TodoList, path: &Option<PathBuf>, dirty: bool| RememberedList {
+            path: path.clone(),
+            unsaved: (dirty || path.is_none()).then(|| list.clone()),
+        };
+        self.remembered_lists = self.all_lists.iter().map(|x| remember(&x.list, &x.path, x.dirty)).collect();
+        self.selected_list = None;
+        if let Some(list) = &self.loaded_list {
+            let index = self.active_index.min(self.remembered_lists.len());
+            self.remembered_lists.insert(index, remember(list, &self.current_path, self.dirty));
+            self.selected_list = Some(index);
         }
+    }
 
-        Default::default()
+    //opens the lists remembered by remember_open_lists, and shows the one that was showing
+    //lists kept whole come back with their changes still unsaved
+    fn reopen_lists(&mut self) {
+        let mut failed = Vec::new();
+        let mut selected = None;
+        if let Some(list) = self.legacy_loaded_list.take() {
+            let path = self.legacy_current_path.take();
+            self.remembered_lists.push(RememberedList { path, unsaved: Some(list) });
+        }
+        for (index, remembered) in std::mem::take(&mut self.remembered_lists).into_iter().enumerate() {
+            let dirty = remembered.unsaved.is_some();
+            let list = match (remembered.unsaved, &remembered.path) {
+                (Some(mut list), _) => {
+                    list.repair();
+                    list
+                }
+                (None, Some(path)) => match TodoList::from_file(path) {
+                    Ok(list) => list,
+                    Err(e) => {
+                        failed.push(format!("{} ({e})", path.display()));
+                        continue;
+                    }
+                },
+                (None, None) => continue,
+            };
+            self.open_list(list, remembered.path);
+            self.dirty |= dirty;
+            if self.selected_list == Some(index) {
+                selected = Some(self.active_index);
+            }
+        }
+        if let Some(index) = selected.filter(|x| *x != self.active_index) {
+            self.switch_to_list(index);
+        }
+        if !failed.is_empty() {
+            self.error_message = Some(format!("Couldn't reopen {}.", failed.join(", ")));
+        }
     }
-    
-    fn top_panel_frame(&self) -> egui::Frame {
-        Frame::none()
-        .fill(Color32::DARK_GRAY)
+
+    //puts loaded_list back among all_lists, so another list can be made active
+    fn stash_loaded_list(&mut self) {
+        if self.history.take_changed() {
+            self.dirty = true;
+        }
+        if let Some(list) = self.loaded_list.take() {
+            let open = OpenList {
+                list,
+                path: self.current_path.take(),
+                dirty: std::mem::take(&mut self.dirty),
+                history: std::mem::take(&mut self.history),
+            };
+            self.all_lists.insert(self.active_index.min(self.all_lists.len()), open);
+        }
     }
 
-}
+    //makes all_lists[index] the loaded list; loaded_list has to be stashed first
+    fn activate_list(&mut self, index: usize) {
+        let open = self.all_lists.remove(index);
+        self.loaded_list = Some(open.list);
+        self.current_path = open.path;
+        self.dirty = open.dirty;
+        self.history = open.history;
+        self.active_index = index;
+    }
 
-impl eframe::App for TodoUi {
-    // framework calls this to save state before shutdown
-    fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, self);
+    //switches to one of the lists in all_lists
+    fn switch_to_list(&mut self, index: usize) {
+        //stashing puts the loaded list in front of everything from active_index on
+        let position = if self.loaded_list.is_some() && index >= self.active_index { index + 1 } else { index };
+        self.stash_loaded_list();
+        self.activate_list(position);
+        self.close_item_popups();
     }
 
-    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        let mut debug_mode = false;
-        let args: Vec<String> = env::args().collect();
-        if args.len() > 1 {
-            if &args[1] == &String::from("debug") {
-                debug_mode = true;
+    //opens the list as a new entry at the end of the sidebar and shows it
+    //a list that's already open from the same path is switched to instead
+    //every way of opening a list comes through here, so this is where old trash is purged and old items archived
+    fn open_list(&mut self, mut list: TodoList, path: Option<PathBuf>) {
+        if let Some(path) = path.as_deref() {
+            if self.current_path.as_deref().is_some_and(|x| same_file(x, path)) {
+                return;
+            }
+            if let Some(index) = self.all_lists.iter().position(|x| x.path.as_deref().is_some_and(|x| same_file(x, path))) {
+                self.switch_to_list(index);
+                return;
             }
         }
+        let tidied = list.tidy(Local::now());
+        self.stash_loaded_list();
+        self.loaded_list = Some(list);
+        self.current_path = path;
+        self.dirty = tidied;
+        self.history = History::default();
+        self.active_index = self.all_lists.len();
+        self.close_item_popups();
+    }
 
-        let top_panel = egui::TopBottomPanel::top("top_panel");
+    //closes the loaded list without saving it and shows its neighbour, if there is one
+    fn close_loaded_list(&mut self) {
+        self.loaded_list = None;
+        self.current_path = None;
+        self.dirty = false;
+        self.history = History::default();
+        if !self.all_lists.is_empty() {
+            self.activate_list(self.active_index.min(self.all_lists.len() - 1));
+        }
+        self.close_item_popups();
+    }
 
-        top_panel
-            .frame(self.top_panel_frame())
-            .show(ctx, |ui| {
-                ui.label(RichText::new("Todo List Manager")
-                    .color(Color32::YELLOW)
-                    .size(36.0)
-                    .family(FontFamily::Name("DM_Sans".into()))
-                ); 
-                ui.label(RichText::new("Catchy subtitle to be determined.")
-                    .family(FontFamily::Monospace));
-            });
+    //the item popups refer to items by id, so they're closed whenever a different list is loaded
+    fn close_item_popups(&mut self) {
+        self.editing_item = None;
+        self.subtask_parent = None;
+    }
 
-        egui::TopBottomPanel::top("top_panel_2").show(ctx, |ui| {
+    //closes the Edit Item and Add Subtask popups if their item has left the loaded list, which undoing, redoing,
+    //deleting or clearing can all do while they're open
+    fn close_missing_item_popups(&mut self) {
+        let list = self.loaded_list.as_ref();
+        let exists = |id: &usize| list.is_some_and(|x| x.get_item_by_id(*id).is_some());
+        self.editing_item = self.editing_item.filter(exists);
+        self.subtask_parent = self.subtask_parent.filter(exists);
+    }
+
+    //starts a new day in every open daily list whose reset time has gone by, returns how many did
+    //this is checked every frame, so it happens as the app starts and again whenever the date changes while it's running
+    fn roll_over_lists(&mut self, now: DateTime<Local>) -> usize {
+        let mut rolled = 0;
+        if let Some(list) = self.loaded_list.as_mut() {
+            if list.roll_over(now) {
+                self.dirty = true;
+                rolled += 1;
+            }
+        }
+        for open in &mut self.all_lists {
+            if open.list.roll_over(now) {
+                open.dirty = true;
+                rolled += 1;
+            }
+        }
+        rolled
+    }
+
+    fn save_loaded_list(&mut self, path: PathBuf) {
+        let Some(list) = self.loaded_list.as_mut() else {
+            return;
+        };
+        match list.save(&path) {
+            Ok(_) => {
+                self.current_path = Some(path);
+                self.dirty = false;
+                //the copy read from the folder is out of date if the list was saved there
+                self.directory_lists = None;
+            }
+            Err(e) => self.error_message = Some(format!("Couldn't save the list: {e}.")),
+        }
+    }
+
+    //whether the loaded list was opened from the file at path, and which of all_lists were
+    fn lists_open_at(&self, path: &Path) -> (bool, Vec<usize>) {
+        let showing = self.current_path.as_deref().is_some_and(|x| same_file(x, path));
+        let open_indices = self.all_lists.iter().enumerate()
+            .filter(|(_, x)| x.path.as_deref().is_some_and(|x| same_file(x, path)))
+            .map(|(index, _)| index)
+            .collect();
+        (showing, open_indices)
+    }
+
+    //the sidebar listing every open list, returns true if a different list is now showing
+    fn workspace_ui(&mut self, ui: &mut Ui) -> bool {
+        ui.heading("Open lists");
+        let open_count = self.all_lists.len() + usize::from(self.loaded_list.is_some());
+        if open_count == 0 {
+            ui.label("No lists are open.");
+        }
+        let mut switch_to = None;
+        let mut close_loaded = false;
+        let mut show_loaded = false; //clicked the loaded list while a smart view was showing instead
+        let viewing = self.smart_view.is_some();
+        for position in 0..open_count {
+            let active = self.loaded_list.is_some() && position == self.active_index;
+            let background_index = if self.loaded_list.is_some() && position > self.active_index { position - 1 } else { position };
+            let (list, path, dirty) = match &self.loaded_list {
+                Some(list) if active => (list, &self.current_path, self.dirty),
+                _ => {
+                    let open = &self.all_lists[background_index];
+                    (&open.list, &open.path, open.dirty)
+                }
+            };
             ui.horizontal(|ui| {
-                ui.label("Options: ");
+                let title = if list.archived_at().is_some() { format!("✓ {}", list.title) } else { list.title.clone() };
+                let label = if dirty { format!("{title} •") } else { title };
+                let hover = match path {
+                    Some(path) => path.display().to_string(),
+                    None => String::from("Not saved to a file yet"),
+                };
+                if ui.selectable_label(active && !viewing, label).on_hover_text(hover).clicked() {
+                    if active {
+                        show_loaded = viewing;
+                    } else {
+                        switch_to = Some(background_index);
+                    }
+                }
+                if active {
+                    if dirty {
+                        ui.menu_button("✕", |ui| {
+                            ui.label("This list has unsaved changes.");
+                            if ui.button("Close without saving").clicked() {
+                                close_loaded = true;
+                                ui.close_menu();
+                            }
+                        });
+                    } else if ui.small_button("✕").on_hover_text("Close this list").clicked() {
+                        close_loaded = true;
+                    }
+                }
+            });
+        }
+        if close_loaded {
+            self.close_loaded_list();
+        } else if let Some(index) = switch_to {
+            self.switch_to_list(index);
+        }
+        let folder_switched = self.folder_ui(ui);
+        close_loaded || switch_to.is_some() || show_loaded || folder_switched
+    }
 
-                if ui.button("New List").clicked() {
-                    //TODO implement check if current list is saved
-                    self.loaded_list = Some(TodoList::new(String::from("New Todo List")));
+    fn workspace_root(&self) -> PathBuf {
+        self.workspace_dir.clone().unwrap_or_else(|| PathBuf::from(LISTS_DIR))
+    }
+
+    fn templates_root(&self) -> PathBuf {
+        self.templates_dir.clone().unwrap_or_else(|| self.workspace_root().join(templates::TEMPLATES_DIR))
+    }
+
+    //every list file in the workspace folder, which are only read when clicked; returns true if one was opened
+    fn folder_ui(&mut self, ui: &mut Ui) -> bool {
+        ui.separator();
+        let root = self.workspace_root();
+        if let Err(e) = self.open_list_directory() {
+            ui.label(RichText::new(format!("Couldn't open the {} folder: {e}.", root.display())).color(Color32::RED));
+        }
+
+        let mut action = None;
+        ui.horizontal(|ui| {
+            ui.heading("Folder");
+            if ui.small_button("⟳").on_hover_text("Look for lists added or removed outside the app").clicked() {
+                action = Some(FolderAction::Refresh);
+            }
+            if ui.small_button("Change…").on_hover_text(root.display().to_string()).clicked() {
+                if let Some(folder) = rfd::FileDialog::new().set_directory(&root).pick_folder() {
+                    action = Some(FolderAction::ChangeRoot(folder));
                 }
+            }
+        });
+        ui.horizontal(|ui| {
+            egui::TextEdit::singleline(self.text_inputs.entry(String::from("new_list_name")).or_default())
+            .hint_text("New list name")
+            .desired_width(110.0)
+            .show(ui);
+            if ui.button("Create").clicked() {
+                action = Some(FolderAction::Create(self.text_inputs.get("new_list_name").cloned().unwrap_or_default()));
+            }
+        });
 
-                if ui.button("Load List From File").clicked() {
-                    //TODO implement check if current list is saved
-                    let empty_path = PathBuf::new();
-                    if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("json", &["json"])
-                        .set_directory(match &self.current_path {
-                            Some(p) => p,
-                            None => &empty_path,
-                        })
-                        .pick_file() {
-                            let load_result = TodoList::from_file(&path);
-                            match load_result {
-                                Ok(list) => {
-                                    self.loaded_list = Some(list);
-                                    self.current_path = Some(path);
-                                }
-                                Err(e) => {
-                                    panic!("Something panicked when attempting to load the list through the UI:\n{e:?}");
-                                }
-                            };
+        if let Some(directory) = &self.list_directory {
+            if directory.paths().is_empty() {
+                ui.label(RichText::new("No lists in this folder yet.").weak());
+            }
+            for path in directory.paths() {
+                let name = ListDirectory::name(path);
+                let (showing, open_indices) = self.lists_open_at(path);
+                let open = showing || !open_indices.is_empty();
+                let label = if open { RichText::new(&name).strong() } else { RichText::new(&name) };
+                let response = ui.selectable_label(showing, label);
+                if response.clicked() {
+                    action = Some(FolderAction::Open(path.clone()));
+                }
+                response.context_menu(|ui| {
+                    let rename_input = self.text_inputs.entry(String::from("rename_list_file")).or_default();
+                    egui::TextEdit::singleline(rename_input)
+                    .hint_text(&name)
+                    .show(ui);
+                    if ui.button("Rename").clicked() {
+                        action = Some(FolderAction::Rename(path.clone(), std::mem::take(rename_input)));
+                        ui.close_menu();
                     }
+                    ui.menu_button("Delete", |ui| {
+                        ui.label("This deletes the file, and can't be undone.");
+                        if ui.button(format!("Delete \"{name}\"")).clicked() {
+                            action = Some(FolderAction::Delete(path.clone()));
+                            ui.close_menu();
+                        }
+                    });
+                });
+            }
+        }
+
+        let Some(action) = action else {
+            return false;
+        };
+        if let FolderAction::ChangeRoot(folder) = action {
+            self.workspace_dir = Some(folder);
+            self.directory_lists = None;
+            return false;
+        }
+        //looked up before the file is renamed or deleted, since paths can only be compared while the file is there
+        let (showing, open_indices) = match &action {
+            FolderAction::Rename(path, _) | FolderAction::Delete(path) => self.lists_open_at(path),
+            _ => (false, Vec::new()),
+        };
+        let Some(directory) = self.list_directory.as_mut() else {
+            return false;
+        };
+        let mut opened = false;
+        let result = match action {
+            FolderAction::Open(path) => directory.load(&path).map(|list| {
+                self.open_list(list, Some(path));
+                opened = true;
+            }),
+            FolderAction::Create(name) => directory.create(&name).and_then(|path| {
+                self.text_inputs.insert(String::from("new_list_name"), String::new());
+                let list = TodoList::from_file(&path)?;
+                self.open_list(list, Some(path));
+                opened = true;
+                Ok(())
+            }),
+            FolderAction::Rename(path, new_name) => directory.rename(&path, &new_name).map(|new_path| {
+                //open lists follow their file
+                if showing {
+                    self.current_path = Some(new_path.clone());
                 }
+                for index in &open_indices {
+                    self.all_lists[*index].path = Some(new_path.clone());
+                }
+            }),
+            FolderAction::Delete(path) => directory.delete(&path).map(|()| {
+                //an open list that's been deleted stays open, but has to be saved somewhere new to keep it
+                if showing {
+                    self.current_path = None;
+                    self.dirty = true;
+                }
+                for index in &open_indices {
+                    self.all_lists[*index].path = None;
+                    self.all_lists[*index].dirty = true;
+                }
+            }),
+            FolderAction::Refresh => directory.refresh(),
+            FolderAction::ChangeRoot(_) => Ok(()),
+        };
+        if let Err(e) = result {
+            self.error_message = Some(format!("Couldn't do that in the {} folder: {e}.", root.display()));
+        }
+        //the folder's files may have changed, so they're read again when next needed
+        self.directory_lists = None;
+        opened
+    }
 
-                if self.loaded_list.is_some() {
-                    if ui.button("Save List To File").clicked() {
-                        let empty_path = PathBuf::new();
-                        if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("json", &["json"])
-                        .set_directory(match &self.current_path {
-                            Some(p) => p,
-                            None => &empty_path,
-                        })
-                        .save_file() {
-                            let mut fallback_list = TodoList::new(String::from("Fallback list title (did something not get loaded?)"));
-                            let saved_list = self.loaded_list.as_mut().unwrap_or(&mut fallback_list);
-                            let _ = match saved_list.save(&path) {
-                                Ok(_) => {
-                                    self.current_path = Some(path);
-                                }
-                                Err(e) => {
-                                    panic!("Something panicked when attempting to save the list through the UI:\n{e:?}");
-                            }};
+    //indexes the workspace folder, unless list_directory already has it
+    fn open_list_directory(&mut self) -> io::Result<&mut ListDirectory> {
+        let root = self.workspace_root();
+        let directory = match self.list_directory.take() {
+            Some(directory) if directory.root() == root => directory,
+            _ => ListDirectory::open(&root)?,
+        };
+        Ok(self.list_directory.insert(directory))
+    }
 
-                        }
+    //reads every list in the workspace folder, unless that's been done since the folder last changed
+    //the files are the ones list_directory finds, so they're the same lists the sidebar shows
+    fn load_directory_lists(&mut self) {
+        if self.directory_lists.is_some() {
+            return;
+        }
+        let root = self.workspace_root();
+        let mut failed = Vec::new();
+        let lists = match self.open_list_directory().and_then(|directory| directory.refresh().map(|()| directory)) {
+            Ok(directory) => directory.paths().iter()
+                .filter_map(|path| match directory.load(path) {
+                    Ok(list) => Some((path.clone(), list)),
+                    Err(e) => {
+                        failed.push(format!("{} ({e})", ListDirectory::name(path)));
+                        None
                     }
+                })
+                .collect(),
+            Err(e) => {
+                failed.push(format!("the {} folder ({e})", root.display()));
+                Vec::new()
+            }
+        };
+        self.directory_lists = Some(lists);
+        if !failed.is_empty() {
+            self.error_message = Some(format!("Couldn't read {}.", failed.join(", ")));
+        }
+    }
+
+    //every open list and, if with_directory, every list read from the folder, along with where each came from
+    //folder lists that are also open are left out, since the open copy is the up to date one
+    fn source_lists(&self, with_directory: bool) -> (Vec<&TodoList>, Vec<ListSource>) {
+        let mut lists: Vec<&TodoList> = Vec::new();
+        let mut sources = Vec::new();
+        if let Some(list) = &self.loaded_list {
+            lists.push(list);
+            sources.push(ListSource::Loaded);
+        }
+        for (index, open) in self.all_lists.iter().enumerate() {
+            lists.push(&open.list);
+            sources.push(ListSource::Open(index));
+        }
+        if with_directory {
+            //compared as canonical paths, since a list opened through the file picker can spell its path differently
+            let open_paths: Vec<PathBuf> = self.current_path.iter()
+                .chain(self.all_lists.iter().filter_map(|x| x.path.as_ref()))
+                .map(|x| fs::canonicalize(x).unwrap_or_else(|_| x.clone()))
+                .collect();
+            for (index, (path, list)) in self.directory_lists.iter().flatten().enumerate() {
+                let path = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+                if !open_paths.contains(&path) {
+                    lists.push(list);
+                    sources.push(ListSource::Directory(index));
                 }
-            });
+            }
+        }
+        (lists, sources)
+    }
+
+    //makes a change to the list a search result or view item came from
+    //open lists take it through their undo history and are saved as usual; a list that was only read from the folder
+    //has nowhere to keep unsaved changes, so its file is read again, changed and saved straight away; commands find their
+    //items by id, so the change lands on the item as it is in the file rather than as it was when the folder was read
+    fn apply_to_source(&mut self, source: ListSource, command: Command) {
+        match source {
+            ListSource::Loaded => {
+                if let Some(list) = self.loaded_list.as_mut() {
+                    self.history.apply(list, command);
+                }
+            }
+            ListSource::Open(index) => {
+                let open = &mut self.all_lists[index];
+                if open.history.apply(&mut open.list, command) {
+                    open.dirty = true;
+                }
+            }
+            ListSource::Directory(index) => self.change_directory_list(index, |list| command.apply(list).is_some()),
+        }
+    }
+
+    //reads directory_lists[index] from its file again, lets change make its change to that fresh copy and saves it
+    //straight away; change returns false if it couldn't find what it was meant to change
+    fn change_directory_list(&mut self, index: usize, change: impl FnOnce(&mut TodoList) -> bool) {
+        let Some((path, cached)) = self.directory_lists.as_mut().and_then(|x| x.get_mut(index)) else {
+            return;
+        };
+        let result = TodoList::from_file(path).and_then(|mut list| {
+            let applied = change(&mut list);
+            if applied {
+                list.save(path)?;
+            }
+            *cached = list;
+            Ok(applied)
         });
+        match result {
+            Ok(true) => {}
+            Ok(false) => self.error_message = Some(format!("Couldn't change {}, the item isn't in it any more.", path.display())),
+            Err(e) => self.error_message = Some(format!("Couldn't save the change to {}: {e}.", path.display())),
+        }
+    }
 
-        //handling for popup that renames list
-        let rename_list_popup = egui::Window::new("Rename List")
-            .title_bar(true)
-            .default_open(true);
-        if let Some(store) = frame.storage_mut() {
-            if let Some(rename_list_popup_status) = store.get_string("rename_list_popup_status") {
-                if rename_list_popup_status == String::from("open") {
-                    rename_list_popup.show(&ctx, |ui| {
-                        ui.label("Enter a new title here.");
-                        egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("list_title"))
-                            .expect("list_title should be properly initialized when Rename List is clicked"))
-                        .hint_text("New Title")
-                        .show(ui);
-                        
-                        ui.horizontal(|ui| {
-                            if ui.button("Set New Title").clicked() {
-                                self.loaded_list.as_mut().expect("loaded_list returned None").title = self.text_inputs.get("list_title").unwrap().clone();
-                                store.set_string("rename_list_popup_status", String::from("closed"));
-                            }
-                            if ui.button("Cancel").clicked() {
-                                store.set_string("rename_list_popup_status", String::from("closed"));
-                            }
-                        });
+    //copies the items from the loaded list to the end of all_lists[index], or moves them if transfer_copy isn't set,
+    //through both lists' undo histories
+    fn transfer_to_open_list(&mut self, ids: &[usize], index: usize) {
+        let (Some(list), Some(open)) = (self.loaded_list.as_mut(), self.all_lists.get_mut(index)) else {
+            return;
+        };
+        let copy = Command::copy_items(list, ids, &mut open.list);
+        if open.history.apply(&mut open.list, copy) {
+            open.dirty = true;
+        }
+        if !self.transfer_copy {
+            let remove = Command::remove_items(list, ids);
+            self.history.apply(list, remove);
+        }
+    }
 
-                    });
+    //transfer_to_open_list for a list that isn't open, which is saved to path with the items added; a move only takes
+    //the items out of the loaded list once that save has worked, so a failed save can't lose them
+    //undoing a move brings the items back here and takes the copies out of the file again
+    fn transfer_to_file(&mut self, ids: &[usize], mut destination: TodoList, path: &PathBuf) -> Result<(), io::Error> {
+        let Some(list) = self.loaded_list.as_mut() else {
+            return Ok(());
+        };
+        let remove_copies = Command::copy_items(list, ids, &mut destination).apply(&mut destination);
+        destination.save(path)?;
+        if !self.transfer_copy {
+            let mut undo = Vec::new();
+            if let Some(inverse) = Command::remove_items(list, ids).apply(list) {
+                undo.push(inverse);
+            }
+            if let Some(remove_copies) = remove_copies {
+                undo.push(Command::InFile { path: path.clone(), command: Box::new(remove_copies) });
+            }
+            if !undo.is_empty() {
+                self.history.record(Command::Batch(undo));
+            }
+        }
+        Ok(())
+    }
+
+    //shows the list the item is in and scrolls to it, for clicks on search results and view items
+    fn show_item(&mut self, source: ListSource, item_id: usize, ctx: &egui::Context) {
+        match source {
+            ListSource::Loaded => {}
+            ListSource::Open(index) => self.switch_to_list(index),
+            ListSource::Directory(index) => {
+                if let Some((path, list)) = self.directory_lists.as_ref().and_then(|x| x.get(index)).cloned() {
+                    self.open_list(list, Some(path));
                 }
             }
         }
+        self.smart_view = None;
+        self.view_editing = None;
+        self.close_item_popups();
+        //filters could be hiding the item
+        self.tag_filter.clear();
+        self.text_inputs.insert(String::from("query"), String::new());
+        ctx.data_mut(|x| x.insert_temp(Id::new(SCROLL_TO_ITEM_KEY), item_id));
+    }
 
-        //handling for popup to add list item
-        let mut add_item_popup = egui::Window::new("Add Item")
-            .title_bar(true)
-            .default_open(true);
-        if let Some(store) = frame.storage_mut() {
-            if let Some(add_item_popup_status) = store.get_string("add_item_popup_status") {
-                if add_item_popup_status == String::from("open") {
-                    add_item_popup.show(&ctx, |ui| {
-
-                        ui.label("Give your new list item a name here.");
-                        egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("item_name"))
-                            .expect("item_name should be properly initialized before Add Item is clicked"))
-                        .hint_text("New Item Name")
-                        .show(ui);
+    //the smart views at the top of the sidebar
+    fn views_ui(&mut self, ui: &mut Ui) {
+        ui.heading("Views");
+        let days = self.completed_view_days.unwrap_or(views::DEFAULT_COMPLETED_DAYS);
+        for view in [SmartView::Today, SmartView::Overdue, SmartView::NextWeek, SmartView::NoDueDate, SmartView::Completed { days }] {
+            let selected = self.smart_view == Some(view);
+            if ui.selectable_label(selected, view.label()).clicked() && !selected {
+                self.smart_view = Some(view);
+                self.view_editing = None;
+            }
+        }
+        ui.horizontal(|ui| {
+            ui.label("Completed view covers");
+            let mut days = days;
+            if ui.add(DragValue::new(&mut days).clamp_range(1..=365).suffix(" days")).changed() {
+                self.completed_view_days = Some(days);
+                if matches!(self.smart_view, Some(SmartView::Completed { .. })) {
+                    self.smart_view = Some(SmartView::Completed { days });
+                }
+            }
+        });
+        ui.separator();
+    }
 
-                        ui.label("Describe the item in more detail here.");
-                        egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("item_description"))
-                            .expect("item_description should be properly initialized before Add Item is clicked"))
-                        .hint_text("New Item Description")
-                        .show(ui);
+    //the items in a smart view, from every open list and every list in the workspace folder
+    //each one can be ticked off, renamed or given a new due date from here, and clicking its name goes to it
+    fn smart_view_ui(&mut self, ui: &mut Ui, view: SmartView, ctx: &egui::Context) {
+        self.load_directory_lists();
+        let now = Local::now();
+        let mut refresh = false;
+        ui.horizontal(|ui| {
+            ui.heading(view.label());
+            refresh = ui.small_button("⟳").on_hover_text("Read the lists in the folder again").clicked();
+        });
+        ui.label(RichText::new(format!("From every open list and every list in the {} folder.", self.workspace_root().display())).weak());
 
-                        ui.horizontal(|ui| {
-                            if ui.button("Add Item").clicked() {
-                                self.loaded_list.as_mut().expect("loaded_list returned None").add(
-                                    self.text_inputs.get("item_name").unwrap().clone(), 
-                                    self.text_inputs.get("item_description").unwrap().clone()
-                                );
-                                store.set_string("add_item_popup_status", String::from("closed"));
-                            }
-                            if ui.button("Cancel").clicked() {
-                                store.set_string("add_item_popup_status", String::from("closed"));
-                            }
-                        });
+        //taken out while the lists are borrowed, so the in-place editor can use them
+        let mut text_inputs = std::mem::take(&mut self.text_inputs);
+        let mut changes: Vec<(ListSource, Command)> = Vec::new();
+        let mut jump_to = None;
+        let mut start_editing = None;
+        let mut stop_editing = false;
+        let (lists, sources) = self.source_lists(true);
+        let entries = views::gather(&lists, view, now);
+        if entries.is_empty() {
+            ui.label("Nothing here.");
+        }
+        for entry in &entries {
+            let source = sources[entry.list_index];
+            let list = lists[entry.list_index];
+            let Some(item) = list.get_item_by_id(entry.item_id) else {
+                continue;
+            };
+            ui.horizontal(|ui| {
+                let mut completed = item.completed;
+                if ui.checkbox(&mut completed, "").on_hover_text("Complete").changed() {
+                    changes.push((source, Command::SetCompleted { id: item.id, completed, at: now }));
+                }
+                if let Some(status) = Self::status_menu(ui, list, item) {
+                    changes.push((source, Command::SetStatus { id: item.id, status, at: now }));
+                }
+                if self.view_editing == Some((source, item.id)) {
+                    egui::TextEdit::singleline(text_inputs.entry(String::from("view_item_name")).or_default())
+                    .desired_width(160.0)
+                    .show(ui);
+                    egui::TextEdit::singleline(text_inputs.entry(String::from("view_item_due")).or_default())
+                    .hint_text(DATE_INPUT_HINT)
+                    .desired_width(120.0)
+                    .show(ui);
+                    let due = parse_date_input(text_inputs.get("view_item_due").map_or("", |x| x.as_str()), NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+                    if ui.add_enabled(due.is_ok(), Button::new("Set")).on_disabled_hover_text(format!("Due dates should look like {DATE_INPUT_HINT}")).clicked() {
+                        //only the fields edited here, so a list read from the folder keeps what's changed in its file since
+                        let name = text_inputs.get("view_item_name").cloned().unwrap_or_default();
+                        changes.push((source, Command::Batch(vec![
+                            Command::ChangeName { id: item.id, name },
+                            Command::ChangeDue { id: item.id, due: due.unwrap_or_default() },
+                        ])));
+                        stop_editing = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        stop_editing = true;
+                    }
+                    return;
+                }
+                if item.priority != Priority::None {
+                    ui.label(RichText::new(item.priority.label()).color(item.priority.color()));
+                }
+                let overdue = item.is_overdue(now);
+                let name = if overdue { RichText::new(&item.name).color(Color32::RED) } else { RichText::new(&item.name) };
+                if ui.add(Label::new(name).sense(Sense::click())).on_hover_text("Go to this item").clicked() {
+                    jump_to = Some((source, item.id));
+                }
+                ui.label(RichText::new(&list.title).small().weak());
+                if let Some(due) = item.due {
+                    let due_text = RichText::new(format!("Due {}", due.format(DATE_DISPLAY_FORMAT)));
+                    ui.label(if overdue { due_text.color(Color32::RED).strong() } else { due_text.weak() });
+                }
+                if let Some(completed_at) = item.completed_at {
+                    ui.label(RichText::new(format!("Completed {}", completed_at.format(DATE_DISPLAY_FORMAT))).weak());
+                }
+                if ui.small_button("Edit").clicked() {
+                    let due = item.due.map(|x| x.format(DATE_DISPLAY_FORMAT).to_string()).unwrap_or_default();
+                    start_editing = Some((source, item.id, item.name.clone(), due));
+                }
+            });
+        }
+        self.text_inputs = text_inputs;
+
+        if stop_editing {
+            self.view_editing = None;
+        }
+        if let Some((source, id, name, due)) = start_editing {
+            self.view_editing = Some((source, id));
+            self.text_inputs.insert(String::from("view_item_name"), name);
+            self.text_inputs.insert(String::from("view_item_due"), due);
+        }
+        for (source, command) in changes {
+            self.apply_to_source(source, command);
+        }
+        if let Some((source, id)) = jump_to {
+            self.show_item(source, id, ctx);
+        }
+        if refresh {
+            self.directory_lists = None;
+            self.view_editing = None;
+        }
+    }
+
+    fn source_list_mut(&mut self, source: ListSource) -> Option<&mut TodoList> {
+        match source {
+            ListSource::Loaded => self.loaded_list.as_mut(),
+            ListSource::Open(index) => self.all_lists.get_mut(index).map(|x| &mut x.list),
+            ListSource::Directory(index) => self.directory_lists.as_mut()?.get_mut(index).map(|(_, list)| list),
+        }
+    }
+
+    //the list a quick-add line's list: part refers to, by title or file name
+    fn find_source(&mut self, typed: &str) -> Option<ListSource> {
+        self.load_directory_lists();
+        let (lists, sources) = self.source_lists(true);
+        let path_of = |source: ListSource| match source {
+            ListSource::Loaded => self.current_path.as_ref(),
+            ListSource::Open(index) => self.all_lists[index].path.as_ref(),
+            ListSource::Directory(index) => self.directory_lists.iter().flatten().nth(index).map(|(path, _)| path),
+        };
+        lists.iter().zip(sources).find_map(|(list, source)| {
+            let file_name = path_of(source).map(|x| ListDirectory::name(x)).unwrap_or_default();
+            (quick_add::list_name_matches(typed, &list.title) || quick_add::list_name_matches(typed, &file_name)).then_some(source)
+        })
+    }
+
+    //adds an item from a quick-add line, to the list it names or else the one showing
+    fn quick_add(&mut self, text: &str, now: DateTime<Local>) -> Result<(), String> {
+        let parsed = quick_add::parse(text, now).map_err(|e| e.to_string())?;
+        let source = match &parsed.list {
+            Some(typed) => self.find_source(typed).ok_or_else(|| format!("there's no list called \"{typed}\""))?,
+            None if self.loaded_list.is_some() => ListSource::Loaded,
+            None => return Err(String::from("open a list first, or name one with list:")),
+        };
+        //a list from the folder gets its id from the file as it is now, which could have had items added since it was read
+        if let ListSource::Directory(index) = source {
+            self.change_directory_list(index, |list| {
+                let mut item = list.new_item(String::new(), String::new());
+                parsed.fill_item(&mut item);
+                Command::Insert(DetachedItem::new(item, None)).apply(list).is_some()
+            });
+            return Ok(());
+        }
+        let list = self.source_list_mut(source).ok_or_else(|| String::from("that list is no longer open"))?;
+        let mut item = list.new_item(String::new(), String::new());
+        parsed.fill_item(&mut item);
+        self.apply_to_source(source, Command::Insert(DetachedItem::new(item, None)));
+        Ok(())
+    }
+
+    //one line of text that becomes a new item, with what it will be read as shown underneath
+    fn quick_add_ui(&mut self, ui: &mut Ui) {
+        let mut submit = false;
+        ui.horizontal(|ui| {
+            let input = self.text_inputs.entry(String::from("quick_add")).or_default();
+            let response = egui::TextEdit::singleline(input)
+                .hint_text("Pay rent tomorrow 9am #home !high every month")
+                .desired_width(360.0)
+                .show(ui)
+                .response
+                .on_hover_text("Dates, times, \"every …\", #tags, !priority and list:name are picked out; quote words to keep them in the name");
+            submit = response.lost_focus() && ui.input(|x| x.key_pressed(Key::Enter));
+            if ui.button("Add").clicked() {
+                submit = true;
+            }
+        });
+
+        let text = self.text_inputs.get("quick_add").cloned().unwrap_or_default();
+        if text.trim().is_empty() {
+            return;
+        }
+        let now = Local::now();
+        match quick_add::parse(&text, now) {
+            Ok(parsed) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new(&parsed.name).strong());
+                    if let Some(due) = parsed.due {
+                        ui.label(RichText::new(format!("Due {}", due.format(DATE_DISPLAY_FORMAT))).weak());
+                    }
+                    if let Some(recurrence) = &parsed.recurrence {
+                        ui.label(RichText::new(format!("Repeats {recurrence}")).weak());
+                    }
+                    if parsed.priority != Priority::None {
+                        ui.label(RichText::new(parsed.priority.label()).color(parsed.priority.color()));
+                    }
+                    for tag in &parsed.tags {
+                        ui.label(RichText::new(tag).color(Color32::LIGHT_BLUE));
+                    }
+                    if let Some(list) = &parsed.list {
+                        ui.label(RichText::new(format!("In {list}")).weak());
+                    }
+                });
+            }
+            Err(e) => {
+                ui.label(RichText::new(e.to_string()).color(Color32::RED));
+            }
+        }
+        if submit {
+            match self.quick_add(&text, now) {
+                Ok(()) => {
+                    self.text_inputs.insert(String::from("quick_add"), String::new());
+                }
+                Err(e) => self.error_message = Some(format!("Couldn't add that: {e}.")),
+            }
+        }
+    }
+
+    fn top_panel_frame(&self) -> egui::Frame {
+        Frame::none()
+        .fill(Color32::DARK_GRAY)
+    }
+
+    //clears the item text inputs so the Add Item popup starts empty
+    fn reset_item_inputs(text_inputs: &mut HashMap<String, String>) {
+        for key in ["item_name", "item_description", "item_due", "item_start", "item_tags", "item_recurrence"] {
+            text_inputs.insert(String::from(key), String::new());
+        }
+        text_inputs.retain(|key, _| !key.starts_with("field:"));
+    }
+
+    //shows the due/start inputs shared by the add and edit popups, returns false if either can't be read as a date
+    fn date_inputs(text_inputs: &mut HashMap<String, String>, ui: &mut Ui) -> bool {
+        ui.label("Due date (optional).");
+        egui::TextEdit::singleline(text_inputs.entry(String::from("item_due")).or_default())
+        .hint_text(DATE_INPUT_HINT)
+        .show(ui);
+
+        ui.label("Start date (optional).");
+        egui::TextEdit::singleline(text_inputs.entry(String::from("item_start")).or_default())
+        .hint_text(DATE_INPUT_HINT)
+        .show(ui);
+
+        let (due, start) = Self::dates_from_inputs(text_inputs);
+        let valid = due.is_ok() && start.is_ok();
+        if !valid {
+            ui.label(RichText::new(format!("Dates should look like {DATE_INPUT_HINT}, the time is optional."))
+                .color(Color32::RED));
+        }
+        valid
+    }
+
+    fn priority_input(priority: &mut Priority, ui: &mut Ui) {
+        ComboBox::from_label("Priority")
+            .selected_text(priority.label())
+            .show_ui(ui, |ui| {
+                for option in Priority::ALL {
+                    ui.selectable_value(priority, option, option.label());
+                }
+            });
+    }
+
+    //returns false if the rule can't be read
+    fn recurrence_input(text_inputs: &mut HashMap<String, String>, ui: &mut Ui) -> bool {
+        ui.label("Repeat (optional).");
+        egui::TextEdit::singleline(text_inputs.entry(String::from("item_recurrence")).or_default())
+        .hint_text("daily, weekdays, every 3 days, weekly on mon,thu, monthly on 15, or an RRULE")
+        .show(ui);
+
+        match Self::recurrence_from_inputs(text_inputs) {
+            Ok(_) => true,
+            Err(e) => {
+                ui.label(RichText::new(format!("Couldn't read the repeat rule: {e}.")).color(Color32::RED));
+                false
+            }
+        }
+    }
+
+    fn recurrence_from_inputs(text_inputs: &HashMap<String, String>) -> Result<Option<Recurrence>, RecurrenceError> {
+        match text_inputs.get("item_recurrence") {
+            Some(rule) if !rule.trim().is_empty() => rule.parse::<Recurrence>().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    //an editor for each of the list's custom fields, returns false if any value doesn't fit its field
+    //every value is kept as text under "field:<name>" until the item is saved
+    fn field_inputs(text_inputs: &mut HashMap<String, String>, fields: &[FieldDef], ui: &mut Ui) -> bool {
+        let mut valid = true;
+        for field in fields {
+            let input = text_inputs.entry(format!("field:{}", field.name)).or_default();
+            match &field.kind {
+                FieldKind::Bool => {
+                    let mut checked = field.parse(input) == Ok(Some(FieldValue::Bool(true)));
+                    if ui.checkbox(&mut checked, &field.name).changed() {
+                        *input = String::from(if checked { "yes" } else { "" });
+                    }
+                }
+                FieldKind::Choice(choices) => {
+                    ComboBox::from_label(&field.name)
+                        .selected_text(input.as_str())
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(input, String::new(), "(none)");
+                            for choice in choices {
+                                ui.selectable_value(input, choice.clone(), choice);
+                            }
+                        });
+                }
+                kind => {
+                    ui.label(format!("{} (optional).", field.name));
+                    let hint = match kind {
+                        FieldKind::Number => "0",
+                        FieldKind::Date => "2024-05-22",
+                        FieldKind::Url => "https://",
+                        _ => "",
+                    };
+                    egui::TextEdit::singleline(input)
+                    .hint_text(hint)
+                    .show(ui);
+                }
+            }
+            if let Err(e) = field.parse(text_inputs.get(&format!("field:{}", field.name)).map_or("", |x| x.as_str())) {
+                ui.label(RichText::new(format!("{e}.")).color(Color32::RED));
+                valid = false;
+            }
+        }
+        valid
+    }
+
+    fn field_values_from_inputs(text_inputs: &HashMap<String, String>, fields: &[FieldDef]) -> Result<BTreeMap<String, FieldValue>, FieldError> {
+        let mut values = BTreeMap::new();
+        for field in fields {
+            let input = text_inputs.get(&format!("field:{}", field.name)).map_or("", |x| x.as_str());
+            if let Some(value) = field.parse(input)? {
+                values.insert(field.name.clone(), value);
+            }
+        }
+        Ok(values)
+    }
+
+    fn tag_input(text_inputs: &mut HashMap<String, String>, ui: &mut Ui) {
+        ui.label("Tags, separated by spaces or commas (optional).");
+        egui::TextEdit::singleline(text_inputs.entry(String::from("item_tags")).or_default())
+        .hint_text("#errand @home")
+        .show(ui);
+    }
+
+    //draws an item that other items can be dragged onto; dropping on the top half moves the dragged item before it,
+    //dropping on the bottom half moves it after
+    fn item_ui(ui: &mut Ui, list: &TodoList, item: &TodoListItem, now: DateTime<Local>, with_subtasks: bool, actions: &mut Vec<ItemAction>) {
+        let response = ui.vertical(|ui| Self::item_contents_ui(ui, list, item, now, with_subtasks, actions)).response;
+        let rect = response.rect;
+        let pointer = ui.ctx().pointer_latest_pos();
+
+        //set when a search result is clicked
+        let scroll_key = Id::new(SCROLL_TO_ITEM_KEY);
+        if ui.ctx().data(|x| x.get_temp::<usize>(scroll_key)) == Some(item.id) {
+            response.scroll_to_me(Some(Align::Center));
+            ui.ctx().data_mut(|x| x.remove::<usize>(scroll_key));
+        }
+
+        if let (Some(dragged), Some(pointer)) = (response.dnd_hover_payload::<usize>(), pointer) {
+            if *dragged != item.id {
+                let y = if pointer.y < rect.center().y { rect.top() } else { rect.bottom() };
+                ui.painter().hline(rect.x_range(), y, Stroke::new(2.0, Color32::YELLOW));
+            }
+        }
+
+        //subtasks are drawn inside this item, so a drop on one of them has already been taken by the time we get here
+        if let (Some(dragged), Some(pointer)) = (response.dnd_release_payload::<usize>(), pointer) {
+            if pointer.y < rect.center().y {
+                actions.push(ItemAction::MoveBefore(*dragged, item.id));
+            } else {
+                actions.push(ItemAction::MoveAfter(*dragged, item.id));
+            }
+        }
+    }
+
+    //text with the given byte ranges picked out, for search results
+    fn highlighted_text(ui: &Ui, text: &str, ranges: &[std::ops::Range<usize>]) -> text::LayoutJob {
+        let normal = TextFormat { color: ui.visuals().text_color(), ..Default::default() };
+        let highlight = TextFormat { color: Color32::BLACK, background: Color32::GOLD, ..Default::default() };
+        let mut job = text::LayoutJob::default();
+        let mut done = 0;
+        for range in ranges {
+            job.append(&text[done..range.start], 0.0, normal.clone());
+            job.append(&text[range.clone()], 0.0, highlight.clone());
+            done = range.end;
+        }
+        job.append(&text[done..], 0.0, normal);
+        job
+    }
+
+    //draws one item and, inside a collapsible section, all of its subtasks unless with_subtasks is false
+    //items waiting on unfinished blockers are greyed out
+    fn item_contents_ui(ui: &mut Ui, list: &TodoList, item: &TodoListItem, now: DateTime<Local>, with_subtasks: bool, actions: &mut Vec<ItemAction>) {
+        let overdue = item.is_overdue(now);
+        let waiting_on = list.open_blockers(item.id);
+        ui.horizontal(|ui| {
+            ui.add(Label::new(RichText::new("☰").weak()).sense(Sense::drag()))
+                .on_hover_text("Drag onto another item to move this one")
+                .dnd_set_drag_payload(item.id);
+
+            let mut completed = item.completed;
+            if ui.checkbox(&mut completed, "").on_hover_text("Complete").changed() {
+                actions.push(ItemAction::SetCompleted(item.id, completed));
+            }
+            if let Some(status) = Self::status_menu(ui, list, item) {
+                actions.push(ItemAction::SetStatus(item.id, status));
+            }
+            if item.priority != Priority::None {
+                ui.label(RichText::new(item.priority.label()).color(item.priority.color()));
+            }
+            let name = if !waiting_on.is_empty() {
+                RichText::new(&item.name).weak()
+            } else if overdue {
+                RichText::new(&item.name).color(Color32::RED)
+            } else {
+                RichText::new(&item.name)
+            };
+            ui.add(Label::new(name).sense(Sense::click())).context_menu(|ui| {
+                if ui.button("Move to another list…").clicked() {
+                    actions.push(ItemAction::Transfer { id: item.id, copy: false });
+                    ui.close_menu();
+                }
+                if ui.button("Copy to another list…").clicked() {
+                    actions.push(ItemAction::Transfer { id: item.id, copy: true });
+                    ui.close_menu();
+                }
+                if ui.button("Save as template…").clicked() {
+                    actions.push(ItemAction::SaveTemplate(item.id));
+                    ui.close_menu();
+                }
+            });
+            if let Some(due) = item.due {
+                let due_text = RichText::new(format!("Due {}", due.format(DATE_DISPLAY_FORMAT)));
+                ui.label(if overdue { due_text.color(Color32::RED).strong() } else { due_text.weak() });
+            }
+            if item.carried_days > 0 {
+                let days = if item.carried_days == 1 { String::from("1 day") } else { format!("{} days", item.carried_days) };
+                ui.label(RichText::new(format!("Carried over {days}")).color(Color32::from_rgb(255, 165, 0)))
+                    .on_hover_text("Left undone when the day ended");
+            }
+            if let ListKind::Habit { reset_at } = list.kind() {
+                let streak = kinds::streak(&item.completion_log, *reset_at, now);
+                if streak > 0 {
+                    let days = if streak == 1 { String::from("1 day") } else { format!("{streak} days") };
+                    ui.label(RichText::new(format!("Streak: {days}")).color(Color32::LIGHT_GREEN));
+                }
+            }
+            if let Some(start) = item.start {
+                if start > now {
+                    ui.label(RichText::new(format!("Starts {}", start.format(DATE_DISPLAY_FORMAT))).weak());
+                }
+            }
+            if let Some(completed_at) = item.completed_at {
+                let log: Vec<String> = item.completion_log.iter()
+                    .map(|x| format!("{} {}", x.change.label(), x.at.format(DATE_DISPLAY_FORMAT)))
+                    .collect();
+                ui.label(RichText::new(format!("Completed {}", completed_at.format(DATE_DISPLAY_FORMAT))).weak())
+                    .on_hover_text(log.join("\n"));
+            }
+            if let Some(recurrence) = &item.recurrence {
+                ui.label(RichText::new(format!("Repeats {recurrence}")).weak())
+                    .on_hover_text(format!("Done {} times so far", item.completion_history.len()));
+            }
+            for tag in &item.tags {
+                if ui.small_button(RichText::new(tag).color(Color32::LIGHT_BLUE)).on_hover_text("Filter by this tag").clicked() {
+                    actions.push(ItemAction::FilterByTag(tag.clone()));
+                }
+            }
+        });
+        if !item.field_values.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                for field in list.fields() {
+                    match item.field_value(&field.name) {
+                        Some(FieldValue::Url(url)) => {
+                            ui.hyperlink_to(&field.name, url);
+                        }
+                        Some(value) => {
+                            ui.label(RichText::new(format!("{}: {value}", field.name)).weak());
+                        }
+                        None => {}
+                    }
+                }
+            });
+        }
+        if !waiting_on.is_empty() {
+            let names: Vec<&str> = waiting_on.iter().map(|x| x.name.as_str()).collect();
+            ui.label(RichText::new(format!("Waiting on: {}", names.join(", "))).weak().italics());
+        }
+        ui.label(&item.description);
+        ui.horizontal(|ui| {
+            if ui.button("Edit").clicked() {
+                actions.push(ItemAction::Edit(item.id));
+            }
+            ui.menu_button("Blocked by…", |ui| {
+                for other in list.all_items() {
+                    if other.id == item.id {
+                        continue;
+                    }
+                    let mut blocks = item.blocked_by.contains(&other.id);
+                    if ui.checkbox(&mut blocks, &other.name).changed() {
+                        actions.push(ItemAction::SetBlocker { id: item.id, blocker_id: other.id, blocks });
+                    }
+                }
+            });
+            if ui.button("Add subtask").clicked() {
+                actions.push(ItemAction::AddSubtask(item.id));
+            }
+            if ui.button("Delete").clicked() {
+                actions.push(ItemAction::Delete(item.id));
+            }
+        });
+
+        if with_subtasks && !item.subtasks.is_empty() {
+            let (done, total) = item.progress();
+            CollapsingHeader::new(format!("Subtasks ({done}/{total} done)"))
+                .id_source(("subtasks", item.id))
+                .default_open(true)
+                .show(ui, |ui| {
+                    for subtask in &item.subtasks {
+                        Self::item_ui(ui, list, subtask, now, true, actions);
+                    }
+                });
+        }
+    }
+
+    //the item's status, as a button that opens a menu of the list's statuses; returns the one picked, if any
+    //hovering shows when the item moved between statuses
+    fn status_menu(ui: &mut Ui, list: &TodoList, item: &TodoListItem) -> Option<String> {
+        let current = list.status_of(item)?;
+        let text = RichText::new(&current.name);
+        let mut picked = None;
+        let response = ui.menu_button(if current.done { text.color(Color32::LIGHT_GREEN) } else { text }, |ui| {
+            for status in list.statuses() {
+                if ui.selectable_label(status == current, &status.name).clicked() {
+                    if status != current {
+                        picked = Some(status.name.clone());
+                    }
+                    ui.close_menu();
+                }
+            }
+        }).response;
+        if !item.status_log.is_empty() {
+            let log: Vec<String> = item.status_log.iter()
+                .map(|x| format!("{} → {} {}", x.from, x.to, x.at.format(DATE_DISPLAY_FORMAT)))
+                .collect();
+            response.on_hover_text(log.join("\n"));
+        }
+        picked
+    }
+
+    //picks the kind of list, its reset time or columns, and whether it's shown as a list or a board;
+    //returns true if anything changed
+    fn list_kind_ui(ui: &mut Ui, list: &mut TodoList, text_inputs: &mut HashMap<String, String>) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Kind of list:");
+            let mut label = list.kind().label();
+            ComboBox::from_id_source("list_kind")
+                .selected_text(label)
+                .show_ui(ui, |ui| {
+                    for option in ListKind::LABELS {
+                        ui.selectable_value(&mut label, option, option);
+                    }
+                });
+            if label != list.kind().label() {
+                list.set_kind(ListKind::from_label(label).expect("label is only set from ListKind::LABELS"), Local::now());
+                changed = true;
+            }
+
+            match list.kind().clone() {
+                ListKind::Daily { reset_at } | ListKind::Habit { reset_at } => {
+                    ui.label("resets at");
+                    let mut hour = reset_at.hour();
+                    let mut minute = reset_at.minute();
+                    ui.add(DragValue::new(&mut hour).clamp_range(0..=23));
+                    ui.label(":");
+                    ui.add(DragValue::new(&mut minute).clamp_range(0..=59));
+                    let new_time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or(reset_at);
+                    if new_time != reset_at {
+                        list.set_kind(list.kind().with_reset_time(new_time), Local::now());
+                        changed = true;
+                    }
+                }
+                ListKind::Kanban { columns } => {
+                    let input = text_inputs.entry(String::from("list_columns")).or_default();
+                    egui::TextEdit::singleline(input)
+                    .hint_text(columns.join(", "))
+                    .desired_width(200.0)
+                    .show(ui);
+                    let parsed = kinds::parse_columns(input);
+                    if ui.add_enabled(parsed.is_some(), Button::new("Set columns"))
+                        .on_hover_text("At least two, separated by commas; each column is a status, and new ones count as done if they're last")
+                        .clicked() {
+                        list.set_kind(ListKind::Kanban { columns: parsed.unwrap_or_default() }, Local::now());
+                        input.clear();
+                        changed = true;
+                    }
+                }
+                ListKind::Standard | ListKind::Checklist => {}
+            }
+
+            ui.separator();
+            ui.label("Show as:");
+            for view in ListView::ALL {
+                if ui.selectable_label(list.view() == view, view.label()).clicked() && list.view() != view {
+                    list.set_view(view);
+                    changed = true;
+                }
+            }
+        });
+        changed
+    }
+
+    //the list's top-level items as cards in the board's columns; cards are dragged from one column to another, and
+    //each column can be collapsed or given a WIP limit, past which it's drawn in red and takes no more cards
+    fn board_ui(ui: &mut Ui, list: &TodoList, now: DateTime<Local>, actions: &mut Vec<ItemAction>) {
+        let columns = list.board_columns();
+        let collapsed = columns.iter().filter(|x| list.board().is_collapsed(x)).count();
+        let expanded = columns.len() - collapsed;
+        let spacing = ui.spacing().item_spacing.x * columns.len().saturating_sub(1) as f32;
+        let free = ui.available_width() - spacing - COLLAPSED_COLUMN_WIDTH * collapsed as f32;
+        let width = (free / expanded.max(1) as f32).max(MIN_COLUMN_WIDTH);
+        ScrollArea::horizontal().id_source("board").show(ui, |ui| {
+            ui.horizontal_top(|ui| {
+                for column in columns {
+                    let cards = list.board_cards(column);
+                    let limit = list.board().wip_limit(column);
+                    let count = match limit {
+                        Some(limit) => format!("{}/{limit}", cards.len()),
+                        None => cards.len().to_string(),
+                    };
+                    let over_limit = limit.is_some_and(|x| cards.len() > x);
+                    let header = |text: String| if over_limit { RichText::new(text).color(Color32::RED) } else { RichText::new(text) };
+
+                    //collapsed columns still take cards dropped on them
+                    let (_, dropped) = ui.dnd_drop_zone::<usize, ()>(Frame::group(ui.style()), |ui| {
+                        if list.board().is_collapsed(column) {
+                            ui.set_width(COLLAPSED_COLUMN_WIDTH - 2.0 * ui.spacing().item_spacing.x);
+                            if ui.small_button("⏵").on_hover_text("Expand").clicked() {
+                                actions.push(ItemAction::CollapseColumn(String::from(column), false));
+                            }
+                            ui.label(header(count)).on_hover_text(column);
+                            return;
+                        }
+                        ui.set_width(width);
+                        ui.horizontal(|ui| {
+                            if ui.small_button("⏴").on_hover_text("Collapse").clicked() {
+                                actions.push(ItemAction::CollapseColumn(String::from(column), true));
+                            }
+                            ui.heading(header(format!("{column} ({count})")));
+                            ui.menu_button("⚙", |ui| {
+                                let mut limited = limit.is_some();
+                                let mut value = limit.unwrap_or(cards.len().max(1));
+                                ui.horizontal(|ui| {
+                                    ui.checkbox(&mut limited, "WIP limit");
+                                    ui.add_enabled(limited, DragValue::new(&mut value).clamp_range(1..=999));
+                                });
+                                let new_limit = limited.then_some(value);
+                                if new_limit != limit {
+                                    actions.push(ItemAction::SetWipLimit(String::from(column), new_limit));
+                                }
+                            });
+                        });
+                        for item in cards {
+                            ui.dnd_drag_source(Id::new(("board_card", item.id)), item.id, |ui| {
+                                Self::card_ui(ui, list, item, now, actions);
+                            });
+                        }
+                    });
+                    if let Some(id) = dropped {
+                        if list.get_item_by_id(*id).is_some_and(|x| list.board_column_of(x) != Some(column)) {
+                            actions.push(ItemAction::MoveToColumn(*id, String::from(column)));
+                        }
+                    }
+                }
+            });
+        });
+    }
+
+    fn card_ui(ui: &mut Ui, list: &TodoList, item: &TodoListItem, now: DateTime<Local>, actions: &mut Vec<ItemAction>) {
+        Frame::group(ui.style()).fill(ui.visuals().faint_bg_color).show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            let name = RichText::new(&item.name).strong();
+            ui.label(if item.is_overdue(now) { name.color(Color32::RED) } else { name })
+                .on_hover_text("Drag to another column to move it");
+            ui.horizontal_wrapped(|ui| {
+                if let Some(status) = Self::status_menu(ui, list, item) {
+                    actions.push(ItemAction::SetStatus(item.id, status));
+                }
+                if item.priority != Priority::None {
+                    ui.label(RichText::new(item.priority.label()).color(item.priority.color()));
+                }
+                if let Some(due) = item.due {
+                    ui.label(RichText::new(format!("Due {}", due.format(DATE_DISPLAY_FORMAT))).weak());
+                }
+                if !item.subtasks.is_empty() {
+                    let (done, total) = item.progress();
+                    ui.label(RichText::new(format!("{done}/{total} subtasks")).weak());
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.small_button("Edit").clicked() {
+                    actions.push(ItemAction::Edit(item.id));
+                }
+                if ui.small_button("Delete").clicked() {
+                    actions.push(ItemAction::Delete(item.id));
+                }
+            });
+        });
+    }
+
+    //copies everything from the add/edit popup inputs onto the item; call date_inputs, recurrence_input and field_inputs
+    //first so the inputs are known to be valid
+    fn fill_item_from_inputs(item: &mut TodoListItem, text_inputs: &HashMap<String, String>, priority: Priority, fields: &[FieldDef]) {
+        let empty = String::new();
+        let (due, start) = Self::dates_from_inputs(text_inputs);
+        item.name = text_inputs.get("item_name").unwrap_or(&empty).clone();
+        item.description = text_inputs.get("item_description").unwrap_or(&empty).clone();
+        item.due = due.unwrap_or_default();
+        item.start = start.unwrap_or_default();
+        item.priority = priority;
+        item.tags = parse_tags(text_inputs.get("item_tags").unwrap_or(&empty));
+        item.recurrence = Self::recurrence_from_inputs(text_inputs).unwrap_or_default();
+        item.field_values = Self::field_values_from_inputs(text_inputs, fields).unwrap_or_default();
+    }
+
+    fn dates_from_inputs(text_inputs: &HashMap<String, String>) -> (DateInput, DateInput) {
+        let empty = String::new();
+        let due = parse_date_input(text_inputs.get("item_due").unwrap_or(&empty), NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+        let start = parse_date_input(text_inputs.get("item_start").unwrap_or(&empty), NaiveTime::MIN);
+        (due, start)
+    }
+
+}
+
+//things clicked on while the items were being drawn, applied once the drawing is done
+//so the list isn't borrowed while it changes
+enum ItemAction {
+    SetCompleted(usize, bool),
+    SetStatus(usize, String),
+    Edit(usize),
+    AddSubtask(usize),
+    Delete(usize),
+    SaveTemplate(usize), //opens the template popup to save the item (and its subtasks) as an item template
+    FilterByTag(String), //toggles the tag in the filter bar
+    SetBlocker { id: usize, blocker_id: usize, blocks: bool },
+    Transfer { id: usize, copy: bool }, //opens the list picker to move or copy the item to another list
+    MoveBefore(usize, usize), //(dragged item, item it was dropped on)
+    MoveAfter(usize, usize),
+    MoveToColumn(usize, String), //on the board view
+    SetWipLimit(String, Option<usize>), //(column, limit)
+    CollapseColumn(String, bool),
+    ReopenAll, //unticks every item, to use a finished checklist again
+}
+
+impl eframe::App for TodoUi {
+    // framework calls this to save state before shutdown
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.remember_open_lists();
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let mut debug_mode = false;
+        let args: Vec<String> = env::args().collect();
+        if args.len() > 1 && args[1] == "debug" {
+            debug_mode = true;
+        }
+
+        let top_panel = egui::TopBottomPanel::top("top_panel");
+
+        top_panel
+            .frame(self.top_panel_frame())
+            .show(ctx, |ui| {
+                ui.label(RichText::new("Todo List Manager")
+                    .color(Color32::YELLOW)
+                    .size(36.0)
+                    .family(FontFamily::Name("DM_Sans".into()))
+                ); 
+                ui.label(RichText::new("Catchy subtitle to be determined.")
+                    .family(FontFamily::Monospace));
+            });
+
+        //changes made through the undo history last frame
+        if self.history.take_changed() {
+            self.dirty = true;
+        }
+        let now = Local::now();
+        self.roll_over_lists(now);
+        //undoing can reopen a finished checklist
+        if let Some(list) = self.loaded_list.as_mut() {
+            if list.update_checklist(now) {
+                self.dirty = true;
+            }
+        }
+        self.close_missing_item_popups();
+
+        egui::TopBottomPanel::top("top_panel_2").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Options: ");
+
+                if ui.button("New List").clicked() {
+                    self.open_list(TodoList::new(String::from("New Todo List")), None);
+                }
+
+                ui.menu_button("New from template…", |ui| {
+                    let templates_dir = self.templates_root();
+                    match templates::template_names(&templates_dir) {
+                        Ok(names) if names.is_empty() => {
+                            ui.label("No templates yet, use \"Save as Template\" on a list first.");
+                        }
+                        Ok(names) => {
+                            for (name, path) in names {
+                                if ui.button(&name).clicked() {
+                                    match templates::load_template(&path) {
+                                        Ok(template) => self.open_list(templates::instantiate(&template, Local::now()), None),
+                                        Err(e) => self.error_message = Some(format!("Couldn't load the template \"{name}\": {e}.")),
+                                    }
+                                    ui.close_menu();
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            ui.label(RichText::new(format!("Couldn't read the templates directory: {e}.")).color(Color32::RED));
+                        }
+                    }
+                    ui.separator();
+                    if ui.button("Change templates folder…").on_hover_text(templates_dir.display().to_string()).clicked() {
+                        if let Some(folder) = rfd::FileDialog::new().set_directory(&templates_dir).pick_folder() {
+                            self.templates_dir = Some(folder);
+                        }
+                        ui.close_menu();
+                    }
+                });
+
+                if self.loaded_list.is_some() {
+                    ui.menu_button("Add from template…", |ui| {
+                        let templates_dir = self.templates_root().join(templates::ITEM_TEMPLATES_DIR);
+                        match templates::template_names(&templates_dir) {
+                            Ok(names) if names.is_empty() => {
+                                ui.label("No item templates yet, right-click an item's name to save one.");
+                            }
+                            Ok(names) => {
+                                for (name, path) in names {
+                                    if ui.button(&name).clicked() {
+                                        match templates::load_template(&path) {
+                                            Ok(template) => {
+                                                let loaded_list = self.loaded_list.as_mut().expect("Add from template is only shown when a list is loaded");
+                                                let commands = templates::instantiate_items(&template, loaded_list, Local::now()).into_iter()
+                                                    .map(|item| Command::Insert(DetachedItem::new(item, None)))
+                                                    .collect();
+                                                self.history.apply(loaded_list, Command::Batch(commands));
+                                            }
+                                            Err(e) => self.error_message = Some(format!("Couldn't load the template \"{name}\": {e}.")),
+                                        }
+                                        ui.close_menu();
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                ui.label(RichText::new(format!("Couldn't read the templates directory: {e}.")).color(Color32::RED));
+                            }
+                        }
+                    });
+                }
+
+                if ui.button("Load List From File").clicked() {
+                    let empty_path = PathBuf::new();
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("json", &["json"])
+                        .set_directory(match &self.current_path {
+                            Some(p) => p,
+                            None => &empty_path,
+                        })
+                        .pick_file() {
+                            let load_result = TodoList::from_file(&path);
+                            match load_result {
+                                Ok(list) => {
+                                    self.open_list(list, Some(path));
+                                }
+                                Err(e) => {
+                                    self.error_message = Some(format!("Couldn't load the list from {}: {e}.", path.display()));
+                                }
+                            };
+                    }
+                }
+
+                if let Some(list) = &self.loaded_list {
+                    if ui.button(format!("Trash ({})", list.trash().len())).clicked() {
+                        frame.storage_mut().expect("storage_mut returned None").set_string("trash_popup_status", String::from("open"));
+                    }
+                }
+
+                if let Some(list) = &self.loaded_list {
+                    if ui.button(format!("Archive ({})", list.archive().len())).clicked() {
+                        frame.storage_mut().expect("storage_mut returned None").set_string("archive_popup_status", String::from("open"));
+                    }
+                }
+
+                if let Some(list) = &self.loaded_list {
+                    if ui.button("Save as Template").clicked() {
+                        self.text_inputs.insert(String::from("template_name"), list.title.clone());
+                        let store = frame.storage_mut().expect("storage_mut returned None");
+                        store.set_string("save_template_item_id", String::new());
+                        store.set_string("save_template_popup_status", String::from("open"));
+                    }
+                }
+
+                if self.loaded_list.is_some() && ui.button("Custom Fields").clicked() {
+                    frame.storage_mut().expect("storage_mut returned None").set_string("fields_popup_status", String::from("open"));
+                }
+
+                if self.loaded_list.is_some() && ui.button("Statuses").clicked() {
+                    frame.storage_mut().expect("storage_mut returned None").set_string("statuses_popup_status", String::from("open"));
+                }
+
+                if let Some(path) = self.current_path.clone() {
+                    if self.loaded_list.is_some() && ui.add_enabled(self.dirty, Button::new("Save")).on_hover_text(path.display().to_string()).clicked() {
+                        self.save_loaded_list(path);
+                    }
+                }
+
+                if self.loaded_list.is_some() && ui.button("Save List To File").clicked() {
+                    let empty_path = PathBuf::new();
+                    if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("json", &["json"])
+                    .set_directory(match &self.current_path {
+                        Some(p) => p,
+                        None => &empty_path,
+                    })
+                    .save_file() {
+                        self.save_loaded_list(path);
+                    }
+                }
+
+                ui.separator();
+                ui.label("Search:");
+                egui::TextEdit::singleline(self.text_inputs.entry(String::from("search")).or_default())
+                .hint_text("Names and descriptions")
+                .desired_width(160.0)
+                .show(ui);
+            });
+        });
+
+        //handling for popup that lists deleted items
+        let trash_popup = egui::Window::new("Trash")
+            .title_bar(true)
+            .default_open(true);
+        if let Some(store) = frame.storage_mut() {
+            if store.get_string("trash_popup_status").is_some_and(|x| x == "open") {
+                trash_popup.show(ctx, |ui| {
+                    match self.loaded_list.as_mut() {
+                        Some(loaded_list) => {
+                            ui.label(format!("Deleted items are kept for {TRASH_RETENTION_DAYS} days."));
+                            let mut to_restore = None;
+                            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                                for trashed in loaded_list.trash().iter().rev() {
+                                    ui.horizontal(|ui| {
+                                        ui.label(&trashed.item().name);
+                                        ui.label(RichText::new(format!("deleted {}", trashed.deleted_at.format(DATE_DISPLAY_FORMAT))).weak());
+                                        if ui.button("Restore").clicked() {
+                                            to_restore = Some(trashed.item().id);
+                                        }
+                                    });
+                                }
+                            });
+                            if let Some(id) = to_restore {
+                                self.history.apply(loaded_list, Command::Restore { id });
+                            }
+                            if loaded_list.trash().is_empty() {
+                                ui.label("The trash is empty.");
+                            } else if ui.button("Empty trash").on_hover_text("This can't be undone").clicked() {
+                                loaded_list.empty_trash();
+                                self.dirty = true;
+                            }
+                        }
+                        None => {
+                            ui.label("No list is loaded.");
+                        }
+                    }
+                    if ui.button("Close").clicked() {
+                        store.set_string("trash_popup_status", String::from("closed"));
+                    }
+                });
+            }
+        }
+
+        //handling for popup that browses archived items
+        let archive_popup = egui::Window::new("Archive")
+            .title_bar(true)
+            .default_open(true);
+        if let Some(store) = frame.storage_mut() {
+            if store.get_string("archive_popup_status").is_some_and(|x| x == "open") {
+                archive_popup.show(ctx, |ui| {
+                    match self.loaded_list.as_mut() {
+                        Some(loaded_list) => {
+                            ui.horizontal(|ui| {
+                                ui.label("Search:");
+                                ui.text_edit_singleline(self.text_inputs.entry(String::from("archive_search")).or_default());
+                            });
+                            let query = self.text_inputs.get("archive_search").cloned().unwrap_or_default();
+                            let mut to_unarchive = None;
+                            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                                for archived in loaded_list.search_archive(&query).into_iter().rev() {
+                                    ui.horizontal(|ui| {
+                                        ui.label(&archived.item().name);
+                                        ui.label(RichText::new(format!("archived {}", archived.archived_at.format(DATE_DISPLAY_FORMAT))).weak());
+                                        if ui.button("Un-archive").clicked() {
+                                            to_unarchive = Some(archived.item().id);
+                                        }
+                                    });
+                                }
+                            });
+                            if let Some(id) = to_unarchive {
+                                self.history.apply(loaded_list, Command::Unarchive { id });
+                            }
+                            if loaded_list.archive().is_empty() {
+                                ui.label("Nothing has been archived.");
+                            }
+                        }
+                        None => {
+                            ui.label("No list is loaded.");
+                        }
+                    }
+                    if ui.button("Close").clicked() {
+                        store.set_string("archive_popup_status", String::from("closed"));
+                    }
+                });
+            }
+        }
+
+        //handling for popup that edits the list's custom fields
+        let fields_popup = egui::Window::new("Custom Fields")
+            .title_bar(true)
+            .default_open(true);
+        if let Some(store) = frame.storage_mut() {
+            if store.get_string("fields_popup_status").is_some_and(|x| x == "open") {
+                fields_popup.show(ctx, |ui| {
+                    match self.loaded_list.as_mut() {
+                        Some(loaded_list) => {
+                            let mut result = Ok(());
+                            let mut undo = None;
+                            let mut to_remove = None;
+                            for field in loaded_list.fields().to_vec() {
+                                ui.horizontal(|ui| {
+                                    let kind = match &field.kind {
+                                        FieldKind::Choice(choices) => format!("Choice of {}", choices.join(", ")),
+                                        kind => String::from(kind.label()),
+                                    };
+                                    ui.label(format!("{} ({kind})", field.name));
+                                    let rename_key = format!("field_rename:{}", field.name);
+                                    egui::TextEdit::singleline(self.text_inputs.entry(rename_key.clone()).or_default())
+                                    .hint_text("New name")
+                                    .desired_width(100.0)
+                                    .show(ui);
+                                    if ui.button("Rename").clicked() {
+                                        let new_name = self.text_inputs.remove(&rename_key).unwrap_or_default();
+                                        result = loaded_list.rename_field(&field.name, &new_name);
+                                        undo = Some(Command::RenameField { name: String::from(new_name.trim()), new_name: field.name.clone() });
+                                    }
+                                    if ui.button("Remove").on_hover_text("Also removes this field's values from every item").clicked() {
+                                        to_remove = Some(field.name.clone());
+                                    }
+                                });
+                            }
+                            if let Some(name) = to_remove {
+                                self.history.apply(loaded_list, Command::RemoveField { name });
+                            }
+
+                            ui.separator();
+                            ui.horizontal(|ui| {
+                                egui::TextEdit::singleline(self.text_inputs.entry(String::from("new_field_name")).or_default())
+                                .hint_text("Field name")
+                                .desired_width(100.0)
+                                .show(ui);
+                                let kind_input = self.text_inputs.entry(String::from("new_field_kind")).or_insert_with(|| String::from(FieldKind::LABELS[0]));
+                                ComboBox::from_id_source("new_field_kind")
+                                    .selected_text(kind_input.as_str())
+                                    .show_ui(ui, |ui| {
+                                        for label in FieldKind::LABELS {
+                                            ui.selectable_value(kind_input, String::from(label), label);
+                                        }
+                                    });
+                            });
+                            let is_choice = self.text_inputs.get("new_field_kind").is_some_and(|x| x == "Choice");
+                            if is_choice {
+                                egui::TextEdit::singleline(self.text_inputs.entry(String::from("new_field_choices")).or_default())
+                                .hint_text("Choices, separated by commas")
+                                .show(ui);
+                            }
+                            if ui.button("Add field").clicked() {
+                                let choices = self.text_inputs.get("new_field_choices").map_or("", |x| x.as_str())
+                                    .split(',')
+                                    .map(|x| String::from(x.trim()))
+                                    .filter(|x| !x.is_empty())
+                                    .collect();
+                                let kind = FieldKind::from_label(self.text_inputs.get("new_field_kind").map_or("", |x| x.as_str()), choices)
+                                    .expect("new_field_kind is only set from FieldKind::LABELS");
+                                let name = self.text_inputs.get("new_field_name").cloned().unwrap_or_default();
+                                result = loaded_list.add_field(FieldDef::new(&name, kind));
+                                if result.is_ok() {
+                                    self.text_inputs.insert(String::from("new_field_name"), String::new());
+                                    self.text_inputs.insert(String::from("new_field_choices"), String::new());
+                                }
+                                undo = Some(Command::RemoveField { name: String::from(name.trim()) });
+                            }
+
+                            //the changes above were made straight on the list, so their errors can be shown
+                            match (result, undo) {
+                                (Ok(()), Some(undo)) => self.history.record(undo),
+                                (Ok(()), None) => {}
+                                (Err(e), _) => self.error_message = Some(format!("Couldn't change the fields: {e}.")),
+                            }
+                        }
+                        None => {
+                            ui.label("No list is loaded.");
+                        }
+                    }
+                    if ui.button("Close").clicked() {
+                        store.set_string("fields_popup_status", String::from("closed"));
+                    }
+                });
+            }
+        }
+
+        //handling for popup that edits the list's statuses
+        let statuses_popup = egui::Window::new("Statuses")
+            .title_bar(true)
+            .default_open(true);
+        if let Some(store) = frame.storage_mut() {
+            if store.get_string("statuses_popup_status").is_some_and(|x| x == "open") {
+                statuses_popup.show(ctx, |ui| {
+                    match self.loaded_list.as_mut() {
+                        Some(loaded_list) => {
+                            let mut result = Ok(());
+                            let mut undo = None;
+                            let mut to_remove = None;
+                            let mut to_move = None;
+                            let statuses = loaded_list.statuses().to_vec();
+                            for (index, status) in statuses.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    if ui.add_enabled(index > 0, Button::new("⏶").small()).clicked() {
+                                        to_move = Some((status.name.clone(), index - 1));
+                                    }
+                                    if ui.add_enabled(index + 1 < statuses.len(), Button::new("⏷").small()).clicked() {
+                                        to_move = Some((status.name.clone(), index + 1));
+                                    }
+                                    ui.label(format!("{} ({})", status.name, status.category_label()));
+                                    let rename_key = format!("status_rename:{}", status.name);
+                                    egui::TextEdit::singleline(self.text_inputs.entry(rename_key.clone()).or_default())
+                                    .hint_text("New name")
+                                    .desired_width(100.0)
+                                    .show(ui);
+                                    if ui.button("Rename").clicked() {
+                                        let new_name = self.text_inputs.remove(&rename_key).unwrap_or_default();
+                                        result = loaded_list.rename_status(&status.name, &new_name);
+                                        undo = Some(Command::RenameStatus { name: String::from(new_name.trim()), new_name: status.name.clone() });
+                                    }
+                                    if ui.button("Remove").on_hover_text("Items in this status go to the first status of the same kind").clicked() {
+                                        to_remove = Some(status.name.clone());
+                                    }
+                                });
+                            }
+                            if let Some((name, index)) = to_move {
+                                self.history.apply(loaded_list, Command::MoveStatus { name, index });
+                            }
+                            if let Some(name) = to_remove {
+                                //checked first, since the command can't say why it didn't work
+                                result = loaded_list.can_remove_status(&name);
+                                if result.is_ok() {
+                                    self.history.apply(loaded_list, Command::RemoveStatus { name, at: Local::now() });
+                                }
+                            }
+                            ui.label(RichText::new("Ticking an item moves it to the first done status, and unticking it to the first open one.").weak());
+
+                            ui.separator();
+                            ui.horizontal(|ui| {
+                                egui::TextEdit::singleline(self.text_inputs.entry(String::from("new_status_name")).or_default())
+                                .hint_text("Status name")
+                                .desired_width(100.0)
+                                .show(ui);
+                                let category_input = self.text_inputs.entry(String::from("new_status_category")).or_insert_with(|| String::from("Open"));
+                                ComboBox::from_id_source("new_status_category")
+                                    .selected_text(category_input.as_str())
+                                    .show_ui(ui, |ui| {
+                                        for label in ["Open", "Done"] {
+                                            ui.selectable_value(category_input, String::from(label), label);
+                                        }
+                                    });
+                            });
+                            if ui.button("Add status").clicked() {
+                                let name = self.text_inputs.get("new_status_name").cloned().unwrap_or_default();
+                                let done = self.text_inputs.get("new_status_category").is_some_and(|x| x == "Done");
+                                result = loaded_list.add_status(StatusDef::new(&name, done));
+                                if result.is_ok() {
+                                    self.text_inputs.insert(String::from("new_status_name"), String::new());
+                                }
+                                undo = Some(Command::RemoveStatus { name: String::from(name.trim()), at: Local::now() });
+                            }
+
+                            //renames and additions are made straight on the list, so their errors can be shown
+                            match (result, undo) {
+                                (Ok(()), Some(undo)) => self.history.record(undo),
+                                (Ok(()), None) => {}
+                                (Err(e), _) => self.error_message = Some(format!("Couldn't change the statuses: {e}.")),
+                            }
+                        }
+                        None => {
+                            ui.label("No list is loaded.");
+                        }
+                    }
+                    if ui.button("Close").clicked() {
+                        store.set_string("statuses_popup_status", String::from("closed"));
+                    }
+                });
+            }
+        }
+
+        //handling for popup that saves the list as a template
+        let save_template_popup = egui::Window::new("Save as Template")
+            .title_bar(true)
+            .default_open(true);
+        if let Some(store) = frame.storage_mut() {
+            if store.get_string("save_template_popup_status").is_some_and(|x| x == "open") {
+                save_template_popup.show(ctx, |ui| {
+                    ui.label("Name the template. Titles, names and descriptions can use {date}, {weekday}, {time}, {month} and {year}.");
+                    egui::TextEdit::singleline(self.text_inputs.entry(String::from("template_name")).or_default())
+                    .hint_text("Template Name")
+                    .show(ui);
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Save Template").clicked() {
+                            let name = self.text_inputs.get("template_name").cloned().unwrap_or_default();
+                            let list = self.loaded_list.as_ref().expect("Save as Template is only shown when a list is loaded");
+                            //set when the template is for one item rather than the whole list
+                            let item_id = store.get_string("save_template_item_id").and_then(|x| x.parse::<usize>().ok());
+                            let saved = match item_id {
+                                Some(id) => templates::save_item_template(&self.templates_root(), &name, list, id),
+                                None => templates::save_template(&self.templates_root(), &name, list),
+                            };
+                            if let Err(e) = saved {
+                                self.error_message = Some(format!("Couldn't save the template: {e}."));
+                            }
+                            store.set_string("save_template_popup_status", String::from("closed"));
+                        }
+                        if ui.button("Cancel").clicked() {
+                            store.set_string("save_template_popup_status", String::from("closed"));
+                        }
+                    });
+                });
+            }
+        }
+
+        //handling for popup that moves or copies items to another list
+        let transfer_popup = egui::Window::new("Move or Copy Items")
+            .title_bar(true)
+            .default_open(true);
+        if let Some(store) = frame.storage_mut() {
+            if store.get_string("transfer_popup_status").is_some_and(|x| x == "open") {
+                transfer_popup.show(ctx, |ui| {
+                    let Some(loaded_list) = self.loaded_list.as_mut() else {
+                        ui.label("No list is loaded.");
+                        if ui.button("Close").clicked() {
+                            store.set_string("transfer_popup_status", String::from("closed"));
+                        }
+                        return;
+                    };
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut self.transfer_copy, false, "Move");
+                        ui.radio_value(&mut self.transfer_copy, true, "Copy");
+                    });
+                    ui.label("Items (subtasks go along with their parent):");
+                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        for item in loaded_list.all_items() {
+                            let mut picked = self.transfer_ids.contains(&item.id);
+                            if ui.checkbox(&mut picked, &item.name).changed() {
+                                if picked {
+                                    self.transfer_ids.insert(item.id);
+                                } else {
+                                    self.transfer_ids.remove(&item.id);
+                                }
+                            }
+                        }
+                    });
+
+                    ui.separator();
+                    ui.label("To:");
+                    let ids: Vec<usize> = self.transfer_ids.iter().copied().collect();
+                    let mut destination = None;
+                    let mut file_destination = None;
+                    ui.add_enabled_ui(!ids.is_empty(), |ui| {
+                        for (index, open) in self.all_lists.iter().enumerate() {
+                            if ui.button(&open.list.title).clicked() {
+                                destination = Some(index);
+                            }
+                        }
+                        if ui.button("Another file…").clicked() {
+                            let empty_path = PathBuf::new();
+                            if let Some(path) = rfd::FileDialog::new()
+                                .add_filter("json", &["json"])
+                                .set_directory(match &self.current_path {
+                                    Some(p) => p,
+                                    None => &empty_path,
+                                })
+                                .pick_file() {
+                                    //a file that's already open has to get the items in its open copy, or saving that
+                                    //copy later would write over them
+                                    let open_index = self.all_lists.iter().position(|x| x.path.as_deref().is_some_and(|p| same_file(p, &path)));
+                                    if self.current_path.as_deref().is_some_and(|x| same_file(x, &path)) {
+                                        self.error_message = Some(String::from("Those items are already in this list."));
+                                    } else if open_index.is_some() {
+                                        destination = open_index;
+                                    } else {
+                                        file_destination = Some(path);
+                                    }
+                            }
+                        }
+                    });
+                    let mut done = false;
+                    if let Some(index) = destination {
+                        self.transfer_to_open_list(&ids, index);
+                        done = true;
+                    }
+                    if let Some(path) = file_destination {
+                        match TodoList::from_file(&path).and_then(|list| self.transfer_to_file(&ids, list, &path)) {
+                            Ok(()) => done = true,
+                            Err(e) => self.error_message = Some(format!("Couldn't add the items to {}: {e}.", path.display())),
+                        }
+                    }
+                    if self.all_lists.is_empty() {
+                        ui.label(RichText::new("Open more lists to see them here.").weak());
+                    }
+                    if done || ui.button("Cancel").clicked() {
+                        store.set_string("transfer_popup_status", String::from("closed"));
+                    }
+                });
+            }
+        }
+
+        //handling for popup that renames list
+        let rename_list_popup = egui::Window::new("Rename List")
+            .title_bar(true)
+            .default_open(true);
+        if let Some(store) = frame.storage_mut() {
+            if let Some(rename_list_popup_status) = store.get_string("rename_list_popup_status") {
+                if rename_list_popup_status == "open" {
+                    rename_list_popup.show(ctx, |ui| {
+                        ui.label("Enter a new title here.");
+                        egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("list_title"))
+                            .expect("list_title should be properly initialized when Rename List is clicked"))
+                        .hint_text("New Title")
+                        .show(ui);
+                        
+                        ui.horizontal(|ui| {
+                            if ui.button("Set New Title").clicked() {
+                                let title = self.text_inputs.get("list_title").unwrap().clone();
+                                self.history.apply(self.loaded_list.as_mut().expect("loaded_list returned None"), Command::Rename { title });
+                                store.set_string("rename_list_popup_status", String::from("closed"));
+                            }
+                            if ui.button("Cancel").clicked() {
+                                store.set_string("rename_list_popup_status", String::from("closed"));
+                            }
+                        });
+
+                    });
+                }
+            }
+        }
+
+        //handling for popup to add a subtask; top-level items come from the quick-add bar
+        let add_item_popup = egui::Window::new("Add Subtask")
+            .title_bar(true)
+            .default_open(true);
+        if let (Some(parent_id), Some(loaded_list)) = (self.subtask_parent, self.loaded_list.as_mut()) {
+            let fields = loaded_list.fields().to_vec();
+            add_item_popup.show(ctx, |ui| {
+
+                ui.label("Give your new list item a name here.");
+                egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("item_name"))
+                    .expect("item_name should be properly initialized before Add Item is clicked"))
+                .hint_text("New Item Name")
+                .show(ui);
+
+                ui.label("Describe the item in more detail here.");
+                egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("item_description"))
+                    .expect("item_description should be properly initialized before Add Item is clicked"))
+                .hint_text("New Item Description")
+                .show(ui);
+
+                let dates_valid = Self::date_inputs(&mut self.text_inputs, ui);
+                let recurrence_valid = Self::recurrence_input(&mut self.text_inputs, ui);
+                Self::priority_input(&mut self.priority_input, ui);
+                Self::tag_input(&mut self.text_inputs, ui);
+                let fields_valid = Self::field_inputs(&mut self.text_inputs, &fields, ui);
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(dates_valid && recurrence_valid && fields_valid, Button::new("Add Item")).clicked() {
+                        let mut item = loaded_list.new_item(String::new(), String::new());
+                        Self::fill_item_from_inputs(&mut item, &self.text_inputs, self.priority_input, &fields);
+                        if !self.history.apply(loaded_list, Command::Insert(DetachedItem::new(item, Some(parent_id)))) {
+                            self.error_message = Some(String::from("Couldn't add the subtask, its parent item is gone."));
+                        }
+                        self.subtask_parent = None;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.subtask_parent = None;
+                    }
+                });
+
+            });
+        }
+
+        //handling for popup that edits list items
+        //close_missing_item_popups has already closed it if its item is gone
+        let edit_target = self.editing_item.zip(self.loaded_list.as_mut())
+            .and_then(|(id, list)| Some((list.get_item_by_id(id)?.name.clone(), id, list)));
+        if let Some((item_name, id, loaded_list)) = edit_target {
+            let fields = loaded_list.fields().to_vec();
+            let edit_list_item_popup = egui::Window::new("Edit Item")
+                .id(Id::new(format!("Editing \"{item_name}\"")))
+                .title_bar(true)
+                .default_open(true);
+
+            edit_list_item_popup.show(ctx, |ui| {
+                ui.label("Enter a new item name here.");
+                egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("item_name"))
+                    .expect("item_name should be properly initialized before Edit Item is clicked"))
+                .hint_text("New Name")
+                .show(ui);
+
+                ui.label("Enter a new item description here.");
+                egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("item_description"))
+                    .expect("item_description should be properly initialized before Edit Item is clicked"))
+                .hint_text("New Description")
+                .show(ui);
+
+                let dates_valid = Self::date_inputs(&mut self.text_inputs, ui);
+                let recurrence_valid = Self::recurrence_input(&mut self.text_inputs, ui);
+                Self::priority_input(&mut self.priority_input, ui);
+                Self::tag_input(&mut self.text_inputs, ui);
+                let fields_valid = Self::field_inputs(&mut self.text_inputs, &fields, ui);
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(dates_valid && recurrence_valid && fields_valid, Button::new("Set Changes")).clicked() {
+                        if let Some(mut item) = loaded_list.get_item_by_id(id).cloned() {
+                            Self::fill_item_from_inputs(&mut item, &self.text_inputs, self.priority_input, &fields);
+                            self.history.apply(loaded_list, Command::UpdateItem(item));
+                        }
+                        self.editing_item = None;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.editing_item = None;
+                    }
+                });
+            });
+        }
+        
+
+        //search results, next to the list so clicking one can scroll to it
+        let search_text = self.text_inputs.get("search").cloned().unwrap_or_default();
+        if !search_text.trim().is_empty() {
+            egui::SidePanel::right("search_results").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("Search results");
+                    if ui.small_button("✕").on_hover_text("Clear the search").clicked() {
+                        self.text_inputs.insert(String::from("search"), String::new());
+                    }
+                });
+                let root = self.workspace_root();
+                ui.checkbox(&mut self.search_directory, format!("Also search the {} folder", root.display()));
+                if self.search_directory {
+                    self.load_directory_lists();
+                }
+                let (lists, sources) = self.source_lists(self.search_directory);
+
+                let hits = search::search(&lists, &search_text);
+                if hits.is_empty() {
+                    ui.label("Nothing matches.");
+                }
+                let mut jump_to = None;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for hit in hits.iter().take(SEARCH_RESULT_LIMIT) {
+                        let list = lists[hit.list_index];
+                        let Some(item) = list.get_item_by_id(hit.item_id) else {
+                            continue;
+                        };
+                        if !matches!(sources[hit.list_index], ListSource::Loaded) {
+                            ui.label(RichText::new(&list.title).small().weak());
+                        }
+                        let name = Self::highlighted_text(ui, &item.name, &hit.name_matches);
+                        if ui.add(Label::new(name).sense(Sense::click())).on_hover_text("Go to this item").clicked() {
+                            jump_to = Some((hit.list_index, hit.item_id));
+                        }
+                        if !hit.description_matches.is_empty() {
+                            ui.label(Self::highlighted_text(ui, &item.description, &hit.description_matches));
+                        }
+                        ui.separator();
+                    }
+                });
+
+                if let Some((index, item_id)) = jump_to {
+                    self.show_item(sources[index], item_id, ctx);
+                }
+            });
+        }
+
+        egui::SidePanel::left("workspace").show(ctx, |ui| {
+            self.views_ui(ui);
+            if self.workspace_ui(ui) {
+                self.smart_view = None;
+                self.view_editing = None;
+            }
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if debug_mode {
+                egui::ScrollArea::vertical().show(ui, |ui| {    
+                    ui.heading("central panel");
+                    ui.label("Context memory dump:");
+                    //ctx.memory_ui(ui);
+                    ui.label("Context inspection ui:");
+                    //ctx.inspection_ui(ui);
+
+                    let mut stored = false;
+                    ui.label("eframe storage status:");
+                    match frame.storage() {
+                        Some(store) => {
+                            ui.label("eframe storage available. \"test\" returns:");
+                            match store.get_string("test") {
+                                Some(value) => { 
+                                    stored = true;
+                                    ui.label(value)
+                                }
+                                None => ui.label("None."),
+                            }
+                        }
+                        None => ui.label("Storage returned None."),
+                        };
+                    if !stored && ui.button("Store test data").clicked() {
+                        frame.storage_mut().expect("Store test data button couldn't run storage_mut")
+                            .set_string("test", String::from("Successfully-retrieved test string! Hello, eframe storage..."));
+                    }
+                })
+            } else if let Some(view) = self.smart_view {
+                egui::ScrollArea::vertical().show(ui, |ui| self.smart_view_ui(ui, view, ctx))
+            } else {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    
+                    let mut placeholder_list = TodoList::new(String::from("New Todo List"));
+                    ui.label("Under construction! Run with \"debug\" as an arg for egui information.");
+
+                    let title = self.loaded_list.as_ref().map_or_else(|| placeholder_list.title.clone(), |x| x.title.clone());
+                    ui.horizontal(|ui| {
+                        ui.heading(&title);
+                        if ui.button("Change title").clicked() {
+                            let _ = self.text_inputs.insert(String::from("list_title"), title.clone());
+                            frame.storage_mut().expect("storage_mut returned None").set_string("rename_list_popup_status", String::from("open"));
+                        }
+                    });
+
+                    self.quick_add_ui(ui);
+
+                    let loaded_list = match self.loaded_list.as_mut() {
+                        Some(list) => list,
+                        None => &mut placeholder_list,
+                    };
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Sort by priority").clicked() {
+                            loaded_list.sort_by_priority();
+                            self.dirty = true;
+                        }
+                        if ui.button("Archive completed").clicked() {
+                            let now = Local::now();
+                            let commands = loaded_list.archivable_items().iter().map(|x| Command::Archive { id: x.id, at: now }).collect();
+                            self.history.apply(loaded_list, Command::Batch(commands));
+                        }
+                        if ui.button("Clear list").on_hover_text("Moves every item to the trash").clicked() {
+                            self.history.apply(loaded_list, Command::Clear { at: Local::now() });
+                        }
+                        if ui.add_enabled(self.history.can_undo(), Button::new("Undo")).on_hover_text("Ctrl+Z").clicked() {
+                            self.history.undo(loaded_list);
+                        }
+                        if ui.add_enabled(self.history.can_redo(), Button::new("Redo")).on_hover_text("Ctrl+Shift+Z").clicked() {
+                            self.history.redo(loaded_list);
+                        }
+                    });
+
+                    //text boxes have their own undo, so the shortcuts only apply to the list when no text box has focus
+                    if !ui.ctx().wants_keyboard_input() {
+                        let redo_shortcut = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z);
+                        let undo_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+                        //redo is checked first, since Ctrl+Z would also match Ctrl+Shift+Z
+                        if ui.ctx().input_mut(|i| i.consume_shortcut(&redo_shortcut)) {
+                            self.history.redo(loaded_list);
+                        } else if ui.ctx().input_mut(|i| i.consume_shortcut(&undo_shortcut)) {
+                            self.history.undo(loaded_list);
+                        }
+                    }
+
+                    if ui.checkbox(&mut loaded_list.auto_complete_parents, "Complete items when all their subtasks are done").changed() {
+                        self.dirty = true;
+                    }
+                    ui.horizontal(|ui| {
+                        let mut auto_archive = loaded_list.archive_after_days.is_some();
+                        ui.checkbox(&mut auto_archive, "Archive completed items after");
+                        let mut days = loaded_list.archive_after_days.unwrap_or(DEFAULT_ARCHIVE_AFTER_DAYS);
+                        ui.add_enabled(auto_archive, DragValue::new(&mut days).clamp_range(0..=365));
+                        ui.label("days (when the list is loaded)");
+                        if loaded_list.archive_after_days != auto_archive.then_some(days) {
+                            loaded_list.archive_after_days = auto_archive.then_some(days);
+                            self.dirty = true;
+                        }
+                    });
+                    if Self::list_kind_ui(ui, loaded_list, &mut self.text_inputs) {
+                        self.dirty = true;
+                    }
+
+                    if let Some(message) = &self.error_message {
+                        let mut dismissed = false;
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(message).color(Color32::RED));
+                            dismissed = ui.small_button("Dismiss").clicked();
+                        });
+                        if dismissed {
+                            self.error_message = None;
+                        }
+                    }
+
+                    let now = Local::now();
+                    let mut actions: Vec<ItemAction> = Vec::new();
+
+                    let mut query = None;
+                    ui.horizontal(|ui| {
+                        ui.label("Query:");
+                        egui::TextEdit::singleline(self.text_inputs.entry(String::from("query")).or_default())
+                        .hint_text("status:open tag:work due<7d priority>=high \"invoice\"")
+                        .desired_width(320.0)
+                        .show(ui);
+                        let query_text = self.text_inputs.get("query").cloned().unwrap_or_default();
+                        if !query_text.trim().is_empty() {
+                            match Query::parse(&query_text) {
+                                Ok(parsed) => query = Some(parsed),
+                                Err(e) => {
+                                    ui.label(RichText::new(format!("Couldn't read the query {e}.")).color(Color32::RED));
+                                }
+                            }
+                            if ui.button("Clear").clicked() {
+                                self.text_inputs.insert(String::from("query"), String::new());
+                            }
+                        }
+
+                        if let Some(query) = &query {
+                            for name in query.unknown_states(loaded_list) {
+                                ui.label(RichText::new(format!("This list has no status called \"{name}\".")).color(Color32::from_rgb(255, 165, 0)));
+                            }
+                        }
+
+                        ui.menu_button("Saved queries", |ui| {
+                            let mut to_remove = None;
+                            for (name, saved) in &self.saved_queries {
+                                ui.horizontal(|ui| {
+                                    if ui.button(name).on_hover_text(saved).clicked() {
+                                        self.text_inputs.insert(String::from("query"), saved.clone());
+                                        ui.close_menu();
+                                    }
+                                    if ui.small_button("✕").on_hover_text("Forget this query").clicked() {
+                                        to_remove = Some(name.clone());
+                                    }
+                                });
+                            }
+                            if let Some(name) = to_remove {
+                                self.saved_queries.remove(&name);
+                            }
+                            if !self.saved_queries.is_empty() {
+                                ui.separator();
+                            }
+                            egui::TextEdit::singleline(self.text_inputs.entry(String::from("query_name")).or_default())
+                            .hint_text("Query Name")
+                            .show(ui);
+                            let name = self.text_inputs.get("query_name").cloned().unwrap_or_default();
+                            let can_save = query.is_some() && !name.trim().is_empty();
+                            if ui.add_enabled(can_save, Button::new("Save current query")).clicked() {
+                                self.saved_queries.insert(String::from(name.trim()), query_text.clone());
+                                self.text_inputs.insert(String::from("query_name"), String::new());
+                                ui.close_menu();
+                            }
+                        });
+                    });
+
+                    let tag_counts = loaded_list.tag_counts();
+                    if !tag_counts.is_empty() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("Filter by tag:");
+                            for (tag, count) in &tag_counts {
+                                if ui.selectable_label(self.tag_filter.contains(tag), format!("{tag} ({count})")).clicked() {
+                                    actions.push(ItemAction::FilterByTag(tag.clone()));
+                                }
+                            }
+                            if !self.tag_filter.is_empty() && ui.button("Clear filter").clicked() {
+                                self.tag_filter.clear();
+                            }
+                        });
+                    }
+                    //tags that no item uses any more can't be unselected from the bar, so drop them
+                    self.tag_filter.retain(|tag| tag_counts.contains_key(tag));
+
+                    match loaded_list.kind() {
+                        ListKind::Checklist => {
+                            let total = loaded_list.all_items().len();
+                            let done = loaded_list.all_items().iter().filter(|x| x.completed).count();
+                            ui.add(ProgressBar::new(if total == 0 { 0.0 } else { done as f32 / total as f32 }).text(format!("{done}/{total} done")));
+                            if let Some(archived_at) = loaded_list.archived_at() {
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new(format!("Finished and archived {}.", archived_at.format(DATE_DISPLAY_FORMAT))).color(Color32::LIGHT_GREEN));
+                                    if ui.button("Use again").on_hover_text("Untick everything").clicked() {
+                                        actions.push(ItemAction::ReopenAll);
+                                    }
+                                });
+                            }
+                        }
+                        ListKind::Daily { reset_at } => {
+                            let next = kinds::last_reset_time(*reset_at, now).map(|x| x + Duration::days(1));
+                            let next = next.map(|x| format!(", next at {}", x.format(DATE_DISPLAY_FORMAT))).unwrap_or_default();
+                            ui.label(RichText::new(format!("Each day at {}{next}, done items are unticked and the rest carry over.", reset_at.format(kinds::RESET_TIME_FORMAT))).weak());
+                            let carried = loaded_list.all_items().iter().filter(|x| x.carried_days() > 0).count();
+                            if carried > 0 {
+                                ui.label(RichText::new(format!("{carried} carried over from earlier days.")).color(Color32::from_rgb(255, 165, 0)));
+                            }
+                        }
+                        ListKind::Habit { reset_at } => {
+                            ui.label(RichText::new(format!("Each day at {}, everything is unticked. Tick an item every day to keep its streak going.", reset_at.format(kinds::RESET_TIME_FORMAT))).weak());
+                        }
+                        ListKind::Standard | ListKind::Kanban { .. } => {}
+                    }
+
+                    match query {
+                        None if self.tag_filter.is_empty() && loaded_list.view() == ListView::Board => {
+                            Self::board_ui(ui, loaded_list, now, &mut actions);
+                        }
+                        None if self.tag_filter.is_empty() => {
+                            for list_item in &loaded_list.items {
+                                Self::item_ui(ui, loaded_list, list_item, now, true, &mut actions);
+                            }
+                        }
+                        _ => {
+                            //matching subtasks are listed on their own, so the tree is flattened while filtering
+                            let filter: Vec<&str> = self.tag_filter.iter().map(|x| x.as_str()).collect();
+                            let found = match &query {
+                                Some(query) => query.apply(loaded_list, now),
+                                None => loaded_list.all_items(),
+                            };
+                            for list_item in found.into_iter().filter(|x| filter.iter().all(|tag| x.has_tag(tag))) {
+                                Self::item_ui(ui, loaded_list, list_item, now, false, &mut actions);
+                            }
+                        }
+                    }
+
+                    for action in actions {
+                        match action {
+                            ItemAction::SetCompleted(id, completed) => {
+                                self.history.apply(loaded_list, Command::SetCompleted { id, completed, at: Local::now() });
+                            }
+                            ItemAction::SetStatus(id, status) => {
+                                self.history.apply(loaded_list, Command::SetStatus { id, status, at: Local::now() });
+                            }
+                            ItemAction::Edit(id) => {
+                                let list_item = loaded_list.get_item_by_id(id).expect("Edit was clicked on an item in this list");
+                                self.text_inputs.insert(String::from("item_name"), list_item.name.clone());
+                                self.text_inputs.insert(String::from("item_description"), list_item.description.clone());
+                                self.text_inputs.insert(String::from("item_due"), list_item.due.map(|d| d.format(DATE_DISPLAY_FORMAT).to_string()).unwrap_or_default());
+                                self.text_inputs.insert(String::from("item_start"), list_item.start.map(|d| d.format(DATE_DISPLAY_FORMAT).to_string()).unwrap_or_default());
+                                self.priority_input = list_item.priority;
+                                self.text_inputs.insert(String::from("item_tags"), list_item.tags.iter().cloned().collect::<Vec<String>>().join(" "));
+                                self.text_inputs.insert(String::from("item_recurrence"), list_item.recurrence.as_ref().map(|x| x.to_string()).unwrap_or_default());
+                                for field in loaded_list.fields() {
+                                    let value = list_item.field_value(&field.name).map(|x| x.to_string()).unwrap_or_default();
+                                    self.text_inputs.insert(format!("field:{}", field.name), value);
+                                }
+                                self.editing_item = Some(id);
+                            }
+                            ItemAction::MoveToColumn(id, column) => {
+                                let moved = Command::move_to_board_column(loaded_list, id, &column, Local::now())
+                                    .and_then(|command| self.history.apply(loaded_list, command).then_some(()).ok_or(BoardError::MissingItem(id)));
+                                if let Err(e) = moved {
+                                    self.error_message = Some(format!("Couldn't move the card: {e}."));
+                                }
+                            }
+                            ItemAction::SetWipLimit(column, limit) => {
+                                loaded_list.board_mut().set_wip_limit(&column, limit);
+                                self.dirty = true;
+                            }
+                            ItemAction::CollapseColumn(column, collapsed) => {
+                                loaded_list.board_mut().set_collapsed(&column, collapsed);
+                                self.dirty = true;
+                            }
+                            ItemAction::ReopenAll => {
+                                let at = Local::now();
+                                let commands = loaded_list.all_items().iter()
+                                    .filter(|x| x.completed)
+                                    .map(|x| Command::SetCompleted { id: x.id, completed: false, at })
+                                    .collect();
+                                self.history.apply(loaded_list, Command::Batch(commands));
+                            }
+                            ItemAction::AddSubtask(id) => {
+                                Self::reset_item_inputs(&mut self.text_inputs);
+                                self.priority_input = Priority::None;
+                                self.subtask_parent = Some(id);
+                            }
+                            ItemAction::Delete(id) => {
+                                self.history.apply(loaded_list, Command::Trash { id, at: Local::now() });
+                            }
+                            ItemAction::SaveTemplate(id) => {
+                                let name = loaded_list.get_item_by_id(id).map(|x| x.name.clone()).unwrap_or_default();
+                                self.text_inputs.insert(String::from("template_name"), name);
+                                let store = frame.storage_mut().expect("storage_mut returned None");
+                                store.set_string("save_template_item_id", id.to_string());
+                                store.set_string("save_template_popup_status", String::from("open"));
+                            }
+                            ItemAction::Transfer { id, copy } => {
+                                self.transfer_ids = BTreeSet::from([id]);
+                                self.transfer_copy = copy;
+                                frame.storage_mut().expect("storage_mut returned None").set_string("transfer_popup_status", String::from("open"));
+                            }
+                            ItemAction::MoveBefore(id, target_id) => {
+                                self.dirty |= loaded_list.move_item_before(id, target_id);
+                            }
+                            ItemAction::MoveAfter(id, target_id) => {
+                                self.dirty |= loaded_list.move_item_after(id, target_id);
+                            }
+                            ItemAction::SetBlocker { id, blocker_id, blocks } => {
+                                if blocks {
+                                    match loaded_list.add_dependency(id, blocker_id) {
+                                        Ok(()) => self.dirty = true,
+                                        Err(e) => self.error_message = Some(format!("Couldn't add that blocker: {e}.")),
+                                    }
+                                } else {
+                                    loaded_list.remove_dependency(id, blocker_id);
+                                    self.dirty = true;
+                                }
+                            }
+                            ItemAction::FilterByTag(tag) => {
+                                if !self.tag_filter.remove(&tag) {
+                                    self.tag_filter.insert(tag);
+                                }
+                            }
+                        }
+                    }
+                })
+            }
+        });
+    }
+}
+
+
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct TodoList {
+    pub title: String,
+    pub items: Vec<TodoListItem>,
+    date_created: DateTime<Local>,
+    #[serde(default)]
+    next_id: usize, //ids are handed out from here and never reused, even after the item is removed
+    #[serde(default)]
+    pub auto_complete_parents: bool,
+    #[serde(default)]
+    trash: Vec<TrashedItem>, //oldest deletion first
+    #[serde(default)]
+    archive: Vec<ArchivedItem>, //oldest first
+    #[serde(default)]
+    pub archive_after_days: Option<u32>, //None means completed items are only archived by hand
+    #[serde(default)]
+    fields: Vec<FieldDef>, //custom fields, in the order they're shown
+    #[serde(default)]
+    kind: ListKind,
+    #[serde(default)]
+    last_reset: Option<DateTime<Local>>, //when a daily list last unticked everything
+    #[serde(default)]
+    archived_at: Option<DateTime<Local>>, //when a checklist was finished, None while it's still going
+    #[serde(default = "statuses::default_statuses")]
+    statuses: Vec<StatusDef>, //in the order they're offered, with at least one open and one done status
+    #[serde(default)]
+    view: Option<ListView>, //None means the default for the kind of list, see TodoList::view
+    #[serde(default)]
+    board: BoardSettings,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TrashedItem {
+    detached: DetachedItem,
+    pub deleted_at: DateTime<Local>,
+}
+
+impl TrashedItem {
+    pub fn item(&self) -> &TodoListItem {
+        &self.detached.item
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ArchivedItem {
+    detached: DetachedItem,
+    pub archived_at: DateTime<Local>,
+}
+
+impl ArchivedItem {
+    pub fn item(&self) -> &TodoListItem {
+        &self.detached.item
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct TodoListItem {
+    id: usize,
+    pub name: String,
+    pub description: String,
+    date_created: DateTime<Local>,
+    completed: bool, //always matches the category of status; use TodoList::set_completed or set_status to change it
+    #[serde(default)]
+    pub due: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub start: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub subtasks: Vec<TodoListItem>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    pub completion_history: Vec<DateTime<Local>>, //when each past occurrence of a recurring item was completed
+    #[serde(default)]
+    skipped_occurrences: u32, //occurrences of a recurring item that went by while it sat overdue, which COUNT counts too
+    #[serde(default)]
+    completed_at: Option<DateTime<Local>>, //None while the item is open
+    #[serde(default)]
+    completion_log: Vec<CompletionEvent>, //every time the item was ticked or unticked, oldest first
+    #[serde(default)]
+    blocked_by: BTreeSet<usize>, //ids of items in the same list that have to be completed first
+    #[serde(default)]
+    field_values: BTreeMap<String, FieldValue>, //keyed by the name of a field in the list's schema
+    #[serde(default, skip_serializing)]
+    column: Option<String>, //only read, from kanban files saved before columns were statuses; see TodoList::repair_statuses
+    #[serde(default)]
+    carried_days: u32, //for daily lists, how many days in a row the item has been left undone, see TodoList::roll_over
+    #[serde(default)]
+    status: Option<String>, //the name of a status in the list, always of the same category as completed; see TodoList::status_of
+    #[serde(default)]
+    status_log: Vec<StatusChange>, //every move between statuses, oldest first
+}
+
+//an item taken out of a list, along with where it was, so it can be put back exactly as it was
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DetachedItem {
+    pub item: TodoListItem,
+    pub parent_id: Option<usize>, //None for top-level items
+    pub index: usize, //position among its siblings; anything past the end appends
+    blocked_refs: Vec<(usize, usize)>, //(item, blocker) pairs where the blocker is this item or one of its subtasks
+}
+
+impl DetachedItem {
+    //wraps an item that hasn't been in the list before, to be appended under parent_id
+    pub fn new(item: TodoListItem, parent_id: Option<usize>) -> DetachedItem {
+        DetachedItem { item, parent_id, index: usize::MAX, blocked_refs: Vec::new() }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DependencyError {
+    MissingItem(usize),
+    BlocksItself,
+    Cycle, //the blocker is already waiting, directly or not, on the item it would block
+}
+
+impl std::fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyError::MissingItem(id) => write!(f, "there is no item with id {id} in this list"),
+            DependencyError::BlocksItself => write!(f, "an item can't block itself"),
+            DependencyError::Cycle => write!(f, "that would make the items wait on each other forever"),
+        }
+    }
+}
+
+impl std::error::Error for DependencyError {}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompletionChange {
+    Completed,
+    Reopened,
+}
+
+impl CompletionChange {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CompletionChange::Completed => "Completed",
+            CompletionChange::Reopened => "Reopened",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CompletionEvent {
+    pub change: CompletionChange,
+    pub at: DateTime<Local>,
+}
+
+//declared lowest to highest so the derived Ord can be used for sorting and filtering
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 5] = [Priority::None, Priority::Low, Priority::Medium, Priority::High, Priority::Urgent];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Priority::None => "No priority",
+            Priority::Low => "Low",
+            Priority::Medium => "Medium",
+            Priority::High => "High",
+            Priority::Urgent => "Urgent",
+        }
+    }
+
+    pub fn color(&self) -> Color32 {
+        match self {
+            Priority::None => Color32::GRAY,
+            Priority::Low => Color32::LIGHT_BLUE,
+            Priority::Medium => Color32::LIGHT_GREEN,
+            Priority::High => Color32::GOLD,
+            Priority::Urgent => Color32::LIGHT_RED,
+        }
+    }
+}
+
+const TRASH_RETENTION_DAYS: i64 = 30;
+const LISTS_DIR: &str = "lists";
+const SEARCH_RESULT_LIMIT: usize = 50;
+const SCROLL_TO_ITEM_KEY: &str = "scroll_to_item";
+const DEFAULT_ARCHIVE_AFTER_DAYS: u32 = 7;
+const DATE_INPUT_HINT: &str = "2024-05-22 17:00";
+const DATE_DISPLAY_FORMAT: &str = "%Y-%m-%d %H:%M";
+const COLLAPSED_COLUMN_WIDTH: f32 = 48.0;
+const MIN_COLUMN_WIDTH: f32 = 180.0;
+
+type DateInput = Result<Option<DateTime<Local>>, ParseError>;
+
+//reads dates typed into the UI, either "%Y-%m-%d %H:%M" or just "%Y-%m-%d" (which gets default_time)
+//an empty input means no date
+pub fn parse_date_input(input: &str, default_time: NaiveTime) -> DateInput {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    let naive = match NaiveDateTime::parse_from_str(input, DATE_DISPLAY_FORMAT) {
+        Ok(date_time) => date_time,
+        Err(_) => NaiveDate::parse_from_str(input, "%Y-%m-%d")?.and_time(default_time),
+    };
+
+    //earliest() picks a side if the time is ambiguous because of a DST change
+    Ok(Local.from_local_datetime(&naive).earliest())
+}
+
+//splits on whitespace and commas, so "#errand, @home" gives two tags
+pub fn parse_tags(input: &str) -> BTreeSet<String> {
+    input.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|x| !x.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+//where a list or template called name is saved in dir
+//names are used as file names, so anything that could reach outside the directory is replaced
+pub(crate) fn list_file_path(dir: &Path, name: &str) -> PathBuf {
+    let file_name: String = name.trim().chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    dir.join(format!("{file_name}.json"))
+}
+
+//whether two paths name the same file, even when one is relative and the other isn't; paths that can't be resolved
+//(usually because the file doesn't exist) are compared as written
+pub(crate) fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+//calls f on every item, subtasks included, parents before their subtasks
+fn visit_items_mut(items: &mut [TodoListItem], f: &mut impl FnMut(&mut TodoListItem)) {
+    for item in items.iter_mut() {
+        f(item);
+        visit_items_mut(&mut item.subtasks, f);
+    }
+}
+
+impl TodoList {
+    pub fn new(title: String) -> TodoList {
+        let date_created = Local::now();
+        let items: Vec<TodoListItem> = Vec::new();
+        TodoList {
+            title,
+            date_created,
+            items,
+            next_id: 0,
+            auto_complete_parents: false,
+            trash: Vec::new(),
+            archive: Vec::new(),
+            archive_after_days: None,
+            fields: Vec::new(),
+            kind: ListKind::Standard,
+            last_reset: None,
+            archived_at: None,
+            statuses: statuses::default_statuses(),
+            view: None,
+            board: BoardSettings::default(),
+        }
+    }
+
+    pub fn add(&mut self, name: String, description: String) -> usize {
+        let item = self.new_item(name, description);
+        let id = item.id;
+        self.items.push(item);
+        id
+    }
+
+    //makes an item with an id reserved in this list, without adding it anywhere yet
+    //it starts out in the list's default open status
+    pub fn new_item(&mut self, name: String, description: String) -> TodoListItem {
+        let id = self.allocate_id();
+        let mut item = TodoListItem::new(id, name, description);
+        item.status = Some(self.default_status(false).name);
+        item
+    }
+
+    //returns None if there is no item with parent_id
+    pub fn add_subtask(&mut self, parent_id: usize, name: String, description: String) -> Option<usize> {
+        self.get_item_by_id(parent_id)?;
+        let item = self.new_item(name, description);
+        let id = item.id;
+        self.get_item_mut_by_id(parent_id)?.subtasks.push(item);
+        Some(id)
+    }
+
+    //from_file runs repair_ids, which keeps next_id past every id in the list, so it can be handed out as it is
+    fn allocate_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    //gets items from another list ready to go in this one without adding them, by giving them (and their subtasks) fresh ids
+    //blockers that aren't among the items and custom field values this list has no matching field for are dropped, and
+    //items in a status this list doesn't have go to its default status for whether they're completed
+    pub fn adopt_items(&mut self, mut items: Vec<TodoListItem>) -> Vec<TodoListItem> {
+        let mut new_ids = HashMap::new();
+        visit_items_mut(&mut items, &mut |item| {
+            let id = self.allocate_id();
+            new_ids.insert(item.id, id);
+            item.id = id;
+        });
+        let fields = &self.fields;
+        let statuses = &self.statuses;
+        visit_items_mut(&mut items, &mut |item| {
+            item.blocked_by = item.blocked_by.iter().filter_map(|x| new_ids.get(x).copied()).collect();
+            item.field_values.retain(|name, value| {
+                fields.iter().any(|field| field.name == *name && field.validate(value).is_ok())
+            });
+            item.status = statuses::resolve(statuses, item.status.as_deref(), item.completed).map(|x| x.name.clone());
+        });
+        items
+    }
+
+    //gives a fresh id to every item whose id was already taken by an earlier item in the list,
+    //returns how many items had to be changed
+    pub fn repair_ids(&mut self) -> usize {
+        fn repair(items: &mut [TodoListItem], seen: &mut HashSet<usize>, next_id: &mut usize) -> usize {
+            let mut repaired = 0;
+            for item in items.iter_mut() {
+                if !seen.insert(item.id) {
+                    item.id = *next_id;
+                    seen.insert(item.id);
+                    *next_id += 1;
+                    repaired += 1;
+                }
+                repaired += repair(&mut item.subtasks, seen, next_id);
+            }
+            repaired
+        }
+
+        //next_id can be behind the items if the file predates it or was edited by hand, so it's moved past every id
+        //already in the list, the trash and the archive first
+        let mut next_id = self.all_stored_items().iter().map(|x| x.id + 1).max().unwrap_or(0).max(self.next_id);
+        let mut seen = HashSet::new();
+        let mut repaired = repair(&mut self.items, &mut seen, &mut next_id);
+        //trashed and archived items have to keep clear of the live ones too, or they couldn't be put back
+        let trashed = self.trash.iter_mut().map(|x| &mut x.detached);
+        let archived = self.archive.iter_mut().map(|x| &mut x.detached);
+        for detached in trashed.chain(archived) {
+            repaired += repair(std::slice::from_mut(&mut detached.item), &mut seen, &mut next_id);
+        }
+        self.next_id = next_id;
+        repaired
+    }
+
+    //like all_items, but also the items in the trash and the archive
+    fn all_stored_items(&self) -> Vec<&TodoListItem> {
+        let mut all = self.all_items();
+        for item in self.trash.iter().map(|x| x.item()).chain(self.archive.iter().map(|x| x.item())) {
+            item.collect_with_subtasks(&mut all);
+        }
+        all
+    }
+
+    //moves every item to the trash, see Command::Clear
+    pub fn clear_list(&mut self, now: DateTime<Local>) {
+        Command::Clear { at: now }.apply(self);
+    }
+
+    //every item in the list, each parent followed by its subtasks
+    pub fn all_items(&self) -> Vec<&TodoListItem> {
+        let mut all = Vec::new();
+        for item in &self.items {
+            item.collect_with_subtasks(&mut all);
+        }
+        all
+    }
+
+    //searches subtasks too
+    pub fn get_item_by_id(&self, id: usize) -> Option<&TodoListItem> {
+        self.items.iter().find_map(|x| x.find(id))
+    }
+
+    fn get_item_mut_by_id(&mut self, id: usize) -> Option<&mut TodoListItem> {
+        self.items.iter_mut().find_map(|x| x.find_mut(id))
+    }
+
+    //None for top-level items, and for ids that aren't in the list
+    pub fn parent_id(&self, id: usize) -> Option<usize> {
+        self.all_items().into_iter()
+            .find(|x| x.subtasks.iter().any(|child| child.id == id))
+            .map(|x| x.id)
+    }
+
+    pub fn change_item_name_by_id(&mut self, name: String, id: usize) {
+        if let Some(item) = self.get_item_mut_by_id(id) {
+            item.name = name;
+        }
+    }
+
+    pub fn change_item_desc_by_id(&mut self, desc: String, id: usize) {
+        if let Some(item) = self.get_item_mut_by_id(id) {
+            item.description = desc;
+        }
+    }
+
+    pub fn change_item_dates_by_id(&mut self, due: Option<DateTime<Local>>, start: Option<DateTime<Local>>, id: usize) {
+        if let Some(item) = self.get_item_mut_by_id(id) {
+            item.due = due;
+            item.start = start;
+        }
+    }
+
+    pub fn change_item_priority_by_id(&mut self, priority: Priority, id: usize) {
+        if let Some(item) = self.get_item_mut_by_id(id) {
+            item.priority = priority;
+        }
+    }
+
+    pub fn change_item_tags_by_id(&mut self, tags: BTreeSet<String>, id: usize) {
+        if let Some(item) = self.get_item_mut_by_id(id) {
+            item.tags = tags;
+        }
+    }
+
+    pub fn items_with_any_tag(&self, tags: &[&str]) -> Vec<&TodoListItem> {
+        self.all_items().into_iter().filter(|x| tags.iter().any(|tag| x.has_tag(tag))).collect()
+    }
+
+    //an empty tags slice matches every item
+    pub fn items_with_all_tags(&self, tags: &[&str]) -> Vec<&TodoListItem> {
+        self.all_items().into_iter().filter(|x| tags.iter().all(|tag| x.has_tag(tag))).collect()
+    }
+
+    //how many items (subtasks included) use each tag
+    pub fn tag_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for item in self.all_items() {
+            for tag in &item.tags {
+                *counts.entry(tag.clone()).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    pub fn change_item_recurrence_by_id(&mut self, recurrence: Option<Recurrence>, id: usize) {
+        if let Some(item) = self.get_item_mut_by_id(id) {
+            item.recurrence = recurrence;
+        }
+    }
+
+    pub fn set_completed(&mut self, id: usize, completed: bool) {
+        self.set_completed_at(id, completed, Local::now());
+    }
+
+    //completing a recurring item moves it on to its next occurrence instead, unless its rule has run out
+    //if auto_complete_parents is set, parents are completed or reopened to match their subtasks
+    pub fn set_completed_at(&mut self, id: usize, completed: bool, now: DateTime<Local>) {
+        let before = self.status_names();
+        self.change_completion(id, completed, now);
+        self.log_status_changes(&before, now);
+    }
+
+    //set_completed_at without logging status changes, so set_status can pick the status first
+    //items that change category go to the default status of the new one
+    fn change_completion(&mut self, id: usize, completed: bool, now: DateTime<Local>) {
+        let open = self.default_status(false);
+        let done = self.default_status(true);
+        match self.get_item_mut_by_id(id) {
+            Some(item) => {
+                let handled = completed && !item.completed && item.advance_recurrence(&open, &done, now);
+                if !handled {
+                    if item.completed != completed {
+                        item.move_to_status(if completed { &done } else { &open }, now);
+                    }
+                } else if !item.completed {
+                    //it moved on to its next occurrence, so its parents have nothing to update
+                    return;
+                }
+            }
+            None => return,
+        }
+
+        if self.auto_complete_parents {
+            let mut child_id = id;
+            while let Some(parent_id) = self.parent_id(child_id) {
+                let parent = self.get_item_mut_by_id(parent_id).expect("parent_id only returns ids that are in the list");
+                let all_done = parent.subtasks.iter().all(|x| x.completed);
+                if parent.completed == all_done {
+                    break;
+                }
+                parent.move_to_status(if all_done { &done } else { &open }, now);
+                child_id = parent_id;
+            }
+        }
+    }
+
+    //moves an item (with its own subtasks) under a new parent, or to the top level if new_parent is None
+    //returns false without changing anything if either id is missing or the move would put an item inside itself
+    pub fn move_item_to_parent(&mut self, id: usize, new_parent: Option<usize>) -> bool {
+        let item = match self.get_item_by_id(id) {
+            Some(item) => item,
+            None => return false,
+        };
+        if let Some(parent_id) = new_parent {
+            if item.find(parent_id).is_some() || self.get_item_by_id(parent_id).is_none() {
+                return false;
+            }
+        }
+
+        let item = self.take_item(id).expect("item was found above");
+        match new_parent {
+            Some(parent_id) => self.get_item_mut_by_id(parent_id).expect("parent was found above").subtasks.push(item),
+            None => self.items.push(item),
+        }
+        true
+    }
+
+    //moves an item to `index` among its siblings, or to the end if index is past the last sibling
+    pub fn move_item_to_index(&mut self, id: usize, index: usize) -> bool {
+        let Some(siblings) = self.siblings_mut(id) else {
+            return false;
+        };
+        let from = siblings.iter().position(|x| x.id == id).expect("siblings_mut returns the Vec holding id");
+        let item = siblings.remove(from);
+        siblings.insert(index.min(siblings.len()), item);
+        true
+    }
+
+    //moves an item (with its subtasks) so it sits just before target_id, under target_id's parent
+    pub fn move_item_before(&mut self, id: usize, target_id: usize) -> bool {
+        self.move_item_next_to(id, target_id, 0)
+    }
+
+    pub fn move_item_after(&mut self, id: usize, target_id: usize) -> bool {
+        self.move_item_next_to(id, target_id, 1)
+    }
+
+    //offset 0 puts the item before the target, 1 after it
+    //returns false without changing anything if either id is missing or target_id is the item itself or one of its subtasks
+    fn move_item_next_to(&mut self, id: usize, target_id: usize, offset: usize) -> bool {
+        match self.get_item_by_id(id) {
+            Some(item) if item.find(target_id).is_none() && self.get_item_by_id(target_id).is_some() => {}
+            _ => return false,
+        }
+
+        let item = self.take_item(id).expect("item was found above");
+        let siblings = self.siblings_mut(target_id).expect("target is still in the list, since it isn't inside the moved item");
+        let index = siblings.iter().position(|x| x.id == target_id).expect("siblings_mut returns the Vec holding target_id");
+        siblings.insert(index + offset, item);
+        true
+    }
+
+    //the Vec that holds the item, which is either self.items or its parent's subtasks
+    fn siblings_mut(&mut self, id: usize) -> Option<&mut Vec<TodoListItem>> {
+        fn search(items: &mut Vec<TodoListItem>, id: usize) -> Option<&mut Vec<TodoListItem>> {
+            if items.iter().any(|x| x.id == id) {
+                return Some(items);
+            }
+            items.iter_mut().find_map(|x| search(&mut x.subtasks, id))
+        }
+        search(&mut self.items, id)
+    }
+
+    //highest priority first; the sort is stable, so items keep their order within a priority
+    //subtasks are sorted among their siblings
+    pub fn sort_by_priority(&mut self) {
+        fn sort(items: &mut [TodoListItem]) {
+            items.sort_by_key(|x| std::cmp::Reverse(x.priority));
+            for item in items.iter_mut() {
+                sort(&mut item.subtasks);
+            }
+        }
+        sort(&mut self.items);
+    }
+
+    pub fn items_with_priority(&self, priority: Priority) -> Vec<&TodoListItem> {
+        self.all_items().into_iter().filter(|x| x.priority == priority).collect()
+    }
+
+    pub fn items_with_priority_at_least(&self, minimum: Priority) -> Vec<&TodoListItem> {
+        self.all_items().into_iter().filter(|x| x.priority >= minimum).collect()
+    }
+
+    //items completed between from and to (inclusive), most recently completed first
+    //recurring items only show up here once their last occurrence is done, see completion_history for the rest
+    pub fn completed_between(&self, from: DateTime<Local>, to: DateTime<Local>) -> Vec<&TodoListItem> {
+        let mut completed: Vec<&TodoListItem> = self.all_items().into_iter()
+            .filter(|x| x.completed_at.is_some_and(|at| at >= from && at <= to))
+            .collect();
+        completed.sort_by_key(|x| std::cmp::Reverse(x.completed_at));
+        completed
+    }
+
+    //open items whose due date has already passed, oldest first
+    pub fn overdue_items(&self, now: DateTime<Local>) -> Vec<&TodoListItem> {
+        let mut overdue: Vec<&TodoListItem> = self.all_items().into_iter().filter(|x| x.is_overdue(now)).collect();
+        overdue.sort_by_key(|x| x.due);
+        overdue
+    }
+
+    //open items due at any time on the same calendar day as now, including ones already overdue today
+    pub fn due_today_items(&self, now: DateTime<Local>) -> Vec<&TodoListItem> {
+        let mut due_today: Vec<&TodoListItem> = self.all_items().into_iter()
+            .filter(|x| !x.completed && x.due.is_some_and(|due| due.date_naive() == now.date_naive()))
+            .collect();
+        due_today.sort_by_key(|x| x.due);
+        due_today
+    }
+
+    //open items due after today but within the next `days` days, soonest first
+    pub fn upcoming_items(&self, now: DateTime<Local>, days: i64) -> Vec<&TodoListItem> {
+        let today = now.date_naive();
+        let last_day = today + Duration::days(days);
+        let mut upcoming: Vec<&TodoListItem> = self.all_items().into_iter()
+            .filter(|x| !x.completed && x.due.is_some_and(|due| due.date_naive() > today && due.date_naive() <= last_day))
+            .collect();
+        upcoming.sort_by_key(|x| x.due);
+        upcoming
+    }
+
+    //moves the item (with its subtasks) to the trash, from wherever it is in the tree
+    //other items stop being blocked by anything that was removed, until it is restored
+    pub fn remove_item(&mut self, id: usize) {
+        self.trash_item(id, Local::now());
+    }
+
+    pub fn trash_item(&mut self, id: usize, now: DateTime<Local>) -> bool {
+        match self.detach_item(id) {
+            Some(detached) => {
+                self.trash.push(TrashedItem { detached, deleted_at: now });
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn trash(&self) -> &[TrashedItem] {
+        &self.trash
+    }
+
+    //puts a trashed item back where it was; if its parent has been deleted since, it goes back at the top level instead
+    //returns false if id isn't in the trash
+    pub fn restore_from_trash(&mut self, id: usize) -> bool {
+        let Some(index) = self.trash.iter().position(|x| x.item().id == id) else {
+            return false;
+        };
+        //the entry stays in the trash unless the item really goes back in
+        if !self.reattach_item(self.trash[index].detached.clone()) {
+            return false;
+        }
+        self.trash.remove(index);
+        true
+    }
+
+    //attach_item, but falling back to the top level if the item's parent has gone
+    fn reattach_item(&mut self, mut detached: DetachedItem) -> bool {
+        if detached.parent_id.is_some_and(|parent_id| self.get_item_by_id(parent_id).is_none()) {
+            detached.parent_id = None;
+            detached.index = usize::MAX;
+        }
+        self.attach_item(detached)
+    }
+
+    pub fn empty_trash(&mut self) {
+        self.trash.clear();
+    }
+
+    //permanently deletes trashed items that were deleted more than max_age ago, returns how many were purged
+    pub fn purge_trash(&mut self, now: DateTime<Local>, max_age: Duration) -> usize {
+        let before = self.trash.len();
+        self.trash.retain(|x| now - x.deleted_at <= max_age);
+        before - self.trash.len()
+    }
+
+    //moves a completed item (with its subtasks) out of the list and into the archive
+    //returns false if the item isn't there or isn't completed
+    pub fn archive_item(&mut self, id: usize, now: DateTime<Local>) -> bool {
+        if !self.get_item_by_id(id).is_some_and(|x| x.completed) {
+            return false;
+        }
+        match self.detach_item(id) {
+            Some(detached) => {
+                self.archive.push(ArchivedItem { detached, archived_at: now });
+                true
+            }
+            None => false,
+        }
+    }
+
+    //completed items that aren't inside another completed item, since archiving a parent takes its subtasks along
+    pub fn archivable_items(&self) -> Vec<&TodoListItem> {
+        fn collect<'a>(items: &'a [TodoListItem], found: &mut Vec<&'a TodoListItem>) {
+            for item in items {
+                if item.completed {
+                    found.push(item);
+                } else {
+                    collect(&item.subtasks, found);
+                }
+            }
+        }
+        let mut found = Vec::new();
+        collect(&self.items, &mut found);
+        found
+    }
+
+    //returns how many items were archived
+    pub fn archive_completed(&mut self, now: DateTime<Local>) -> usize {
+        let ids: Vec<usize> = self.archivable_items().iter().map(|x| x.id).collect();
+        ids.into_iter().filter(|id| self.archive_item(*id, now)).count()
+    }
+
+    //archives items that were completed more than archive_after_days ago, returns how many were archived
+    pub fn auto_archive(&mut self, now: DateTime<Local>) -> usize {
+        let Some(days) = self.archive_after_days else {
+            return 0;
+        };
+        let cutoff = now - Duration::days(days.into());
+        let ids: Vec<usize> = self.archivable_items().iter()
+            .filter(|x| x.completed_at.is_some_and(|at| at <= cutoff))
+            .map(|x| x.id)
+            .collect();
+        ids.into_iter().filter(|id| self.archive_item(*id, now)).count()
+    }
+
+    //the upkeep a list gets whenever it's opened: purges trash past TRASH_RETENTION_DAYS and auto-archives
+    //returns whether that changed anything, which then needs saving
+    pub fn tidy(&mut self, now: DateTime<Local>) -> bool {
+        let purged = self.purge_trash(now, Duration::days(TRASH_RETENTION_DAYS));
+        let archived = self.auto_archive(now);
+        purged + archived > 0
+    }
+
+    pub fn archive(&self) -> &[ArchivedItem] {
+        &self.archive
+    }
+
+    //archived items whose name, description or tags contain the query, ignoring case; an empty query matches everything
+    pub fn search_archive(&self, query: &str) -> Vec<&ArchivedItem> {
+        let query = query.trim().to_lowercase();
+        self.archive.iter()
+            .filter(|x| {
+                let item = x.item();
+                item.name.to_lowercase().contains(&query)
+                    || item.description.to_lowercase().contains(&query)
+                    || item.tags.iter().any(|tag| tag.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    //puts an archived item back where it was, like restore_from_trash; it stays completed
+    pub fn unarchive(&mut self, id: usize) -> bool {
+        let Some(index) = self.archive.iter().position(|x| x.item().id == id) else {
+            return false;
+        };
+        if !self.reattach_item(self.archive[index].detached.clone()) {
+            return false;
+        }
+        self.archive.remove(index);
+        true
+    }
+
+    //ids in the order given, leaving out missing items and items inside another of the items (they come along anyway)
+    fn transfer_roots(&self, ids: &[usize]) -> Vec<usize> {
+        let picked: HashSet<usize> = ids.iter().copied().collect();
+        let mut roots = Vec::new();
+        for id in ids {
+            if self.get_item_by_id(*id).is_none() || roots.contains(id) {
+                continue;
+            }
+            let mut ancestor = self.parent_id(*id);
+            while let Some(parent_id) = ancestor {
+                if picked.contains(&parent_id) {
+                    break;
+                }
+                ancestor = self.parent_id(parent_id);
+            }
+            if ancestor.is_none() {
+                roots.push(*id);
+            }
+        }
+        roots
+    }
+
+    //copies the items, with their subtasks, to the end of the destination list, returns their new ids there
+    pub fn copy_items_to(&self, ids: &[usize], destination: &mut TodoList) -> Vec<usize> {
+        let items = self.transfer_roots(ids).into_iter()
+            .filter_map(|id| self.get_item_by_id(id).cloned())
+            .collect();
+        destination.import_items(items)
+    }
+
+    //like copy_items_to, but the items are taken out of this list for good
+    pub fn move_items_to(&mut self, ids: &[usize], destination: &mut TodoList) -> Vec<usize> {
+        let roots = self.transfer_roots(ids);
+        let new_ids = self.copy_items_to(&roots, destination);
+        for id in roots {
+            self.detach_item(id);
+        }
+        new_ids
+    }
+
+    //adds items from another list at the top level, giving them (and their subtasks) fresh ids
+    //everything else about them is kept, except what adopt_items drops
+    pub fn import_items(&mut self, items: Vec<TodoListItem>) -> Vec<usize> {
+        let items = self.adopt_items(items);
+        let roots = items.iter().map(|x| x.id).collect();
+        self.items.extend(items);
+        roots
+    }
+
+    //takes the item out for good (it doesn't go in the trash), keeping what's needed to put it back with attach_item
+    pub fn detach_item(&mut self, id: usize) -> Option<DetachedItem> {
+        let parent_id = self.parent_id(id);
+        let siblings = self.siblings_mut(id)?;
+        let index = siblings.iter().position(|x| x.id == id).expect("siblings_mut returns the Vec holding id");
+        let item = siblings.remove(index);
+
+        let mut removed_items = Vec::new();
+        item.collect_with_subtasks(&mut removed_items);
+        let removed_ids: HashSet<usize> = removed_items.iter().map(|x| x.id).collect();
+        let mut blocked_refs = Vec::new();
+        self.for_each_item_mut(&mut |other| {
+            for blocker in other.blocked_by.iter().filter(|x| removed_ids.contains(x)) {
+                blocked_refs.push((other.id, *blocker));
+            }
+            other.blocked_by.retain(|x| !removed_ids.contains(x));
+        });
+
+        Some(DetachedItem { item, parent_id, index, blocked_refs })
+    }
+
+    //puts a detached item back, restoring blockers that pointed at it if those items are still around
+    //returns false without changing anything if its parent is gone or any of its ids are already in use
+    pub fn attach_item(&mut self, detached: DetachedItem) -> bool {
+        let mut incoming = Vec::new();
+        detached.item.collect_with_subtasks(&mut incoming);
+        if incoming.iter().any(|x| self.get_item_by_id(x.id).is_some()) {
+            return false;
+        }
+        let siblings = match detached.parent_id {
+            Some(parent_id) => match self.get_item_mut_by_id(parent_id) {
+                Some(parent) => &mut parent.subtasks,
+                None => return false,
+            },
+            None => &mut self.items,
+        };
+        siblings.insert(detached.index.min(siblings.len()), detached.item);
+
+        for (id, blocker_id) in detached.blocked_refs {
+            if let Some(item) = self.get_item_mut_by_id(id) {
+                item.blocked_by.insert(blocker_id);
+            }
+        }
+        true
+    }
+
+    fn for_each_item_mut(&mut self, f: &mut impl FnMut(&mut TodoListItem)) {
+        visit_items_mut(&mut self.items, f);
+    }
+
+    //like for_each_item_mut, but also goes through the trash and the archive
+    fn for_each_stored_item_mut(&mut self, f: &mut impl FnMut(&mut TodoListItem)) {
+        visit_items_mut(&mut self.items, f);
+        let trashed = self.trash.iter_mut().map(|x| &mut x.detached);
+        let archived = self.archive.iter_mut().map(|x| &mut x.detached);
+        for detached in trashed.chain(archived) {
+            visit_items_mut(std::slice::from_mut(&mut detached.item), f);
+        }
+    }
+
+    pub fn fields(&self) -> &[FieldDef] {
+        &self.fields
+    }
+
+    pub fn field(&self, name: &str) -> Option<&FieldDef> {
+        self.fields.iter().find(|x| x.name == name)
+    }
+
+    pub fn add_field(&mut self, field: FieldDef) -> Result<(), FieldError> {
+        if field.name.trim().is_empty() {
+            return Err(FieldError::EmptyName);
+        }
+        if self.field(&field.name).is_some() {
+            return Err(FieldError::DuplicateField(field.name));
+        }
+        if matches!(&field.kind, FieldKind::Choice(choices) if choices.is_empty()) {
+            return Err(FieldError::NoChoices);
+        }
+        self.fields.push(field);
+        Ok(())
+    }
+
+    //values stored under the old name move to the new one, including on trashed and archived items
+    pub fn rename_field(&mut self, name: &str, new_name: &str) -> Result<(), FieldError> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(FieldError::EmptyName);
+        }
+        if new_name != name && self.field(new_name).is_some() {
+            return Err(FieldError::DuplicateField(String::from(new_name)));
+        }
+        let field = self.fields.iter_mut().find(|x| x.name == name).ok_or_else(|| FieldError::UnknownField(String::from(name)))?;
+        field.name = String::from(new_name);
+        self.for_each_stored_item_mut(&mut |item| {
+            if let Some(value) = item.field_values.remove(name) {
+                item.field_values.insert(String::from(new_name), value);
+            }
+        });
+        Ok(())
+    }
+
+    //drops the field and every item's value for it, returns false if there was no such field
+    pub fn remove_field(&mut self, name: &str) -> bool {
+        let before = self.fields.len();
+        self.fields.retain(|x| x.name != name);
+        if self.fields.len() == before {
+            return false;
+        }
+        self.for_each_stored_item_mut(&mut |item| {
+            item.field_values.remove(name);
+        });
+        true
+    }
+
+    //None clears the value; values are checked against the field's kind first
+    pub fn set_field_value(&mut self, id: usize, name: &str, value: Option<FieldValue>) -> Result<(), FieldError> {
+        let field = self.field(name).ok_or_else(|| FieldError::UnknownField(String::from(name)))?;
+        if let Some(value) = &value {
+            field.validate(value)?;
+        }
+        let item = self.get_item_mut_by_id(id).ok_or(FieldError::MissingItem(id))?;
+        match value {
+            Some(value) => item.field_values.insert(String::from(name), value),
+            None => item.field_values.remove(name),
+        };
+        Ok(())
+    }
+
+    pub fn statuses(&self) -> &[StatusDef] {
+        &self.statuses
+    }
+
+    pub fn status(&self, name: &str) -> Option<&StatusDef> {
+        self.statuses.iter().find(|x| x.name == name)
+    }
+
+    //the status the item is in; only None for a list that somehow has no status of the item's category
+    pub fn status_of(&self, item: &TodoListItem) -> Option<&StatusDef> {
+        statuses::resolve(&self.statuses, item.status.as_deref(), item.completed)
+    }
+
+    //the first status of the category, where items go when they're ticked or unticked
+    //lists always have one of each, but one saved without them (and not loaded through from_file) gets the built-in default
+    fn default_status(&self, done: bool) -> StatusDef {
+        statuses::default_status(&self.statuses, done).cloned()
+            .unwrap_or_else(|| statuses::default_statuses().into_iter().find(|x| x.done == done).expect("the defaults have both categories"))
+    }
+
+    pub fn add_status(&mut self, status: StatusDef) -> Result<(), StatusError> {
+        let name = status.name.trim();
+        if name.is_empty() {
+            return Err(StatusError::EmptyName);
+        }
+        if self.status(name).is_some() {
+            return Err(StatusError::DuplicateStatus(String::from(name)));
+        }
+        self.statuses.push(StatusDef::new(name, status.done));
+        Ok(())
+    }
+
+    //items in the status move to the new name, and so do past changes in their logs, including on trashed and archived items
+    pub fn rename_status(&mut self, name: &str, new_name: &str) -> Result<(), StatusError> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(StatusError::EmptyName);
+        }
+        if new_name != name && self.status(new_name).is_some() {
+            return Err(StatusError::DuplicateStatus(String::from(new_name)));
+        }
+        let status = self.statuses.iter_mut().find(|x| x.name == name).ok_or_else(|| StatusError::UnknownStatus(String::from(name)))?;
+        status.name = String::from(new_name);
+        self.board.rename_column(name, new_name);
+        let rename = |x: &mut String| if x == name { *x = String::from(new_name) };
+        if let ListKind::Kanban { columns } = &mut self.kind {
+            columns.iter_mut().for_each(rename);
+        }
+        self.for_each_stored_item_mut(&mut |item| {
+            if let Some(status) = &mut item.status {
+                rename(status);
+            }
+            for change in &mut item.status_log {
+                rename(&mut change.from);
+                rename(&mut change.to);
+            }
+        });
+        Ok(())
+    }
+
+    //items in the status move to the default status of the same category, which is logged as a change
+    //a kanban list loses the column too, unless that would leave it with fewer than kinds::MIN_COLUMNS
+    pub fn remove_status(&mut self, name: &str, now: DateTime<Local>) -> Result<(), StatusError> {
+        self.can_remove_status(name)?;
+        let done = self.status(name).expect("can_remove_status checks the status is there").done;
+        if let ListKind::Kanban { columns } = &mut self.kind {
+            columns.retain(|x| x != name);
+        }
+        let before = self.status_names();
+        self.statuses.retain(|x| x.name != name);
+        self.board.remove_column(name);
+        let fallback = self.default_status(done).name;
+        self.for_each_stored_item_mut(&mut |item| {
+            if item.status.as_deref() == Some(name) {
+                item.status = Some(fallback.clone());
+            }
+        });
+        self.log_status_changes(&before, now);
+        Ok(())
+    }
+
+    //the error remove_status would give, without removing anything
+    pub fn can_remove_status(&self, name: &str) -> Result<(), StatusError> {
+        let status = self.status(name).ok_or_else(|| StatusError::UnknownStatus(String::from(name)))?;
+        if self.statuses.iter().filter(|x| x.done == status.done).count() == 1 {
+            return Err(StatusError::LastOfCategory(String::from(name)));
+        }
+        if self.columns().iter().any(|x| x == name) && self.columns().len() <= kinds::MIN_COLUMNS {
+            return Err(StatusError::NeededColumn(String::from(name)));
+        }
+        Ok(())
+    }
+
+    //moves a status to index among the others, which can change the default for its category;
+    //returns false if there's no such status
+    pub fn move_status(&mut self, name: &str, index: usize) -> bool {
+        let Some(from) = self.statuses.iter().position(|x| x.name == name) else {
+            return false;
+        };
+        let status = self.statuses.remove(from);
+        self.statuses.insert(index.min(self.statuses.len()), status);
+        true
+    }
+
+    //moving to a status of the other category completes or reopens the item, with everything that comes with that
+    //(recurring items move on to their next occurrence and land back on the default open status, parents follow
+    //their subtasks if auto_complete_parents is set)
+    pub fn set_status(&mut self, id: usize, name: &str, now: DateTime<Local>) -> Result<(), StatusError> {
+        let done = self.status(name).ok_or_else(|| StatusError::UnknownStatus(String::from(name)))?.done;
+        let completed = self.get_item_by_id(id).ok_or(StatusError::MissingItem(id))?.completed;
+        let before = self.status_names();
+        if completed != done {
+            self.change_completion(id, done, now);
+        }
+        if let Some(item) = self.get_item_mut_by_id(id).filter(|x| x.completed == done) {
+            item.status = Some(String::from(name));
+        }
+        self.log_status_changes(&before, now);
+        Ok(())
+    }
+
+    //gives every item, trashed and archived ones included, the name of a status of the list that matches whether it's
+    //completed, for items loaded without one or with one the list doesn't have
+    fn pin_statuses(&mut self) {
+        let statuses = self.statuses.clone();
+        self.for_each_stored_item_mut(&mut |item| {
+            item.status = statuses::resolve(&statuses, item.status.as_deref(), item.completed).map(|x| x.name.clone());
+        });
+    }
+
+    //every item's status by id, taken before a change so log_status_changes can tell what it moved
+    fn status_names(&self) -> HashMap<usize, String> {
+        self.all_items().into_iter()
+            .filter_map(|item| Some((item.id, self.status_of(item)?.name.clone())))
+            .collect()
+    }
+
+    //adds a StatusChange to every item whose status isn't the one it had in before
+    fn log_status_changes(&mut self, before: &HashMap<usize, String>, now: DateTime<Local>) {
+        let statuses = self.statuses.clone();
+        self.for_each_item_mut(&mut |item| {
+            let current = statuses::resolve(&statuses, item.status.as_deref(), item.completed);
+            if let (Some(old), Some(current)) = (before.get(&item.id), current) {
+                if *old != current.name {
+                    let change = StatusChange { from: old.clone(), to: current.name.clone(), at: now };
+                    item.status_log.push(change);
+                }
+            }
+        });
+    }
+
+    //makes sure the list has an open and a done status and one for each kanban column, and writes down every item's
+    //status; items from kanban files saved before columns were statuses go to the status of their column
+    //returns true if a status had to be added
+    pub fn repair_statuses(&mut self) -> bool {
+        let mut added = false;
+        for done in [false, true] {
+            if statuses::default_status(&self.statuses, done).is_none() {
+                let default = statuses::default_statuses().into_iter().find(|x| x.done == done).expect("the defaults have both categories");
+                self.statuses.retain(|x| x.name != default.name);
+                self.statuses.push(default);
+                added = true;
+            }
+        }
+        let count = self.statuses.len();
+        self.add_column_statuses();
+        added |= self.statuses.len() != count;
+
+        let statuses = self.statuses.clone();
+        self.for_each_stored_item_mut(&mut |item| {
+            if let Some(column) = item.column.take() {
+                if statuses.iter().any(|x| x.name == column && x.done == item.completed) {
+                    item.status = Some(column);
+                }
+            }
+        });
+        self.pin_statuses();
+        added
+    }
+
+    //a kanban list's columns are statuses, so each column the list has no status for gets one, which counts as done
+    //if it's the last column and as open otherwise
+    fn add_column_statuses(&mut self) {
+        let ListKind::Kanban { columns } = &self.kind else {
+            return;
+        };
+        for (index, column) in columns.iter().enumerate() {
+            if !self.statuses.iter().any(|x| x.name == *column) {
+                self.statuses.push(StatusDef::new(column, index + 1 == columns.len()));
+            }
+        }
+    }
+
+    pub fn kind(&self) -> &ListKind {
+        &self.kind
+    }
+
+    //a list made daily (or a habit list) counts as just reset, so whatever is already ticked stays ticked until the next reset time
+    //a kanban list gets a status for each of its columns that the list doesn't have yet, see add_column_statuses
+    pub fn set_kind(&mut self, kind: ListKind, now: DateTime<Local>) {
+        self.last_reset = kind.reset_time().map(|_| now);
+        self.kind = kind;
+        self.add_column_statuses();
+        //settings for columns the board doesn't have any more would come back if a column of the same name did
+        let columns: Vec<String> = self.board_columns().into_iter().map(String::from).collect();
+        self.board.retain_columns(&columns);
+        self.update_checklist(now);
+    }
+
+    //starts a new day for a daily list once its reset time has gone by: done items are unticked, and items that weren't
+    //done roll over, counting one more carried day for every day that went by (so a list left closed over a weekend
+    //counts the whole weekend) and moving any due date left behind onto the new day, at the same time of day
+    //habit lists are unticked the same way, but nothing carries over, since a missed day just ends the item's streak
+    //returns true if it did anything; call it when the list is loaded and whenever the date might have changed
+    pub fn roll_over(&mut self, now: DateTime<Local>) -> bool {
+        let Some(reset_at) = self.kind.reset_time() else {
+            return false;
+        };
+        let carry_over = matches!(self.kind, ListKind::Daily { .. });
+        let Some(reset_time) = kinds::last_reset_time(reset_at, now) else {
+            return false;
+        };
+        //lists that have never been reset count as a day behind
+        let days = match self.last_reset.and_then(|x| kinds::last_reset_time(reset_at, x)) {
+            Some(last) if last >= reset_time => return false,
+            Some(last) => (reset_time.date_naive() - last.date_naive()).num_days(),
+            None => 1,
+        };
+        self.last_reset = Some(now);
+        let before = self.status_names();
+        let open = self.default_status(false);
+        self.for_each_item_mut(&mut |item| {
+            if item.completed {
+                item.move_to_status(&open, now);
+                item.carried_days = 0;
+            } else if carry_over {
+                item.carried_days = item.carried_days.saturating_add(u32::try_from(days).unwrap_or(u32::MAX));
+                if let Some(due) = item.due.filter(|x| *x < reset_time) {
+                    item.due = Some(kinds::carry_due_date(due, reset_time));
+                }
+            }
+        });
+        self.log_status_changes(&before, now);
+        true
+    }
+
+    pub fn archived_at(&self) -> Option<DateTime<Local>> {
+        self.archived_at
+    }
+
+    //archives a checklist once every item in it is done, and brings it back if one is reopened;
+    //returns true if that changed anything
+    pub fn update_checklist(&mut self, now: DateTime<Local>) -> bool {
+        let finished = self.kind == ListKind::Checklist && !self.items.is_empty() && self.all_items().iter().all(|x| x.completed);
+        if finished == self.archived_at.is_some() {
+            return false;
+        }
+        self.archived_at = finished.then_some(now);
+        true
+    }
+
+    //the columns of a kanban list, in order, each the name of one of its statuses; empty for other kinds of list
+    pub fn columns(&self) -> &[String] {
+        match &self.kind {
+            ListKind::Kanban { columns } => columns,
+            _ => &[],
+        }
+    }
+
+    //the column an item is in on a kanban board, which is its status; items in a status that isn't a column are shown
+    //in the first column of the same category, or failing that the last column if they're done and the first if not
+    pub fn column_of(&self, item: &TodoListItem) -> Option<&str> {
+        let status = self.status_of(item)?;
+        let columns = self.columns();
+        let same_category = |x: &&String| self.status(x).is_some_and(|x| x.done == status.done);
+        let column = columns.iter().find(|x| **x == status.name)
+            .or_else(|| columns.iter().find(same_category))
+            .or(if status.done { columns.last() } else { columns.first() });
+        column.map(|x| x.as_str())
+    }
+
+    //how the list is drawn; kanban lists are shown as a board and everything else as a list until that's changed
+    pub fn view(&self) -> ListView {
+        match self.view {
+            Some(view) => view,
+            None if matches!(self.kind, ListKind::Kanban { .. }) => ListView::Board,
+            None => ListView::List,
+        }
+    }
+
+    pub fn set_view(&mut self, view: ListView) {
+        self.view = Some(view);
+    }
+
+    pub fn board(&self) -> &BoardSettings {
+        &self.board
+    }
+
+    pub fn board_mut(&mut self) -> &mut BoardSettings {
+        &mut self.board
+    }
+
+    //the columns of the board view, in order: a kanban list's own columns, or else one per status
+    pub fn board_columns(&self) -> Vec<&str> {
+        match &self.kind {
+            ListKind::Kanban { columns } => columns.iter().map(|x| x.as_str()).collect(),
+            _ => self.statuses.iter().map(|x| x.name.as_str()).collect(),
+        }
+    }
+
+    pub fn board_column_of(&self, item: &TodoListItem) -> Option<&str> {
+        match &self.kind {
+            ListKind::Kanban { .. } => self.column_of(item),
+            _ => self.status_of(item).map(|x| x.name.as_str()),
+        }
+    }
+
+    //the top-level items in a column of the board, in list order
+    pub fn board_cards(&self, column: &str) -> Vec<&TodoListItem> {
+        self.items.iter().filter(|x| self.board_column_of(x) == Some(column)).collect()
+    }
+
+    //see Command::move_to_board_column
+    pub fn move_to_board_column(&mut self, id: usize, column: &str, now: DateTime<Local>) -> Result<(), BoardError> {
+        Command::move_to_board_column(self, id, column, now)?.apply(self).map(|_| ()).ok_or(BoardError::MissingItem(id))
+    }
+
+    //makes id wait on blocker_id
+    pub fn add_dependency(&mut self, id: usize, blocker_id: usize) -> Result<(), DependencyError> {
+        if id == blocker_id {
+            return Err(DependencyError::BlocksItself);
+        }
+        for x in [id, blocker_id] {
+            if self.get_item_by_id(x).is_none() {
+                return Err(DependencyError::MissingItem(x));
+            }
+        }
+        if self.depends_on(blocker_id, id) {
+            return Err(DependencyError::Cycle);
+        }
+
+        self.get_item_mut_by_id(id).expect("checked above").blocked_by.insert(blocker_id);
+        Ok(())
+    }
+
+    pub fn remove_dependency(&mut self, id: usize, blocker_id: usize) {
+        if let Some(item) = self.get_item_mut_by_id(id) {
+            item.blocked_by.remove(&blocker_id);
+        }
+    }
+
+    //true if id is blocked by blocker_id directly or through a chain of other blockers
+    pub fn depends_on(&self, id: usize, blocker_id: usize) -> bool {
+        let mut seen: HashSet<usize> = HashSet::new();
+        let mut to_visit = vec![id];
+        while let Some(current) = to_visit.pop() {
+            if !seen.insert(current) {
+                continue;
+            }
+            if let Some(item) = self.get_item_by_id(current) {
+                if item.blocked_by.contains(&blocker_id) {
+                    return true;
+                }
+                to_visit.extend(item.blocked_by.iter().copied());
+            }
+        }
+        false
+    }
+
+    //the blockers of id that aren't completed yet
+    pub fn open_blockers(&self, id: usize) -> Vec<&TodoListItem> {
+        match self.get_item_by_id(id) {
+            Some(item) => item.blocked_by.iter()
+                .filter_map(|x| self.get_item_by_id(*x))
+                .filter(|x| !x.completed)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn is_blocked(&self, id: usize) -> bool {
+        !self.open_blockers(id).is_empty()
+    }
+
+    //open items that aren't waiting on anything
+    pub fn actionable_items(&self) -> Vec<&TodoListItem> {
+        self.all_items().into_iter().filter(|x| !x.completed && !self.is_blocked(x.id)).collect()
+    }
+
+    fn take_item(&mut self, id: usize) -> Option<TodoListItem> {
+        fn take(items: &mut Vec<TodoListItem>, id: usize) -> Option<TodoListItem> {
+            if let Some(i) = items.iter().position(|x| x.id == id) {
+                return Some(items.remove(i));
+            }
+            items.iter_mut().find_map(|x| take(&mut x.subtasks, id))
+        }
+        take(&mut self.items, id)
+    }
+
+    pub fn from_file(path: &PathBuf) -> Result<TodoList, io::Error> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let mut contents: TodoList = serde_json::from_reader(reader)?;
+        contents.repair();
+        Ok(contents)
+    }
+
+    //fixes up a list read back from JSON, from a file or the app's saved state
+    fn repair(&mut self) {
+        //duplicate ids can come from hand-edited files or files saved before ids were allocated properly
+        self.repair_ids();
+        //kanban lists need columns to put their items in
+        self.kind.repair();
+        //files from before statuses existed only have completed, which picks the default status for each item, and
+        //kanban files from before columns were statuses keep each item's column as its status
+        self.repair_statuses();
+    }
+
+    pub fn save(&mut self, path: &PathBuf) -> Result<(), io::Error> {
+        let json = serde_json::to_string(self)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+impl TodoListItem {
+    pub fn new(id: usize, name: String, description: String) -> TodoListItem {
+        let date_created = Local::now();
+        TodoListItem {
+            id,
+            name,
+            description,
+            date_created,
+            completed: false,
+            due: None,
+            start: None,
+            priority: Priority::None,
+            subtasks: Vec::new(),
+            tags: BTreeSet::new(),
+            recurrence: None,
+            completion_history: Vec::new(),
+            skipped_occurrences: 0,
+            completed_at: None,
+            completion_log: Vec::new(),
+            blocked_by: BTreeSet::new(),
+            field_values: BTreeMap::new(),
+            column: None,
+            carried_days: 0,
+            status: None,
+            status_log: Vec::new(),
+        }
+    }
+
+    //puts the item in status, and makes completed (along with completed_at and the completion log) follow its category
+    //callers in TodoList log the move itself with log_status_changes
+    fn move_to_status(&mut self, status: &StatusDef, now: DateTime<Local>) {
+        self.status = Some(status.name.clone());
+        if self.completed == status.done {
+            return;
+        }
+        self.completed = status.done;
+        self.completed_at = if status.done { Some(now) } else { None };
+        let change = if status.done { CompletionChange::Completed } else { CompletionChange::Reopened };
+        self.completion_log.push(CompletionEvent { change, at: now });
+    }
+
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    pub fn completed_at(&self) -> Option<DateTime<Local>> {
+        self.completed_at
+    }
+
+    pub fn carried_days(&self) -> u32 {
+        self.carried_days
+    }
+
+    pub fn completion_log(&self) -> &[CompletionEvent] {
+        &self.completion_log
+    }
+
+    //use TodoList::status_of for the status itself, since it depends on the list's statuses
+    pub fn status_log(&self) -> &[StatusChange] {
+        &self.status_log
+    }
+
+    //use TodoList::set_field_value to change these, so they're checked against the list's fields
+    pub fn field_value(&self, name: &str) -> Option<&FieldValue> {
+        self.field_values.get(name)
+    }
+
+    pub fn field_values(&self) -> &BTreeMap<String, FieldValue> {
+        &self.field_values
+    }
+
+    //use TodoList::add_dependency to change these, so cycles are caught
+    pub fn blocked_by(&self) -> &BTreeSet<usize> {
+        &self.blocked_by
+    }
+
+    pub fn is_overdue(&self, now: DateTime<Local>) -> bool {
+        !self.completed && self.due.is_some_and(|due| due < now)
+    }
+
+    //items without a start date count as started
+    pub fn has_started(&self, now: DateTime<Local>) -> bool {
+        self.start.map_or(true, |start| start <= now)
+    }
+
+    //returns false if the item already had the tag
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        self.tags.insert(String::from(tag.trim()))
+    }
+
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        self.tags.remove(tag.trim())
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag.trim())
+    }
+
+    //records a completion of a recurring item and moves its dates on to the first occurrence after now, back in the
+    //open status, reopening its subtasks; once the rule runs out the item is completed for good, in the done status
+    //returns false if the item doesn't repeat, so the caller should complete it normally
+    fn advance_recurrence(&mut self, open: &StatusDef, done: &StatusDef, now: DateTime<Local>) -> bool {
+        let Some(recurrence) = &self.recurrence else {
+            return false;
+        };
+
+        //occurrences used up so far, this one included; occurrences that were missed while the item sat overdue are
+        //skipped, but still count toward COUNT like they would in any other calendar
+        let mut used = self.completion_history.len() as u64 + 1 + u64::from(self.skipped_occurrences);
+        let mut next = recurrence.next_after(self.due.unwrap_or(now));
+        while let Some(date) = next {
+            if recurrence.count.is_some_and(|count| used >= u64::from(count)) {
+                next = None;
+            } else if date > now {
+                break;
+            } else {
+                used += 1;
+                self.skipped_occurrences += 1;
+                next = recurrence.next_after(date);
+            }
+        }
+
+        self.completion_history.push(now);
+        //the rule has run out through COUNT or UNTIL, so this completion is the last one
+        let Some(next) = next else {
+            self.move_to_status(done, now);
+            return true;
+        };
+        self.completion_log.push(CompletionEvent { change: CompletionChange::Completed, at: now });
+
+        if let (Some(start), Some(due)) = (self.start, self.due) {
+            self.start = Some(start + (next - due));
+        }
+        self.due = Some(next);
+        self.status = Some(open.name.clone());
+        self.reopen_subtasks(open, now);
+        true
+    }
+
+    fn reopen_subtasks(&mut self, open: &StatusDef, now: DateTime<Local>) {
+        for subtask in &mut self.subtasks {
+            if subtask.completed {
+                subtask.move_to_status(open, now);
+            }
+            subtask.reopen_subtasks(open, now);
+        }
+    }
+
+    //(completed, total) for the item's direct subtasks
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self.subtasks.iter().filter(|x| x.completed).count();
+        (done, self.subtasks.len())
+    }
+
+    //looks through this item and everything below it
+    pub fn find(&self, id: usize) -> Option<&TodoListItem> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.subtasks.iter().find_map(|x| x.find(id))
+    }
+
+    fn find_mut(&mut self, id: usize) -> Option<&mut TodoListItem> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.subtasks.iter_mut().find_map(|x| x.find_mut(id))
+    }
+
+    fn collect_with_subtasks<'a>(&'a self, all: &mut Vec<&'a TodoListItem>) {
+        all.push(self);
+        for subtask in &self.subtasks {
+            subtask.collect_with_subtasks(all);
+        }
+    }
+
+    pub fn date_created(&self) -> DateTime<Local> {
+        self.date_created
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(clippy::single_component_path_imports)]
+    use tempfile;
+    use std::io::{Read, Write};
+
+    fn make_empty_list() -> TodoList {
+        TodoList::new(String::from("test list"))
+    }
+
+    fn make_one_item_list() -> TodoList {
+        let mut list = TodoList::new(String::from("test list"));
+        list.add(String::from("Test Item"), String::from("Test Description"));
+        list
+    }
+
+    fn make_item_no_list() -> TodoListItem {
+        TodoListItem::new(999, String::from("Test Item"), String::from("Test Description"))
+    }
+
+    #[test]
+    fn create_todo_list() {
+        let list = make_empty_list();
+        assert_eq!(list.title, String::from("test list"));
+    }
+
+    #[test]
+    fn create_list_item() {
+        let item = make_item_no_list();
+        assert_eq!(item.name, String::from("Test Item"));
+        assert_eq!(item.description, String::from("Test Description"));
+        assert!(!item.completed);
+        //TODO assert something about item.date_created();
+    }
+
+    #[test]
+    fn add_item_to_todo_list() {
+        let list = make_one_item_list();
+
+        assert_eq!(list.items[0].name, String::from("Test Item"));
+        assert_eq!(list.items[0].description, String::from("Test Description"));
+    }
+
+    #[test]
+    fn clear_list() {
+        let mut list = make_one_item_list();
+        list.clear_list(fixed_now());
+        assert!(list.items.is_empty());
+        assert_eq!(list.trash().len(), 1);
+    }
+
+    #[test]
+    fn remove_item_from_list() {
+        let mut list = make_one_item_list();
+        list.add(String::from("New Item"), String::from("Other Description"));
+        list.remove_item(0);
+
+        assert!(list.items.len() == 1);
+        assert_eq!(list.items[0].name, String::from("New Item"));
+    }
+
+    #[test]
+    fn only_removes_one_item() {
+        let mut list = make_one_item_list();
+        list.add(String::from("New Item"), String::from("Other Description"));
+        list.add(String::from("New Item"), String::from("Other Description"));
+
+        list.remove_item(1);
+        assert!(list.items.len() == 2);
+    }
+
+    #[test]
+    fn clears_whole_list() {
+        let mut list = make_one_item_list();
+        list.add(String::from("New Item"), String::from("Other Description"));
+        list.add(String::from("New Item"), String::from("Other Description"));
+
+        list.clear_list(fixed_now());
+        assert!(list.items.is_empty());
+        assert_eq!(list.trash().len(), 3);
+    }
+
+    #[test]
+    fn saves_json_to_file() {
+        let mut list = make_one_item_list();
+
+        let mut temp = tempfile::NamedTempFile::new().expect("saves_json_to_file() test panicked; temporary file creation failed.");
+        let temp_path = temp.path().to_path_buf();
+
+        list.save(&temp_path).expect("saves_json_to_file() test panicked; temporary file created but could not be saved to.");
+        
+        let mut temp_contents = String::new();
+
+        temp.read_to_string(&mut temp_contents).expect("saves_json_to_file() test panicked; temporary file created but could not be read from.");
+
+        //eprintln!("{}", temp_contents); // run with `-- --nocapture` argument to `cargo test` 
+
+        assert!(!temp_contents.is_empty()); //for now, we assert anything is in the file; TODO more specific assertions?
+        temp.close().expect("saves_json_to_file() test panicked; temporary file was not deleted afterwards.");
+    }
+
+    #[test]
+    fn loads_json_from_file() {
+        let mut temp = tempfile::NamedTempFile::new().expect("loads_json_from_file() test panicked; temporary file creation failed.");
+
+        let contents = String::from("{\"title\":\"test list\",\"items\":\
+        [{\"id\":0,\"name\":\"Test Item\",\"description\":\"Test Description\",\"date_created\":\"2024-05-22T15:40:04.970459400-04:00\",\"completed\":false}],\
+        \"date_created\":\"2024-05-22T15:40:04.969858100-04:00\"}");
+
+        temp.write_all(contents.as_bytes()).expect("loads_json_from_file() test panicked; temporary file created, but could not be written to.");
+        let temp_path = temp.path().to_path_buf();
+
+        let list = TodoList::from_file(&temp_path).expect("loads_json_from_file() test panicked; a temp file was created, but TodoList::from_file could not construct a TodoList.");
+
+        assert!(!list.items.is_empty());
+
+        //eprintln!("{}", temp_path.display()); // run with `-- --nocapture` argument to `cargo test` 
+        temp.close().expect("saves_json_to_file() test panicked; temporary file was not deleted afterwards.");
+
+    }
+
+    #[test]
+    fn ids_not_reused_after_removal() {
+        let mut list = make_one_item_list();
+        let second = list.add(String::from("New Item"), String::from("Other Description"));
+        list.remove_item(second);
+        let third = list.add(String::from("Newer Item"), String::from("Other Description"));
+
+        assert_ne!(second, third);
+        assert_eq!(list.get_item_by_id(third).unwrap().name, String::from("Newer Item"));
+    }
+
+    #[test]
+    fn ids_stay_unique_after_removing_first_item() {
+        let mut list = make_one_item_list();
+        list.add(String::from("New Item"), String::from("Other Description"));
+        list.remove_item(0);
+        let new_id = list.add(String::from("Newer Item"), String::from("Other Description"));
+
+        assert_ne!(list.items[0].id(), new_id);
+    }
+
+    #[test]
+    fn repairs_duplicate_ids_on_load() {
+        let mut temp = tempfile::NamedTempFile::new().expect("repairs_duplicate_ids_on_load() test panicked; temporary file creation failed.");
+
+        let contents = String::from("{\"title\":\"test list\",\"items\":[\
+        {\"id\":3,\"name\":\"First\",\"description\":\"\",\"date_created\":\"2024-05-22T15:40:04.970459400-04:00\",\"completed\":false},\
+        {\"id\":3,\"name\":\"Second\",\"description\":\"\",\"date_created\":\"2024-05-22T15:40:04.970459400-04:00\",\"completed\":false}],\
+        \"date_created\":\"2024-05-22T15:40:04.969858100-04:00\"}");
+
+        temp.write_all(contents.as_bytes()).expect("repairs_duplicate_ids_on_load() test panicked; temporary file created, but could not be written to.");
+        let temp_path = temp.path().to_path_buf();
+
+        let mut list = TodoList::from_file(&temp_path).expect("repairs_duplicate_ids_on_load() test panicked; TodoList::from_file could not construct a TodoList.");
+
+        assert_eq!(list.items[0].id(), 3);
+        assert_ne!(list.items[1].id(), 3);
+        let new_id = list.add(String::from("Third"), String::new());
+        assert!(new_id != 3 && new_id != list.items[1].id());
+
+        temp.close().expect("repairs_duplicate_ids_on_load() test panicked; temporary file was not deleted afterwards.");
+    }
+
+    #[test]
+    fn ids_survive_save_and_load() {
+        let mut list = make_one_item_list();
+        let removed = list.add(String::from("New Item"), String::from("Other Description"));
+        list.remove_item(removed);
+
+        let temp = tempfile::NamedTempFile::new().expect("ids_survive_save_and_load() test panicked; temporary file creation failed.");
+        let temp_path = temp.path().to_path_buf();
+        list.save(&temp_path).expect("ids_survive_save_and_load() test panicked; could not save to the temporary file.");
+
+        let mut loaded = TodoList::from_file(&temp_path).expect("ids_survive_save_and_load() test panicked; could not load from the temporary file.");
+        assert_ne!(loaded.add(String::from("Newer Item"), String::new()), removed);
+
+        temp.close().expect("ids_survive_save_and_load() test panicked; temporary file was not deleted afterwards.");
+    }
+
+    fn fixed_now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 22, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn finds_overdue_today_and_upcoming_items() {
+        let now = fixed_now();
+        let mut list = make_empty_list();
+        let overdue = list.add(String::from("Overdue"), String::new());
+        let later_today = list.add(String::from("Later Today"), String::new());
+        let next_week = list.add(String::from("Next Week"), String::new());
+        let done = list.add(String::from("Done"), String::new());
+        list.add(String::from("No Due Date"), String::new());
+
+        list.change_item_dates_by_id(Some(now - Duration::days(1)), None, overdue);
+        list.change_item_dates_by_id(Some(now + Duration::hours(3)), None, later_today);
+        list.change_item_dates_by_id(Some(now + Duration::days(5)), None, next_week);
+        list.change_item_dates_by_id(Some(now - Duration::days(2)), None, done);
+        list.set_completed(done, true);
+
+        let overdue_ids: Vec<usize> = list.overdue_items(now).iter().map(|x| x.id()).collect();
+        let today_ids: Vec<usize> = list.due_today_items(now).iter().map(|x| x.id()).collect();
+        assert_eq!(overdue_ids, vec![overdue]);
+        assert_eq!(today_ids, vec![later_today]);
+        assert_eq!(list.upcoming_items(now, 7).len(), 1);
+        assert!(list.upcoming_items(now, 3).is_empty());
+    }
+
+    #[test]
+    fn item_without_start_date_has_started() {
+        let now = fixed_now();
+        let mut item = make_item_no_list();
+        assert!(item.has_started(now));
+
+        item.start = Some(now + Duration::days(1));
+        assert!(!item.has_started(now));
+    }
+
+    #[test]
+    fn parses_date_inputs() {
+        let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+
+        assert_eq!(parse_date_input("  ", end_of_day).unwrap(), None);
+        assert_eq!(parse_date_input("2024-05-22 17:00", end_of_day).unwrap(), Some(Local.with_ymd_and_hms(2024, 5, 22, 17, 0, 0).unwrap()));
+        assert_eq!(parse_date_input("2024-05-22", end_of_day).unwrap(), Some(Local.with_ymd_and_hms(2024, 5, 22, 23, 59, 59).unwrap()));
+        assert!(parse_date_input("next tuesday", end_of_day).is_err());
+    }
+
+    #[test]
+    fn due_dates_survive_save_and_load() {
+        let mut list = make_one_item_list();
+        list.change_item_dates_by_id(Some(fixed_now()), None, 0);
+
+        let temp = tempfile::NamedTempFile::new().expect("due_dates_survive_save_and_load() test panicked; temporary file creation failed.");
+        let temp_path = temp.path().to_path_buf();
+        list.save(&temp_path).expect("due_dates_survive_save_and_load() test panicked; could not save to the temporary file.");
+
+        let loaded = TodoList::from_file(&temp_path).expect("due_dates_survive_save_and_load() test panicked; could not load from the temporary file.");
+        assert_eq!(loaded.items[0].due, Some(fixed_now()));
+        assert_eq!(loaded.items[0].start, None);
+
+        temp.close().expect("due_dates_survive_save_and_load() test panicked; temporary file was not deleted afterwards.");
+    }
+
+    #[test]
+    fn sorts_and_filters_by_priority() {
+        let mut list = make_empty_list();
+        let low = list.add(String::from("Low"), String::new());
+        let urgent = list.add(String::from("Urgent"), String::new());
+        let none = list.add(String::from("None"), String::new());
+        let high = list.add(String::from("High"), String::new());
+        list.change_item_priority_by_id(Priority::Low, low);
+        list.change_item_priority_by_id(Priority::Urgent, urgent);
+        list.change_item_priority_by_id(Priority::High, high);
+
+        assert_eq!(list.items_with_priority_at_least(Priority::High).len(), 2);
+        assert_eq!(list.items_with_priority(Priority::None)[0].id(), none);
+
+        list.sort_by_priority();
+        let order: Vec<usize> = list.items.iter().map(|x| x.id()).collect();
+        assert_eq!(order, vec![urgent, high, low, none]);
+    }
+
+    #[test]
+    fn loads_items_without_priority() {
+        let item: TodoListItem = serde_json::from_str("{\"id\":0,\"name\":\"Test Item\",\"description\":\"\",\
+        \"date_created\":\"2024-05-22T15:40:04.970459400-04:00\",\"completed\":false}").unwrap();
+        assert_eq!(item.priority, Priority::None);
+    }
+
+    #[test]
+    fn adds_and_finds_subtasks() {
+        let mut list = make_one_item_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        let grandchild = list.add_subtask(child, String::from("Grandchild"), String::new()).unwrap();
+
+        assert_eq!(list.items.len(), 1);
+        assert_eq!(list.get_item_by_id(grandchild).unwrap().name, String::from("Grandchild"));
+        assert_eq!(list.parent_id(grandchild), Some(child));
+        assert_eq!(list.parent_id(0), None);
+        assert_eq!(list.all_items().len(), 3);
+        assert!(list.add_subtask(999, String::from("Orphan"), String::new()).is_none());
+    }
+
+    #[test]
+    fn subtask_ids_are_unique_across_the_tree() {
+        let mut list = make_one_item_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        let top = list.add(String::from("Top"), String::new());
+
+        assert_ne!(child, top);
+        assert_ne!(child, 0);
+    }
+
+    #[test]
+    fn removing_a_parent_removes_its_subtasks() {
+        let mut list = make_one_item_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        list.add_subtask(child, String::from("Grandchild"), String::new());
+
+        list.remove_item(child);
+        assert!(list.items[0].subtasks.is_empty());
+        assert_eq!(list.all_items().len(), 1);
+    }
+
+    #[test]
+    fn moves_subtasks_but_not_into_themselves() {
+        let mut list = make_one_item_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        let other = list.add(String::from("Other"), String::new());
+
+        assert!(!list.move_item_to_parent(0, Some(child)));
+        assert!(!list.move_item_to_parent(0, Some(0)));
+        assert!(list.move_item_to_parent(child, Some(other)));
+        assert_eq!(list.parent_id(child), Some(other));
+        assert!(list.move_item_to_parent(child, None));
+        assert_eq!(list.items.len(), 3);
+    }
+
+    #[test]
+    fn progress_and_auto_completion_roll_up() {
+        let mut list = make_one_item_list();
+        let first = list.add_subtask(0, String::from("First"), String::new()).unwrap();
+        let second = list.add_subtask(0, String::from("Second"), String::new()).unwrap();
+
+        list.set_completed(first, true);
+        assert_eq!(list.items[0].progress(), (1, 2));
+        assert!(!list.items[0].completed);
+
+        list.auto_complete_parents = true;
+        list.set_completed(second, true);
+        assert!(list.items[0].completed);
+
+        list.set_completed(first, false);
+        assert!(!list.items[0].completed);
+    }
+
+    #[test]
+    fn parses_tag_inputs() {
+        let tags = parse_tags("#errand, @home  #errand");
+        assert_eq!(tags.len(), 2);
+        assert!(tags.contains("#errand") && tags.contains("@home"));
+    }
+
+    #[test]
+    fn queries_items_by_tag() {
+        let mut list = make_one_item_list();
+        let errand = list.add(String::from("Errand"), String::new());
+        let subtask = list.add_subtask(errand, String::from("Subtask"), String::new()).unwrap();
+        list.change_item_tags_by_id(parse_tags("#errand @home"), errand);
+        list.change_item_tags_by_id(parse_tags("#errand"), subtask);
+        list.change_item_tags_by_id(parse_tags("@work"), 0);
+
+        assert_eq!(list.items_with_any_tag(&["@home", "@work"]).len(), 2);
+        assert_eq!(list.items_with_all_tags(&["#errand", "@home"])[0].id(), errand);
+        assert_eq!(list.items_with_all_tags(&[]).len(), 3);
+
+        let counts = list.tag_counts();
+        assert_eq!(counts.get("#errand"), Some(&2));
+        assert_eq!(counts.get("@work"), Some(&1));
+    }
+
+    #[test]
+    fn adds_and_removes_tags() {
+        let mut item = make_item_no_list();
+        assert!(item.add_tag(" @home "));
+        assert!(!item.add_tag("@home"));
+        assert!(item.has_tag("@home"));
+        assert!(item.remove_tag("@home"));
+        assert!(item.tags.is_empty());
+    }
+
+    #[test]
+    fn completing_a_recurring_item_reschedules_it() {
+        let now = fixed_now();
+        let mut list = make_one_item_list();
+        let subtask = list.add_subtask(0, String::from("Step"), String::new()).unwrap();
+        list.change_item_dates_by_id(Some(now), None, 0);
+        list.change_item_recurrence_by_id(Some(Recurrence::daily()), 0);
+        list.set_completed_at(subtask, true, now);
+
+        list.set_completed_at(0, true, now);
+        let item = list.get_item_by_id(0).unwrap();
+        assert!(!item.completed);
+        assert_eq!(item.due, Some(now + Duration::days(1)));
+        assert_eq!(item.completion_history, vec![now]);
+        assert!(!item.subtasks[0].completed);
+    }
+
+    #[test]
+    fn recurring_item_skips_missed_occurrences() {
+        let now = fixed_now();
+        let mut list = make_one_item_list();
+        list.change_item_dates_by_id(Some(now - Duration::days(3)), Some(now - Duration::days(4)), 0);
+        list.change_item_recurrence_by_id(Some(Recurrence::daily()), 0);
+
+        list.set_completed_at(0, true, now);
+        let item = list.get_item_by_id(0).unwrap();
+        assert_eq!(item.due, Some(now + Duration::days(1)));
+        assert_eq!(item.start, Some(now));
+    }
+
+    #[test]
+    fn recurring_item_completes_when_its_count_runs_out() {
+        let now = fixed_now();
+        let mut list = make_one_item_list();
+        list.change_item_dates_by_id(Some(now), None, 0);
+        list.change_item_recurrence_by_id(Some("FREQ=DAILY;COUNT=2".parse().unwrap()), 0);
+
+        list.set_completed_at(0, true, now);
+        assert!(!list.items[0].completed);
+        list.set_completed_at(0, true, now + Duration::days(1));
+        assert!(list.items[0].completed);
+        assert_eq!(list.items[0].completion_history.len(), 2);
+    }
+
+    #[test]
+    fn skipped_occurrences_count_toward_the_count() {
+        let now = fixed_now();
+        let mut list = make_one_item_list();
+        list.change_item_dates_by_id(Some(now - Duration::days(2)), None, 0);
+        list.change_item_recurrence_by_id(Some("FREQ=DAILY;COUNT=5".parse().unwrap()), 0);
+
+        //the occurrences due yesterday and today went by, so tomorrow's is the fourth of five
+        list.set_completed_at(0, true, now);
+        assert!(!list.items[0].completed);
+        assert_eq!(list.items[0].due, Some(now + Duration::days(1)));
+        list.set_completed_at(0, true, now + Duration::days(1));
+        assert!(!list.items[0].completed);
+        list.set_completed_at(0, true, now + Duration::days(2));
+        assert!(list.items[0].completed);
+        assert_eq!(list.items[0].completion_history.len(), 3);
+    }
+
+    #[test]
+    fn recurring_item_completes_once_when_it_passes_its_until_date() {
+        let now = fixed_now();
+        let mut list = make_one_item_list();
+        list.change_item_dates_by_id(Some(now), None, 0);
+        list.change_item_recurrence_by_id(Some("FREQ=DAILY;UNTIL=20240522".parse().unwrap()), 0);
+
+        list.set_completed_at(0, true, now);
+        assert!(list.items[0].completed);
+        assert_eq!(list.items[0].completed_at(), Some(now));
+        assert_eq!(list.items[0].completion_history.len(), 1);
+        assert_eq!(list.items[0].completion_log().len(), 1);
+    }
+
+    #[test]
+    fn recurrence_survives_save_and_load() {
+        let mut list = make_one_item_list();
+        list.change_item_recurrence_by_id(Some("weekly on mon,thu".parse().unwrap()), 0);
+
+        let temp = tempfile::NamedTempFile::new().expect("recurrence_survives_save_and_load() test panicked; temporary file creation failed.");
+        let temp_path = temp.path().to_path_buf();
+        list.save(&temp_path).expect("recurrence_survives_save_and_load() test panicked; could not save to the temporary file.");
+
+        let loaded = TodoList::from_file(&temp_path).expect("recurrence_survives_save_and_load() test panicked; could not load from the temporary file.");
+        assert_eq!(loaded.items[0].recurrence, Some(Recurrence::weekly_on(&[Weekday::Mon, Weekday::Thu])));
+
+        temp.close().expect("recurrence_survives_save_and_load() test panicked; temporary file was not deleted afterwards.");
+    }
+
+    #[test]
+    fn records_completion_time_and_log() {
+        let now = fixed_now();
+        let mut list = make_one_item_list();
+
+        list.set_completed_at(0, true, now);
+        assert_eq!(list.items[0].completed_at(), Some(now));
+
+        list.set_completed_at(0, false, now + Duration::hours(1));
+        assert_eq!(list.items[0].completed_at(), None);
+
+        list.set_completed_at(0, false, now + Duration::hours(2));
+        let changes: Vec<CompletionChange> = list.items[0].completion_log().iter().map(|x| x.change).collect();
+        assert_eq!(changes, vec![CompletionChange::Completed, CompletionChange::Reopened]);
+    }
+
+    #[test]
+    fn finds_items_completed_in_a_range() {
+        let now = fixed_now();
+        let mut list = make_one_item_list();
+        let last_week = list.add(String::from("Last Week"), String::new());
+        list.add(String::from("Open"), String::new());
+
+        list.set_completed_at(0, true, now);
+        list.set_completed_at(last_week, true, now - Duration::days(8));
+
+        let this_week = list.completed_between(now - Duration::days(7), now);
+        assert_eq!(this_week.len(), 1);
+        assert_eq!(this_week[0].id(), 0);
+    }
+
+    #[test]
+    fn auto_completed_parents_record_completion_time() {
+        let now = fixed_now();
+        let mut list = make_one_item_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        list.auto_complete_parents = true;
+
+        list.set_completed_at(child, true, now);
+        assert_eq!(list.items[0].completed_at(), Some(now));
+    }
+
+    fn make_three_item_list() -> TodoList {
+        let mut list = make_one_item_list();
+        list.add(String::from("Second"), String::new());
+        list.add(String::from("Third"), String::new());
+        list
+    }
+
+    fn item_order(items: &[TodoListItem]) -> Vec<usize> {
+        items.iter().map(|x| x.id()).collect()
+    }
+
+    fn item_order_refs(items: &[&TodoListItem]) -> Vec<usize> {
+        items.iter().map(|x| x.id()).collect()
+    }
+
+    #[test]
+    fn moves_items_to_an_index() {
+        let mut list = make_three_item_list();
+
+        assert!(list.move_item_to_index(2, 0));
+        assert_eq!(item_order(&list.items), vec![2, 0, 1]);
+        assert!(list.move_item_to_index(2, 99));
+        assert_eq!(item_order(&list.items), vec![0, 1, 2]);
+        assert!(!list.move_item_to_index(99, 0));
+    }
+
+    #[test]
+    fn moves_items_before_and_after_others() {
+        let mut list = make_three_item_list();
+
+        assert!(list.move_item_before(2, 1));
+        assert_eq!(item_order(&list.items), vec![0, 2, 1]);
+        assert!(list.move_item_after(0, 1));
+        assert_eq!(item_order(&list.items), vec![2, 1, 0]);
+        assert!(!list.move_item_after(0, 0));
+    }
+
+    #[test]
+    fn moving_next_to_a_subtask_changes_parent() {
+        let mut list = make_three_item_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+
+        assert!(list.move_item_before(2, child));
+        assert_eq!(list.parent_id(2), Some(0));
+        assert_eq!(item_order(&list.items[0].subtasks), vec![2, child]);
+        assert!(!list.move_item_after(0, child));
+    }
+
+    #[test]
+    fn blocked_items_are_not_actionable() {
+        let mut list = make_three_item_list();
+        list.add_dependency(2, 1).unwrap();
+
+        assert!(list.is_blocked(2));
+        assert_eq!(item_order_refs(&list.actionable_items()), vec![0, 1]);
+
+        list.set_completed(1, true);
+        assert!(!list.is_blocked(2));
+        assert_eq!(item_order_refs(&list.actionable_items()), vec![0, 2]);
+    }
+
+    #[test]
+    fn rejects_dependency_cycles() {
+        let mut list = make_three_item_list();
+        list.add_dependency(2, 1).unwrap();
+        list.add_dependency(1, 0).unwrap();
+
+        assert_eq!(list.add_dependency(0, 2), Err(DependencyError::Cycle));
+        assert_eq!(list.add_dependency(0, 0), Err(DependencyError::BlocksItself));
+        assert_eq!(list.add_dependency(0, 99), Err(DependencyError::MissingItem(99)));
+        assert!(list.depends_on(2, 0));
+        assert!(!list.depends_on(0, 2));
+    }
+
+    #[test]
+    fn removing_a_blocker_cleans_up_references() {
+        let mut list = make_three_item_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        list.add_dependency(2, child).unwrap();
+        list.add_dependency(2, 1).unwrap();
+
+        list.remove_item(0);
+        let blockers: Vec<usize> = list.get_item_by_id(2).unwrap().blocked_by().iter().copied().collect();
+        assert_eq!(blockers, vec![1]);
+    }
+
+    #[test]
+    fn removed_items_go_to_the_trash_and_can_be_restored() {
+        let mut list = make_three_item_list();
+        let child = list.add_subtask(1, String::from("Child"), String::new()).unwrap();
+        list.add_dependency(2, child).unwrap();
+
+        list.remove_item(1);
+        assert_eq!(item_order(&list.items), vec![0, 2]);
+        assert_eq!(list.trash().len(), 1);
+        assert!(!list.is_blocked(2));
+
+        assert!(list.restore_from_trash(1));
+        assert_eq!(item_order(&list.items), vec![0, 1, 2]);
+        assert_eq!(list.parent_id(child), Some(1));
+        assert!(list.is_blocked(2));
+        assert!(list.trash().is_empty());
+        assert!(!list.restore_from_trash(1));
+    }
+
+    #[test]
+    fn restores_to_top_level_if_the_parent_is_gone() {
+        let mut list = make_one_item_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+
+        list.remove_item(child);
+        assert!(list.detach_item(0).is_some());
+        assert!(list.items.is_empty());
+
+        assert!(list.restore_from_trash(child));
+        assert_eq!(item_order(&list.items), vec![child]);
+        assert_eq!(list.parent_id(child), None);
+    }
+
+    #[test]
+    fn trashed_ids_are_never_handed_out_again() {
+        let mut list = make_one_item_list();
+        list.remove_item(0);
+        //as if loaded from a file saved before next_id existed
+        list.next_id = 0;
+        list.repair_ids();
+        let id = list.add(String::from("Second"), String::new());
+        assert_ne!(id, 0);
+        assert!(list.restore_from_trash(0));
+    }
+
+    #[test]
+    fn failed_restores_leave_the_item_in_the_trash() {
+        let mut list = make_three_item_list();
+        list.remove_item(1);
+        //a hand-edited file could have a trashed item with the same id as a live one
+        list.trash[0].detached.item.id = 2;
+        assert!(!list.restore_from_trash(2));
+        assert_eq!(list.trash().len(), 1);
+
+        assert_eq!(list.repair_ids(), 1);
+        let id = list.trash()[0].item().id();
+        assert!(list.restore_from_trash(id));
+        assert_eq!(list.all_items().len(), 3);
+    }
+
+    #[test]
+    fn purges_old_trash() {
+        let now = fixed_now();
+        let mut list = make_three_item_list();
+        list.trash_item(0, now - Duration::days(40));
+        list.trash_item(1, now - Duration::days(2));
+
+        assert_eq!(list.purge_trash(now, Duration::days(30)), 1);
+        assert_eq!(list.trash()[0].item().id(), 1);
+    }
+
+    #[test]
+    fn trash_survives_save_and_load() {
+        let mut list = make_three_item_list();
+        list.remove_item(1);
+
+        let temp = tempfile::NamedTempFile::new().expect("trash_survives_save_and_load() test panicked; temporary file creation failed.");
+        let temp_path = temp.path().to_path_buf();
+        list.save(&temp_path).expect("trash_survives_save_and_load() test panicked; could not save to the temporary file.");
+
+        let mut loaded = TodoList::from_file(&temp_path).expect("trash_survives_save_and_load() test panicked; could not load from the temporary file.");
+        assert!(loaded.restore_from_trash(1));
+        assert_eq!(item_order(&loaded.items), vec![0, 1, 2]);
+
+        temp.close().expect("trash_survives_save_and_load() test panicked; temporary file was not deleted afterwards.");
+    }
+
+    #[test]
+    fn archives_completed_items_and_unarchives_them() {
+        let now = fixed_now();
+        let mut list = make_three_item_list();
+        let child = list.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        list.set_completed_at(child, true, now);
+        list.set_completed_at(2, true, now);
+
+        assert!(!list.archive_item(1, now));
+        assert_eq!(list.archive_completed(now), 2);
+        assert_eq!(item_order(&list.items), vec![0, 1]);
+        assert!(list.get_item_by_id(child).is_none());
+
+        assert!(list.unarchive(child));
+        assert_eq!(list.parent_id(child), Some(0));
+        assert!(list.get_item_by_id(child).unwrap().completed);
+        assert_eq!(list.archive().len(), 1);
+    }
+
+    #[test]
+    fn auto_archives_items_completed_long_enough_ago() {
+        let now = fixed_now();
+        let mut list = make_three_item_list();
+        list.set_completed_at(0, true, now - Duration::days(10));
+        list.set_completed_at(1, true, now - Duration::days(1));
+
+        assert_eq!(list.auto_archive(now), 0);
+        list.archive_after_days = Some(7);
+        assert_eq!(list.auto_archive(now), 1);
+        assert_eq!(item_order(&list.items), vec![1, 2]);
+    }
+
+    #[test]
+    fn failed_unarchives_leave_the_item_in_the_archive() {
+        let mut list = make_three_item_list();
+        list.set_completed(1, true);
+        assert!(list.archive_item(1, fixed_now()));
+        list.archive[0].detached.item.id = 2;
+        assert!(!list.unarchive(2));
+        assert_eq!(list.archive().len(), 1);
+    }
+
+    #[test]
+    fn searches_the_archive() {
+        let now = fixed_now();
+        let mut list = make_three_item_list();
+        list.change_item_desc_by_id(String::from("Pay the Invoice"), 0);
+        list.change_item_tags_by_id(parse_tags("billing"), 1);
+        for id in 0..3 {
+            list.set_completed_at(id, true, now);
+        }
+        list.archive_completed(now);
+
+        let found: Vec<usize> = list.search_archive("invoice").iter().map(|x| x.item().id()).collect();
+        assert_eq!(found, vec![0]);
+        assert_eq!(list.search_archive("BILL").len(), 1);
+        assert_eq!(list.search_archive("").len(), 3);
+    }
+
+    #[test]
+    fn archive_survives_save_and_load() {
+        let now = fixed_now();
+        let mut list = make_three_item_list();
+        list.set_completed_at(1, true, now);
+        list.archive_completed(now);
+        list.archive_after_days = Some(3);
+
+        let temp = tempfile::NamedTempFile::new().expect("archive_survives_save_and_load() test panicked; temporary file creation failed.");
+        let temp_path = temp.path().to_path_buf();
+        list.save(&temp_path).expect("archive_survives_save_and_load() test panicked; could not save to the temporary file.");
+
+        let loaded = TodoList::from_file(&temp_path).expect("archive_survives_save_and_load() test panicked; could not load from the temporary file.");
+        assert_eq!(item_order(&loaded.items), vec![0, 2]);
+        assert_eq!(loaded.archive()[0].item().id(), 1);
+        assert_eq!(loaded.archive_after_days, Some(3));
+
+        temp.close().expect("archive_survives_save_and_load() test panicked; temporary file was not deleted afterwards.");
+    }
+
+    #[test]
+    fn validates_custom_field_values() {
+        let mut list = make_one_item_list();
+        list.add_field(FieldDef::new("Cost", FieldKind::Number)).unwrap();
+        list.add_field(FieldDef::new("Size", FieldKind::Choice(vec![String::from("S"), String::from("L")]))).unwrap();
+
+        assert_eq!(list.add_field(FieldDef::new("Cost", FieldKind::Text)), Err(FieldError::DuplicateField(String::from("Cost"))));
+        assert_eq!(list.add_field(FieldDef::new("Empty", FieldKind::Choice(Vec::new()))), Err(FieldError::NoChoices));
+
+        list.set_field_value(0, "Cost", Some(FieldValue::Number(9.5))).unwrap();
+        assert!(list.set_field_value(0, "Cost", Some(FieldValue::Text(String::from("lots")))).is_err());
+        assert!(list.set_field_value(0, "Size", Some(FieldValue::Choice(String::from("M")))).is_err());
+        assert_eq!(list.set_field_value(0, "Colour", None), Err(FieldError::UnknownField(String::from("Colour"))));
+        assert_eq!(list.set_field_value(100, "Cost", Some(FieldValue::Number(1.0))), Err(FieldError::MissingItem(100)));
+        assert_eq!(list.items[0].field_value("Cost"), Some(&FieldValue::Number(9.5)));
+    }
+
+    #[test]
+    fn migrates_values_when_fields_are_renamed_or_removed() {
+        let mut list = make_three_item_list();
+        list.add_field(FieldDef::new("Ticket", FieldKind::Text)).unwrap();
+        list.add_field(FieldDef::new("Billed", FieldKind::Bool)).unwrap();
+        for id in 0..3 {
+            list.set_field_value(id, "Ticket", Some(FieldValue::Text(format!("T-{id}")))).unwrap();
+            list.set_field_value(id, "Billed", Some(FieldValue::Bool(true))).unwrap();
+        }
+        list.remove_item(2);
+
+        list.rename_field("Ticket", "Issue").unwrap();
+        assert!(list.remove_field("Billed"));
+        assert!(!list.remove_field("Billed"));
+        assert_eq!(list.rename_field("Issue", ""), Err(FieldError::EmptyName));
+
+        assert_eq!(list.items[1].field_value("Issue"), Some(&FieldValue::Text(String::from("T-1"))));
+        assert!(list.items[1].field_value("Ticket").is_none());
+        assert!(list.items[1].field_value("Billed").is_none());
+        //trashed items are migrated too, so restoring them brings back valid values
+        list.restore_from_trash(2);
+        assert_eq!(list.get_item_by_id(2).unwrap().field_values().len(), 1);
+    }
+
+    #[test]
+    fn custom_fields_survive_save_and_load() {
+        let mut list = make_one_item_list();
+        list.add_field(FieldDef::new("Link", FieldKind::Url)).unwrap();
+        list.set_field_value(0, "Link", Some(FieldValue::Url(String::from("https://example.com")))).unwrap();
+
+        let temp = tempfile::NamedTempFile::new().expect("custom_fields_survive_save_and_load() test panicked; temporary file creation failed.");
+        let temp_path = temp.path().to_path_buf();
+        list.save(&temp_path).expect("custom_fields_survive_save_and_load() test panicked; could not save to the temporary file.");
+
+        let loaded = TodoList::from_file(&temp_path).expect("custom_fields_survive_save_and_load() test panicked; could not load from the temporary file.");
+        assert_eq!(loaded.fields(), list.fields());
+        assert_eq!(loaded.items[0].field_value("Link"), Some(&FieldValue::Url(String::from("https://example.com"))));
+
+        temp.close().expect("custom_fields_survive_save_and_load() test panicked; temporary file was not deleted afterwards.");
+    }
+
+    #[test]
+    fn statuses_keep_completion_in_step_and_log_every_move() {
+        let mut list = make_three_item_list();
+        let now = fixed_now();
+        list.set_status(0, "In progress", now).unwrap();
+        assert!(!list.items[0].completed);
+        list.set_status(0, "Won't do", now + Duration::hours(1)).unwrap();
+        assert!(list.items[0].completed);
+        assert_eq!(list.items[0].completed_at(), Some(now + Duration::hours(1)));
+        list.set_completed_at(0, false, now + Duration::hours(2));
+        assert_eq!(list.status_of(&list.items[0]).unwrap().name, "To do");
+        let moves: Vec<(&str, &str)> = list.items[0].status_log().iter().map(|x| (x.from.as_str(), x.to.as_str())).collect();
+        assert_eq!(moves, vec![("To do", "In progress"), ("In progress", "Won't do"), ("Won't do", "To do")]);
+
+        list.set_completed_at(1, true, now);
+        assert_eq!(list.status_of(&list.items[1]).unwrap().name, "Done");
+        assert_eq!(list.set_status(1, "Nope", now), Err(StatusError::UnknownStatus(String::from("Nope"))));
+        assert_eq!(list.set_status(99, "Done", now), Err(StatusError::MissingItem(99)));
+    }
 
-                    });
-                }
-            }
-        }
+    #[test]
+    fn changing_statuses_leaves_items_where_they_were() {
+        let mut list = make_three_item_list();
+        let now = fixed_now();
+        list.set_status(0, "Waiting", now).unwrap();
+        list.rename_status("Waiting", "Blocked").unwrap();
+        assert_eq!(list.status_of(&list.items[0]).unwrap().name, "Blocked");
+        assert_eq!(list.items[0].status_log()[0].to, "Blocked");
 
-        //handling for popup that edits list items
-        let mut edit_list_item_popup = egui::Window::new("Edit Item")
-            .title_bar(true)
-            .default_open(true);
-        if let Some(store) = frame.storage_mut() {
-            if let Some(edit_item_popup_status) = store.get_string("edit_item_popup_status") {
-                if edit_item_popup_status == String::from("open") {
-                    let mut loaded_list = self.loaded_list.as_mut().expect("loaded_list should not return None, as Edit Item button should not exist if no list is loaded");
-                    let id = store.get_string("edit_item_popup_target_id").expect("edit_item_popup_target_id was not found, should be set by Edit Item button in CentralPanel")
-                        .parse::<usize>().expect("failed to parse edit_item_popup_target_id from storage; should be set by Edit Item button in CentralPanel");
-                    let mut item = loaded_list.get_item_by_id(id).expect("edit_item_popup_target_id should match an actual id in loaded_list.items");
-                    let item_name = item.name.clone();
-                    edit_list_item_popup = edit_list_item_popup.id(Id::new("Editing \"{item_name}\"")); //id() apparently takes ownership if I don't assign like this
-
-                    edit_list_item_popup.show(&ctx, |ui| {
-                        ui.label("Enter a new item name here.");
-                        egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("item_name"))
-                            .expect("item_name should be properly initialized before Edit Item is clicked"))
-                        .hint_text("New Name")
-                        .show(ui);
+        //item 1 was in "To do" by default, and stays there when another status becomes the default
+        assert!(list.move_status("In progress", 0));
+        assert_eq!(list.status_of(&list.items[1]).unwrap().name, "To do");
+        list.set_completed_at(2, true, now);
+        list.set_completed_at(2, false, now);
+        assert_eq!(list.status_of(&list.items[2]).unwrap().name, "In progress");
 
-                        ui.label("Enter a new item description here.");
-                        egui::TextEdit::singleline(self.text_inputs.get_mut(&String::from("item_description"))
-                            .expect("item_description should be properly initialized before Edit Item is clicked"))
-                        .hint_text("New Description")
-                        .show(ui);
+        list.remove_status("Blocked", now).unwrap();
+        assert_eq!(list.status_of(&list.items[0]).unwrap().name, "In progress");
+        assert_eq!(list.items[0].status_log().len(), 2);
+        list.remove_status("Won't do", now).unwrap();
+        assert_eq!(list.remove_status("Done", now), Err(StatusError::LastOfCategory(String::from("Done"))));
+        assert_eq!(list.add_status(StatusDef::new("To do", true)), Err(StatusError::DuplicateStatus(String::from("To do"))));
+    }
 
-                        ui.horizontal(|ui| {
-                            if ui.button("Set Changes").clicked() {
-                                loaded_list.change_item_name_by_id(self.text_inputs.get("item_name").unwrap().clone(), id);
-                                loaded_list.change_item_desc_by_id(self.text_inputs.get("item_description").unwrap().clone(), id);
-                                store.set_string("edit_item_popup_status", String::from("closed"));
-                            }
-                            if ui.button("Cancel").clicked() {
-                                store.set_string("edit_item_popup_status", String::from("closed"));
-                            }
-                        });
-                    });
-                }
-            }
+    #[test]
+    fn files_from_before_statuses_get_them_from_completed() {
+        let mut list = make_three_item_list();
+        list.set_completed(1, true);
+        let mut json = serde_json::to_value(&list).unwrap();
+        json.as_object_mut().unwrap().remove("statuses");
+        for item in json["items"].as_array_mut().unwrap() {
+            item.as_object_mut().unwrap().remove("status");
+            item.as_object_mut().unwrap().remove("status_log");
         }
-        
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if debug_mode {
-                egui::ScrollArea::vertical().show(ui, |ui| {    
-                    ui.heading("central panel");
-                    ui.label("Context memory dump:");
-                    //ctx.memory_ui(ui);
-                    ui.label("Context inspection ui:");
-                    //ctx.inspection_ui(ui);
+        let mut temp = tempfile::NamedTempFile::new().expect("files_from_before_statuses_get_them_from_completed() test panicked; temporary file creation failed.");
+        temp.write_all(json.to_string().as_bytes()).expect("files_from_before_statuses_get_them_from_completed() test panicked; could not write to the temporary file.");
+        let mut loaded = TodoList::from_file(&temp.path().to_path_buf()).expect("files_from_before_statuses_get_them_from_completed() test panicked; could not load from the temporary file.");
+        assert_eq!(loaded.statuses(), statuses::default_statuses().as_slice());
+        let names: Vec<Option<&str>> = loaded.items.iter().map(|x| x.status.as_deref()).collect();
+        assert_eq!(names, vec![Some("To do"), Some("Done"), Some("To do")]);
 
-                    let mut stored = false;
-                    ui.label("eframe storage status:");
-                    match frame.storage() {
-                        Some(store) => {
-                            ui.label("eframe storage available. \"test\" returns:");
-                            match store.get_string("test") {
-                                Some(value) => { 
-                                    stored = true;
-                                    ui.label(value)
-                                }
-                                None => ui.label("None."),
-                            }
-                        }
-                        None => ui.label("Storage returned None."),
-                        };
-                    if !stored {
-                        if ui.button("Store test data").clicked() {
-                            frame.storage_mut().expect("Store test data button couldn't run storage_mut")
-                                .set_string("test", String::from("Successfully-retrieved test string! Hello, eframe storage..."));
-                        }
-                    }
-                })
-            } else {
-                egui::ScrollArea::vertical().show(ui, |ui| {
-                    
-                    let mut placeholder_list = TodoList::new(String::from("New Todo List"));
-                    ui.label("Under construction! Run with \"debug\" as an arg for egui information.");
+        //items that were already done don't get a made-up move into Done when something else changes
+        loaded.set_completed_at(0, true, fixed_now());
+        assert_eq!(loaded.items[0].status_log().len(), 1);
+        assert!(loaded.items[1].status_log().is_empty());
 
-                    let mut loaded_list = match self.loaded_list.as_mut() {
-                        Some(mut list) => list,
-                        None => &mut placeholder_list,
-                    };
+        temp.close().expect("files_from_before_statuses_get_them_from_completed() test panicked; temporary file was not deleted afterwards.");
+    }
 
-                    ui.horizontal(|ui| {
-                        ui.heading(&loaded_list.title);
-                        if ui.button("Change title").clicked() {
-                            let _ = self.text_inputs.insert(String::from("list_title"), loaded_list.title.clone());
-                            frame.storage_mut().expect("storage_mut returned None").set_string("rename_list_popup_status", String::from("open"));
-                        }
-                    });
+    #[test]
+    fn kanban_files_from_before_columns_were_statuses_keep_their_columns() {
+        let mut list = make_three_item_list();
+        list.set_completed(2, true);
+        let mut json = serde_json::to_value(&list).unwrap();
+        json["kind"] = serde_json::json!({ "Kanban": { "columns": ["Backlog", "Review", "Shipped"] } });
+        json.as_object_mut().unwrap().remove("statuses");
+        for (item, column) in json["items"].as_array_mut().unwrap().iter_mut().zip(["Review", "Shipped", "Shipped"]) {
+            item.as_object_mut().unwrap().remove("status");
+            item["column"] = serde_json::json!(column);
+        }
 
-                    if ui.button("Add item").clicked() {
-                            frame.storage_mut().expect("storage_mut returned None").set_string("add_item_popup_status", String::from("open"));
-                    }
+        let mut temp = tempfile::NamedTempFile::new().expect("kanban_files_from_before_columns_were_statuses_keep_their_columns() test panicked; temporary file creation failed.");
+        temp.write_all(json.to_string().as_bytes()).expect("kanban_files_from_before_columns_were_statuses_keep_their_columns() test panicked; could not write to the temporary file.");
+        let loaded = TodoList::from_file(&temp.path().to_path_buf()).expect("kanban_files_from_before_columns_were_statuses_keep_their_columns() test panicked; could not load from the temporary file.");
+        let names: Vec<Option<&str>> = loaded.items.iter().map(|x| x.status.as_deref()).collect();
+        //item 1 wasn't completed, so the done column it claimed to be in doesn't count
+        assert_eq!(names, vec![Some("Review"), Some("To do"), Some("Shipped")]);
+        assert_eq!(loaded.column_of(&loaded.items[1]), Some("Backlog"));
+        assert!(loaded.items.iter().all(|x| x.column.is_none()));
+        assert!(!serde_json::to_string(&loaded).unwrap().contains("\"column\""));
 
-                    //workaround for ownership issue with item deletion
-                    let mut id_to_delete = usize::MAX;
+        temp.close().expect("kanban_files_from_before_columns_were_statuses_keep_their_columns() test panicked; temporary file was not deleted afterwards.");
+    }
 
-                    for list_item in &mut loaded_list.items {
-                        ui.horizontal(|ui| {
-                            ui.checkbox(&mut list_item.completed, "Complete");
-                            ui.label(&list_item.name);
-                        });
-                        ui.label(&list_item.description);
-                        ui.horizontal(|ui| {
-                            if ui.button("Edit").clicked() {
-                                self.text_inputs.insert(String::from("item_name"), String::from(&list_item.name.clone()));
-                                self.text_inputs.insert(String::from("item_description"), String::from(&list_item.description.clone()));
-                                frame.storage_mut().expect("storage_mut returned None").set_string("edit_item_popup_status", String::from("open"));
-                                frame.storage_mut().expect("storage_mut returned None").set_string("edit_item_popup_target_id", list_item.id().to_string());
-                            }
-                            if ui.button("Delete").clicked() {
-                                //TODO implement popup for item deletion; "Are you sure?"
-                                id_to_delete = list_item.id;
-                            }
-                        });
-                    }
+    //a clock for the rollover tests, `days` days after fixed_now at the given time
+    fn day_at(days: i64, hour: u32) -> DateTime<Local> {
+        let date = fixed_now().date_naive() + Duration::days(days);
+        Local.from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap()).unwrap()
+    }
 
-                    if id_to_delete < usize::MAX {
-                        loaded_list.remove_item(id_to_delete);
-                        id_to_delete = usize::MAX;
-                    }
-                })
-            }
-        });
+    fn make_daily_list() -> TodoList {
+        let mut list = make_three_item_list();
+        list.set_kind(ListKind::Daily { reset_at: NaiveTime::from_hms_opt(4, 0, 0).unwrap() }, day_at(0, 12));
+        list
     }
-}
 
+    #[test]
+    fn daily_lists_untick_done_items_and_carry_the_rest() {
+        let mut list = make_daily_list();
+        list.set_completed_at(0, true, day_at(0, 12));
+        let subtask = list.add_subtask(1, String::from("Subtask"), String::new()).unwrap();
+        list.set_completed_at(subtask, true, day_at(0, 12));
+        assert!(!list.roll_over(day_at(0, 23)));
+        assert!(!list.roll_over(day_at(1, 3)));
 
+        assert!(list.roll_over(day_at(1, 5)));
+        assert!(list.all_items().iter().all(|x| !x.completed));
+        let carried: Vec<u32> = list.all_items().iter().map(|x| x.carried_days()).collect();
+        assert_eq!(carried, vec![0, 1, 0, 1]);
+        assert!(!list.roll_over(day_at(1, 9)));
 
-#[derive(Serialize, Deserialize, Default)]
-pub struct TodoList {
-    pub title: String,
-    pub items: Vec<TodoListItem>,
-    date_created: DateTime<Local>,
-}
+        //done today, so it stops being carried at the next reset
+        list.set_completed_at(1, true, day_at(1, 9));
+        assert!(list.roll_over(day_at(2, 4)));
+        assert_eq!(list.get_item_by_id(1).unwrap().carried_days(), 0);
+        assert_eq!(list.get_item_by_id(2).unwrap().carried_days(), 2);
+    }
 
-#[derive(Serialize, Deserialize, Default)]
-pub struct TodoListItem {
-    id: usize,
-    pub name: String,
-    pub description: String,
-    date_created: DateTime<Local>,
-    pub completed: bool,
-}
+    #[test]
+    fn rollover_counts_every_missed_day_and_moves_due_dates() {
+        let mut list = make_daily_list();
+        list.change_item_dates_by_id(Some(day_at(0, 18)), None, 2);
+        list.change_item_dates_by_id(Some(day_at(5, 18)), None, 1);
 
-impl TodoList {
-    pub fn new(title: String) -> TodoList {
-        let date_created = Local::now();
-        let items: Vec<TodoListItem> = Vec::new();
-        TodoList {title, date_created, items}
+        //closed for three days
+        assert!(list.roll_over(day_at(3, 8)));
+        assert_eq!(list.get_item_by_id(2).unwrap().carried_days(), 3);
+        assert_eq!(list.get_item_by_id(2).unwrap().due, Some(day_at(3, 18)));
+        assert_eq!(list.get_item_by_id(1).unwrap().due, Some(day_at(5, 18)));
     }
 
-    pub fn add(&mut self, name: String, description: String) {
+    #[test]
+    fn rollover_moves_due_dates_that_were_already_overdue_onto_the_new_day() {
+        let mut list = make_daily_list();
+        //overdue well before the list was made daily
+        list.change_item_dates_by_id(Some(day_at(0, 18) - Duration::days(5)), None, 2);
+        list.change_item_dates_by_id(Some(day_at(0, 2)), None, 1);
+
+        assert!(list.roll_over(day_at(1, 8)));
+        assert_eq!(list.get_item_by_id(2).unwrap().due, Some(day_at(1, 18)));
+        //2:00 on the new date is before its reset, so it goes to the end of the new day instead
+        assert_eq!(list.get_item_by_id(1).unwrap().due, Some(day_at(2, 2)));
+    }
 
-        let ids: Vec<usize> = self.items.iter().map(|x| x.id).collect();
-        let mut id: usize = 0;
+    #[test]
+    fn open_daily_lists_roll_over_on_start_and_when_the_date_changes() {
+        let mut ui = TodoUi::default();
+        ui.open_list(make_daily_list(), None);
+        ui.open_list(make_three_item_list(), None);
+        ui.open_list(make_daily_list(), None);
 
-        for i in 0..ids.len() {
-            if i >= id {
-                id = i + 1;
-            }
+        //the first frame after starting the next day
+        assert_eq!(ui.roll_over_lists(day_at(1, 8)), 2);
+        assert!(ui.dirty);
+        assert!(ui.all_lists[0].dirty);
+        assert!(!ui.all_lists[1].dirty);
+        assert_eq!(ui.roll_over_lists(day_at(1, 8) + Duration::seconds(1)), 0);
+        //still running when the next reset comes round
+        assert_eq!(ui.roll_over_lists(day_at(2, 4)), 2);
+        assert_eq!(ui.all_lists[0].list.items[0].carried_days(), 2);
+    }
+
+    #[test]
+    fn habit_lists_untick_everything_and_keep_streaks() {
+        let mut list = make_three_item_list();
+        let reset_at = NaiveTime::from_hms_opt(4, 0, 0).unwrap();
+        list.set_kind(ListKind::Habit { reset_at }, day_at(0, 12));
+        list.change_item_dates_by_id(Some(day_at(0, 18)), None, 2);
+        for day in 0..3 {
+            list.roll_over(day_at(day, 8));
+            list.set_completed_at(0, true, day_at(day, 9));
         }
 
-        self.items.push(TodoListItem::new(id, name, description));
+        assert!(list.roll_over(day_at(3, 8)));
+        assert!(list.all_items().iter().all(|x| !x.completed));
+        //nothing carries over, missing a day just ends the streak
+        assert_eq!(list.get_item_by_id(2).unwrap().carried_days(), 0);
+        assert_eq!(list.get_item_by_id(2).unwrap().due, Some(day_at(0, 18)));
+        let streak = |list: &TodoList, id| kinds::streak(list.get_item_by_id(id).unwrap().completion_log(), reset_at, day_at(3, 12));
+        assert_eq!(streak(&list, 0), 3);
+        assert_eq!(streak(&list, 1), 0);
     }
 
-    fn clear_list(&mut self) {
-        self.items = Vec::new();
+    #[test]
+    fn checklists_archive_themselves_when_finished() {
+        let now = fixed_now();
+        let mut list = make_three_item_list();
+        list.set_kind(ListKind::Checklist, now);
+        for id in [0, 1] {
+            list.set_completed_at(id, true, now);
+        }
+        assert!(!list.update_checklist(now));
+        list.set_completed_at(2, true, now);
+        assert!(list.update_checklist(now));
+        assert_eq!(list.archived_at(), Some(now));
+
+        list.set_completed_at(2, false, now);
+        assert!(list.update_checklist(now));
+        assert_eq!(list.archived_at(), None);
     }
 
-    //TODO gracefully handle situations where multiple items may have the same id, for methods that take it
-    //this could happen due to bugs or due to manually editing the saved list files
+    #[test]
+    fn kanban_columns_stand_in_for_completion() {
+        let now = fixed_now();
+        let mut list = make_three_item_list();
+        list.set_completed_at(2, true, now);
+        list.set_kind(ListKind::Kanban { columns: kinds::default_columns() }, now);
+        let column = |list: &TodoList, id| list.column_of(list.get_item_by_id(id).unwrap()).map(String::from);
+        assert_eq!(column(&list, 0).as_deref(), Some("To do"));
+        assert_eq!(column(&list, 2).as_deref(), Some("Done"));
 
-    fn get_item_by_id(&mut self, id: usize) -> Option<&TodoListItem> {
-        for i in 0..self.items.len() {
-            if self.items[i].id == id {
-                return Some(&self.items[i]);
-            }
-        }
-        None
+        //a column is the item's status, so moving a card and picking a status are the same thing
+        list.move_to_board_column(0, "In progress", now).unwrap();
+        assert_eq!(column(&list, 0).as_deref(), Some("In progress"));
+        assert_eq!(list.status_of(list.get_item_by_id(0).unwrap()).unwrap().name, "In progress");
+        list.set_status(1, "In progress", now).unwrap();
+        assert_eq!(column(&list, 1).as_deref(), Some("In progress"));
+        list.move_to_board_column(0, "Done", now).unwrap();
+        assert!(list.get_item_by_id(0).unwrap().completed);
+        list.move_to_board_column(2, "To do", now).unwrap();
+        assert!(!list.get_item_by_id(2).unwrap().completed);
+        assert!(list.move_to_board_column(1, "Nowhere", now).is_err());
+
+        //ticking an item off keeps a status it already has of the right category
+        list.set_status(0, "Won't do", now).unwrap();
+        list.set_completed_at(0, true, now);
+        assert_eq!(list.status_of(list.get_item_by_id(0).unwrap()).unwrap().name, "Won't do");
+        list.set_completed_at(0, false, now);
+        assert_eq!(column(&list, 0).as_deref(), Some("To do"));
+
+        //new columns become statuses, and items whose status isn't a column show in the first column of its category
+        list.set_completed_at(0, true, now);
+        list.set_kind(ListKind::Kanban { columns: vec![String::from("Backlog"), String::from("Shipped")] }, now);
+        assert!(list.statuses().iter().any(|x| x.name == "Shipped" && x.done));
+        assert_eq!(column(&list, 1).as_deref(), Some("Backlog"));
+        assert_eq!(column(&list, 0).as_deref(), Some("Shipped"));
     }
 
-    fn change_item_name_by_id(&mut self, name: String, id: usize) {
-        for i in 0..self.items.len() {
-            if self.items[i].id == id {
-                self.items[i].name = name;
-                break;
-            }
-        }
+    #[test]
+    fn list_kinds_survive_save_and_load() {
+        let temp = tempfile::NamedTempFile::new().expect("list_kinds_survive_save_and_load() test panicked; temporary file creation failed.");
+        let temp_path = temp.path().to_path_buf();
+        let mut list = make_three_item_list();
+        list.set_kind(ListKind::Kanban { columns: kinds::default_columns() }, fixed_now());
+        list.move_to_board_column(1, "In progress", fixed_now()).unwrap();
+        list.save(&temp_path).expect("list_kinds_survive_save_and_load() test panicked; could not save to the temporary file.");
+
+        let loaded = TodoList::from_file(&temp_path).expect("list_kinds_survive_save_and_load() test panicked; could not load from the temporary file.");
+        assert_eq!(loaded.kind(), list.kind());
+        assert_eq!(loaded.column_of(loaded.get_item_by_id(1).unwrap()), Some("In progress"));
+
+        //a hand-edited file can leave a kanban list with nowhere to put its items
+        let json = serde_json::to_string(&loaded).unwrap();
+        assert!(json.contains(r#""columns":["To do","In progress","Done"]"#));
+        let json = json.replace(r#""columns":["To do","In progress","Done"]"#, r#""columns":[]"#);
+        fs::write(&temp_path, json).expect("list_kinds_survive_save_and_load() test panicked; could not write the edited file.");
+        let loaded = TodoList::from_file(&temp_path).expect("list_kinds_survive_save_and_load() test panicked; could not load the edited file.");
+        assert_eq!(loaded.columns(), kinds::default_columns());
+
+        temp.close().expect("list_kinds_survive_save_and_load() test panicked; temporary file was not deleted afterwards.");
     }
 
-    fn change_item_desc_by_id(&mut self, desc: String, id: usize) {
-        for i in 0..self.items.len() {
-            if self.items[i].id == id {
-                self.items[i].description = desc;
-                break;
-            }
-        }
+    #[test]
+    fn board_columns_are_statuses_unless_the_list_is_kanban() {
+        let mut list = make_three_item_list();
+        let now = fixed_now();
+        let card_ids = |list: &TodoList, column: &str| -> Vec<usize> { list.board_cards(column).iter().map(|x| x.id).collect() };
+        assert_eq!(list.view(), ListView::List);
+        assert_eq!(list.board_columns(), vec!["To do", "In progress", "Waiting", "Done", "Won't do"]);
+        list.move_to_board_column(0, "Done", now).unwrap();
+        assert!(list.items[0].completed);
+        assert_eq!(card_ids(&list, "To do"), vec![1, 2]);
+
+        list.board_mut().set_wip_limit("In progress", Some(1));
+        list.move_to_board_column(1, "In progress", now).unwrap();
+        assert_eq!(list.move_to_board_column(2, "In progress", now), Err(BoardError::ColumnFull { column: String::from("In progress"), limit: 1 }));
+        assert_eq!(list.move_to_board_column(2, "Nowhere", now), Err(BoardError::UnknownColumn(String::from("Nowhere"))));
+        list.rename_status("In progress", "Doing").unwrap();
+        assert_eq!(list.board().wip_limit("Doing"), Some(1));
+
+        let columns = vec![String::from("To do"), String::from("Doing"), String::from("Done")];
+        list.set_kind(ListKind::Kanban { columns: columns.clone() }, now);
+        assert_eq!(list.view(), ListView::Board);
+        assert_eq!(list.board_columns(), vec!["To do", "Doing", "Done"]);
+        list.move_to_board_column(2, "Done", now).unwrap();
+        assert_eq!(card_ids(&list, "Done"), vec![0, 2]);
+        assert_eq!(list.board().wip_limit("Doing"), Some(1));
+        //a limit left on a replaced column would come back with any later column of the same name
+        list.set_kind(ListKind::Kanban { columns: vec![String::from("Backlog"), String::from("Shipped")] }, now);
+        list.set_kind(ListKind::Kanban { columns }, now);
+        assert_eq!(list.board().wip_limit("Doing"), None);
+        list.set_view(ListView::List);
+        assert_eq!(list.view(), ListView::List);
     }
 
-    pub fn remove_item(&mut self, id: usize) {
-        for i in 0..self.items.len() {
-            if self.items[i].id == id {
-                self.items.remove(i);
-                break;
+    #[test]
+    fn board_moves_and_the_status_menu_agree() {
+        let now = fixed_now();
+        let kanban = ListKind::Kanban { columns: kinds::default_columns() };
+        for kind in [ListKind::Standard, kanban] {
+            let mut dragged = make_three_item_list();
+            dragged.set_kind(kind.clone(), now);
+            let mut picked = dragged.clone();
+            for (id, column) in [(0, "In progress"), (1, "Done"), (1, "To do"), (2, "Done")] {
+                let command = Command::move_to_board_column(&dragged, id, column, now).unwrap();
+                assert!(matches!(&command, Command::SetStatus { status, .. } if status == column));
+                command.apply(&mut dragged);
+                Command::SetStatus { id, status: String::from(column), at: now }.apply(&mut picked);
+                assert_eq!(serde_json::to_value(&dragged).unwrap(), serde_json::to_value(&picked).unwrap());
+                assert_eq!(dragged.board_column_of(dragged.get_item_by_id(id).unwrap()), Some(column));
             }
         }
     }
 
-    pub fn from_file(path: &PathBuf) -> Result<TodoList, io::Error> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        let contents = serde_json::from_reader(reader)?;
+    #[test]
+    fn board_view_and_settings_survive_save_and_load() {
+        let temp = tempfile::NamedTempFile::new().expect("board_view_and_settings_survive_save_and_load() test panicked; temporary file creation failed.");
+        let temp_path = temp.path().to_path_buf();
+        let mut list = make_three_item_list();
+        list.set_view(ListView::Board);
+        list.board_mut().set_wip_limit("Waiting", Some(2));
+        list.board_mut().set_collapsed("Won't do", true);
+        list.save(&temp_path).expect("board_view_and_settings_survive_save_and_load() test panicked; could not save to the temporary file.");
 
-        Ok(contents)
-    }
+        let loaded = TodoList::from_file(&temp_path).expect("board_view_and_settings_survive_save_and_load() test panicked; could not load from the temporary file.");
+        assert_eq!(loaded.view(), ListView::Board);
+        assert_eq!(loaded.board(), list.board());
 
-    pub fn save(&mut self, path: &PathBuf) -> Result<(), io::Error> {
-        let json = serde_json::to_string(self)?;
-        fs::write(path, json)?;
-        Ok(())
+        temp.close().expect("board_view_and_settings_survive_save_and_load() test panicked; temporary file was not deleted afterwards.");
     }
-}
 
-impl TodoListItem {
-    pub fn new(id: usize, name: String, description: String) -> TodoListItem {
-        let date_created = Local::now();
-        TodoListItem { id, name, description, date_created, completed: false, }
+    fn open_titles(ui: &TodoUi) -> Vec<String> {
+        let mut titles: Vec<String> = ui.all_lists.iter().map(|x| x.list.title.clone()).collect();
+        if let Some(list) = &ui.loaded_list {
+            titles.insert(ui.active_index, list.title.clone());
+        }
+        titles
     }
 
-    pub fn date_created(&self) -> DateTime<Local> {
-        self.date_created
+    #[test]
+    fn switching_lists_keeps_sidebar_order_paths_and_dirty_flags() {
+        let mut ui = TodoUi::default();
+        ui.open_list(TodoList::new(String::from("A")), Some(PathBuf::from("a.json")));
+        ui.open_list(TodoList::new(String::from("B")), None);
+        ui.dirty = true;
+        ui.open_list(TodoList::new(String::from("C")), Some(PathBuf::from("c.json")));
+        assert_eq!(open_titles(&ui), vec!["A", "B", "C"]);
+
+        //all_lists now holds A and B
+        ui.switch_to_list(1);
+        assert_eq!(ui.loaded_list.as_ref().unwrap().title, "B");
+        assert!(ui.dirty);
+        assert_eq!(ui.current_path, None);
+        assert_eq!(open_titles(&ui), vec!["A", "B", "C"]);
+
+        ui.switch_to_list(0);
+        assert_eq!(ui.current_path, Some(PathBuf::from("a.json")));
+        assert!(!ui.dirty);
+        assert!(ui.all_lists[0].dirty);
+        assert_eq!(open_titles(&ui), vec!["A", "B", "C"]);
     }
 
-    pub fn id(&self) -> usize {
-        self.id
+    //app storage that keeps what's saved in memory, like eframe's file storage does until it's flushed
+    #[derive(Default)]
+    struct MemoryStorage(HashMap<String, String>);
+
+    impl eframe::Storage for MemoryStorage {
+        fn get_string(&self, key: &str) -> Option<String> {
+            self.0.get(key).cloned()
+        }
+
+        fn set_string(&mut self, key: &str, value: String) {
+            self.0.insert(key.to_string(), value);
+        }
+
+        fn flush(&mut self) {}
     }
 
-}
+    #[test]
+    fn the_app_remembers_open_lists_and_opens_them_again() {
+        let dir = tempfile::tempdir().expect("the_app_remembers_open_lists_and_opens_them_again() test panicked; temporary directory creation failed.");
+        let mut ui = TodoUi::default();
+        for title in ["A", "B"] {
+            let path = dir.path().join(format!("{title}.json"));
+            let mut list = make_three_item_list();
+            list.title = String::from(title);
+            list.save(&path).expect("the_app_remembers_open_lists_and_opens_them_again() test panicked; could not save to the temporary directory.");
+            ui.open_list(list, Some(path));
+        }
+        ui.open_list(TodoList::new(String::from("Unsaved")), None);
+        ui.switch_to_list(0);
+        //changes that were never saved to the file are kept with the app, like the list with no file
+        ui.loaded_list.as_mut().unwrap().title = String::from("A renamed");
+        ui.dirty = true;
+        ui.open_list(TodoList::new(String::from("Missing")), Some(dir.path().join("missing.json")));
+        ui.switch_to_list(0);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile;
-    use std::io::{Read, Write};
+        let mut storage = MemoryStorage::default();
+        eframe::App::save(&mut ui, &mut storage);
+        let mut reopened: TodoUi = eframe::get_value(&storage, eframe::APP_KEY)
+            .expect("the_app_remembers_open_lists_and_opens_them_again() test panicked; the app wasn't saved.");
+        reopened.reopen_lists();
+        assert_eq!(open_titles(&reopened), vec!["A renamed", "B", "Unsaved"]);
+        assert_eq!(reopened.loaded_list.as_ref().unwrap().title, "A renamed");
+        assert_eq!(reopened.current_path, Some(dir.path().join("A.json")));
+        assert!(reopened.dirty);
+        assert!(!reopened.all_lists[0].dirty);
+        assert!(reopened.all_lists[1].dirty && reopened.all_lists[1].path.is_none());
+        assert!(reopened.error_message.as_ref().is_some_and(|x| x.contains("missing.json")));
+    }
 
-    fn make_empty_list() -> TodoList {
-        TodoList::new(String::from("test list"))
+    #[test]
+    fn the_list_saved_by_older_versions_is_opened_once() {
+        let mut list = make_three_item_list();
+        list.title = String::from("Old");
+        let json = serde_json::json!({ "loaded_list": list, "current_path": "old.json", "text_inputs": {} }).to_string();
+        let mut ui: TodoUi = serde_json::from_str(&json).unwrap();
+        ui.reopen_lists();
+        assert_eq!(ui.loaded_list.as_ref().unwrap().title, "Old");
+        assert_eq!(ui.current_path, Some(PathBuf::from("old.json")));
+        assert!(ui.dirty);
+
+        let json = serde_json::to_string(&ui).unwrap();
+        assert!(!json.contains("loaded_list"));
+        let mut reopened: TodoUi = serde_json::from_str(&json).unwrap();
+        reopened.reopen_lists();
+        assert!(reopened.loaded_list.is_none());
     }
 
-    fn make_one_item_list() -> TodoList {
-        let mut list = TodoList::new(String::from("test list"));
-        list.add(String::from("Test Item"), String::from("Test Description"));
-        list
+    #[test]
+    fn folder_lists_are_read_from_the_folder_index() {
+        let dir = tempfile::tempdir().expect("folder_lists_are_read_from_the_folder_index() test panicked; temporary directory creation failed.");
+        make_three_item_list().save(&dir.path().join("a.json")).expect("folder_lists_are_read_from_the_folder_index() test panicked; could not save to the temporary directory.");
+        fs::write(dir.path().join("broken.json"), "not a list").unwrap();
+        fs::create_dir(dir.path().join("folder.json")).unwrap();
+
+        let mut ui = TodoUi { workspace_dir: Some(dir.path().to_path_buf()), ..TodoUi::default() };
+        ui.load_directory_lists();
+        let lists = ui.directory_lists.as_ref().unwrap();
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].0, dir.path().join("a.json"));
+        assert!(ui.error_message.as_ref().is_some_and(|x| x.contains("broken") && !x.contains("folder")));
+        assert_eq!(ui.list_directory.as_ref().unwrap().paths().len(), 2);
     }
 
-    fn make_item_no_list() -> TodoListItem {
-        TodoListItem::new(999, String::from("Test Item"), String::from("Test Description"))
+    #[test]
+    fn opening_an_open_path_switches_to_it() {
+        let mut ui = TodoUi::default();
+        ui.open_list(TodoList::new(String::from("A")), Some(PathBuf::from("a.json")));
+        ui.open_list(TodoList::new(String::from("B")), None);
+        ui.open_list(TodoList::new(String::from("A again")), Some(PathBuf::from("a.json")));
+        assert_eq!(open_titles(&ui), vec!["A", "B"]);
+        assert_eq!(ui.loaded_list.as_ref().unwrap().title, "A");
     }
 
     #[test]
-    fn create_todo_list() {
-        let list = make_empty_list();
-        assert_eq!(list.title, String::from("test list"));
+    fn opening_a_list_purges_old_trash_and_archives_old_items() {
+        let now = Local::now();
+        let mut list = make_three_item_list();
+        list.archive_after_days = Some(7);
+        list.set_completed_at(0, true, now - Duration::days(10));
+        list.trash_item(1, now - Duration::days(TRASH_RETENTION_DAYS + 1));
+
+        let mut ui = TodoUi::default();
+        ui.open_list(list, Some(PathBuf::from("a.json")));
+        let opened = ui.loaded_list.as_ref().unwrap();
+        assert!(opened.trash().is_empty());
+        assert_eq!(opened.archive().len(), 1);
+        assert_eq!(item_order(&opened.items), vec![2]);
+        //so the purge and the archiving get saved
+        assert!(ui.dirty);
+
+        ui.open_list(make_three_item_list(), Some(PathBuf::from("b.json")));
+        assert!(!ui.dirty);
     }
 
     #[test]
-    fn create_list_item() {
-        let item = make_item_no_list();
-        assert_eq!(item.name, String::from("Test Item"));
-        assert_eq!(item.description, String::from("Test Description"));
-        assert_eq!(item.completed, false);
-        //TODO assert something about item.date_created();
+    fn closing_a_list_shows_its_neighbour() {
+        let mut ui = TodoUi::default();
+        for title in ["A", "B", "C"] {
+            ui.open_list(TodoList::new(String::from(title)), None);
+        }
+        ui.switch_to_list(1);
+        ui.close_loaded_list();
+        assert_eq!(ui.loaded_list.as_ref().unwrap().title, "C");
+        ui.close_loaded_list();
+        ui.close_loaded_list();
+        assert!(ui.loaded_list.is_none());
+        assert!(open_titles(&ui).is_empty());
     }
 
     #[test]
-    fn add_item_to_todo_list() {
-        let list = make_one_item_list();
+    fn item_popups_close_when_undo_takes_their_item_away() {
+        let mut ui = TodoUi::default();
+        ui.open_list(make_one_item_list(), None);
+        let list = ui.loaded_list.as_mut().unwrap();
+        let command = Command::add(list, None, String::from("New"), String::new());
+        ui.history.apply(list, command);
+        let id = list.items[1].id;
+        ui.editing_item = Some(id);
+        ui.subtask_parent = Some(id);
+        ui.close_missing_item_popups();
+        assert_eq!(ui.editing_item, Some(id));
 
-        assert_eq!(list.items[0].name, String::from("Test Item"));
-        assert_eq!(list.items[0].description, String::from("Test Description"));
+        ui.history.undo(ui.loaded_list.as_mut().unwrap());
+        ui.close_missing_item_popups();
+        assert_eq!(ui.editing_item, None);
+        assert_eq!(ui.subtask_parent, None);
     }
 
     #[test]
-    fn clear_list() {
-        let mut list = make_one_item_list();
-        list.clear_list();
-        assert!(list.items.len() == 0);
+    fn item_popups_close_when_another_list_is_loaded() {
+        let mut ui = TodoUi::default();
+        //both lists have an item 0, which the popups mustn't carry over to
+        ui.open_list(make_one_item_list(), None);
+        ui.open_list(make_one_item_list(), None);
+        let open_popups = |ui: &mut TodoUi| {
+            ui.editing_item = Some(0);
+            ui.subtask_parent = Some(0);
+        };
+        open_popups(&mut ui);
+        ui.switch_to_list(0);
+        assert_eq!((ui.editing_item, ui.subtask_parent), (None, None));
+        open_popups(&mut ui);
+        ui.open_list(make_one_item_list(), None);
+        assert_eq!((ui.editing_item, ui.subtask_parent), (None, None));
+        open_popups(&mut ui);
+        ui.close_loaded_list();
+        assert_eq!((ui.editing_item, ui.subtask_parent), (None, None));
     }
 
     #[test]
-    fn remove_item_from_list() {
-        let mut list = make_one_item_list();
-        list.add(String::from("New Item"), String::from("Other Description"));
-        list.remove_item(0);
+    fn view_edits_go_back_to_the_owning_list() {
+        let dir = tempfile::tempdir().expect("view_edits_go_back_to_the_owning_list() test panicked; temporary directory creation failed.");
+        let now = fixed_now();
+        let mut on_disk = TodoList::new(String::from("On disk"));
+        let disk_id = on_disk.add(String::from("Water plants"), String::new());
+        on_disk.change_item_dates_by_id(Some(now), None, disk_id);
+        let disk_path = dir.path().join("on disk.json");
+        on_disk.save(&disk_path).unwrap();
+        let mut open_copy = TodoList::new(String::from("Open"));
+        let open_id = open_copy.add(String::from("Pay rent"), String::new());
+        open_copy.change_item_dates_by_id(Some(now), None, open_id);
+        open_copy.save(&dir.path().join("open.json")).unwrap();
 
-        assert!(list.items.len() == 1);
-        assert_eq!(list.items[0].name, String::from("New Item"));
+        let mut ui = TodoUi { workspace_dir: Some(dir.path().to_path_buf()), ..Default::default() };
+        //opened through a path that's spelled differently from the one the folder lists
+        let open_path = dir.path().join(".").join("open.json");
+        ui.open_list(TodoList::from_file(&open_path).unwrap(), Some(open_path.clone()));
+        ui.open_list(TodoList::new(String::from("Showing")), None);
+        ui.load_directory_lists();
+
+        //the open list's file is also in the folder, but only the open copy is gathered
+        let (lists, sources) = ui.source_lists(true);
+        let entries = views::gather(&lists, SmartView::Today, now);
+        let found: Vec<(ListSource, usize)> = entries.iter().map(|x| (sources[x.list_index], x.item_id)).collect();
+        assert_eq!(found, vec![(ListSource::Open(0), open_id), (ListSource::Directory(0), disk_id)]);
+
+        ui.apply_to_source(ListSource::Open(0), Command::SetCompleted { id: open_id, completed: true, at: now });
+        assert!(ui.all_lists[0].dirty);
+        assert!(ui.all_lists[0].list.get_item_by_id(open_id).unwrap().completed);
+
+        ui.apply_to_source(ListSource::Directory(0), Command::ChangeName { id: disk_id, name: String::from("Water the plants") });
+        let saved = TodoList::from_file(&disk_path).unwrap();
+        assert_eq!(saved.get_item_by_id(disk_id).unwrap().name, "Water the plants");
+        assert!(ui.error_message.is_none());
+
+        //edits made to the file after it was read survive a change from the view
+        let mut edited = TodoList::from_file(&disk_path).unwrap();
+        edited.get_item_mut_by_id(disk_id).unwrap().add_tag("#garden");
+        edited.save(&disk_path).unwrap();
+        ui.apply_to_source(ListSource::Directory(0), Command::Batch(vec![
+            Command::ChangeName { id: disk_id, name: String::from("Water plants") },
+            Command::ChangeDue { id: disk_id, due: None },
+        ]));
+        let saved = TodoList::from_file(&disk_path).unwrap();
+        let item = saved.get_item_by_id(disk_id).unwrap();
+        assert_eq!((item.name.as_str(), item.due), ("Water plants", None));
+        assert!(item.has_tag("#garden"));
+
+        ui.apply_to_source(ListSource::Directory(0), Command::ChangeName { id: disk_id + 1, name: String::from("Gone") });
+        assert!(ui.error_message.take().is_some());
+
+        ui.open_list(TodoList::from_file(&open_path).unwrap(), Some(dir.path().join("open.json")));
+        assert_eq!(open_titles(&ui), vec!["Open", "Showing"]);
     }
 
     #[test]
-    fn only_removes_one_item() {
-        let mut list = make_one_item_list();
-        list.add(String::from("New Item"), String::from("Other Description"));
-        list.add(String::from("New Item"), String::from("Other Description"));
+    fn quick_add_goes_to_the_named_list() {
+        let dir = tempfile::tempdir().expect("quick_add_goes_to_the_named_list() test panicked; temporary directory creation failed.");
+        TodoList::new(String::from("Shopping")).save(&dir.path().join("big shop.json")).unwrap();
+        let mut ui = TodoUi { workspace_dir: Some(dir.path().to_path_buf()), ..Default::default() };
+        assert!(ui.quick_add("Pay rent", fixed_now()).is_err());
 
-        list.remove_item(1);
-        assert!(list.items.len() == 2);
+        ui.open_list(TodoList::new(String::from("Home")), None);
+        ui.open_list(TodoList::new(String::from("Work")), None);
+        ui.quick_add("Send invoice tomorrow !high", fixed_now()).unwrap();
+        ui.quick_add("Pay rent list:home #bills", fixed_now()).unwrap();
+        //added to the file after the folder was read
+        ui.load_directory_lists();
+        let mut shop = TodoList::from_file(&dir.path().join("big shop.json")).unwrap();
+        shop.add(String::from("Eggs"), String::new());
+        shop.save(&dir.path().join("big shop.json")).unwrap();
+        ui.quick_add("Milk list:Big-Shop", fixed_now()).unwrap();
+        assert!(ui.quick_add("Milk list:nowhere", fixed_now()).is_err());
+
+        let work = ui.loaded_list.as_ref().unwrap();
+        assert_eq!(work.items[0].name, "Send invoice");
+        assert_eq!(work.items[0].priority, Priority::High);
+        assert!(ui.history.can_undo());
+        let home = &ui.all_lists[0];
+        assert!(home.dirty);
+        assert!(home.list.items[0].has_tag("#bills"));
+        let shop = TodoList::from_file(&dir.path().join("big shop.json")).unwrap();
+        let names: Vec<&str> = shop.items.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, vec!["Eggs", "Milk"]);
+        assert_ne!(shop.items[0].id, shop.items[1].id);
     }
 
     #[test]
-    fn clears_whole_list() {
-        let mut list = make_one_item_list();
-        list.add(String::from("New Item"), String::from("Other Description"));
-        list.add(String::from("New Item"), String::from("Other Description"));
+    fn same_file_sees_through_different_spellings_of_a_path() {
+        let dir = tempfile::tempdir().expect("same_file_sees_through_different_spellings_of_a_path() test panicked; temporary directory creation failed.");
+        let path = dir.path().join("list.json");
+        fs::write(&path, "{}").expect("same_file_sees_through_different_spellings_of_a_path() test panicked; could not write the list file.");
+        assert!(same_file(&path, &dir.path().join(".").join("list.json")));
+        assert!(!same_file(&path, &dir.path().join("other.json")));
+    }
 
-        list.clear_list();
-        assert!(list.items.len() == 0);
+    #[test]
+    fn folder_entries_find_their_open_lists_however_the_path_is_spelled() {
+        let dir = tempfile::tempdir().expect("folder_entries_find_their_open_lists_however_the_path_is_spelled() test panicked; temporary directory creation failed.");
+        let path = dir.path().join("list.json");
+        fs::write(&path, "{}").expect("folder_entries_find_their_open_lists_however_the_path_is_spelled() test panicked; could not write the list file.");
+        let mut ui = TodoUi::default();
+        ui.open_list(TodoList::new(String::from("A")), Some(dir.path().join(".").join("list.json")));
+        ui.open_list(TodoList::new(String::from("B")), None);
+        assert_eq!(ui.lists_open_at(&path), (false, vec![0]));
+        ui.switch_to_list(0);
+        assert_eq!(ui.lists_open_at(&path), (true, Vec::new()));
     }
 
     #[test]
-    fn saves_json_to_file() {
-        let mut list = make_one_item_list();
+    fn copies_items_to_another_list_with_new_ids() {
+        let now = fixed_now();
+        let mut source = make_three_item_list();
+        let child = source.add_subtask(1, String::from("Child"), String::new()).unwrap();
+        source.set_completed_at(child, true, now);
+        source.change_item_tags_by_id(parse_tags("work"), 1);
+        let mut destination = make_three_item_list();
 
-        let mut temp = tempfile::NamedTempFile::new().expect("saves_json_to_file() test panicked; temporary file creation failed.");
-        let temp_path = temp.path().to_path_buf();
+        let new_ids = source.copy_items_to(&[1], &mut destination);
+        assert_eq!(new_ids.len(), 1);
+        assert_eq!(source.items.len(), 3);
+        let copy = destination.get_item_by_id(new_ids[0]).unwrap();
+        assert!(new_ids[0] > 2);
+        assert_eq!(copy.name, source.items[1].name);
+        assert_eq!(copy.date_created(), source.items[1].date_created());
+        assert!(copy.has_tag("work"));
+        assert!(copy.subtasks[0].completed);
+        assert_eq!(copy.subtasks[0].completed_at(), Some(now));
+        assert_ne!(copy.subtasks[0].id(), child);
+    }
 
-        list.save(&temp_path).expect("saves_json_to_file() test panicked; temporary file created but could not be saved to.");
-        
-        let mut temp_contents = String::new();
+    #[test]
+    fn moves_items_in_bulk_keeping_blockers_between_them() {
+        let mut source = make_three_item_list();
+        let child = source.add_subtask(0, String::from("Child"), String::new()).unwrap();
+        source.add_dependency(2, 0).unwrap();
+        source.add_dependency(1, 2).unwrap();
+        let mut destination = make_empty_list();
+        destination.add(String::from("Already here"), String::new());
 
-        temp.read_to_string(&mut temp_contents).expect("saves_json_to_file() test panicked; temporary file created but could not be read from.");
+        //the child is picked too, but it comes along with its parent anyway
+        let new_ids = source.move_items_to(&[2, child, 0], &mut destination);
+        assert_eq!(new_ids.len(), 2);
+        assert_eq!(item_order(&source.items), vec![1]);
+        assert!(!source.is_blocked(1));
 
-        //eprintln!("{}", temp_contents); // run with `-- --nocapture` argument to `cargo test` 
+        assert_eq!(destination.items.len(), 3);
+        let moved_blocked = destination.get_item_by_id(new_ids[0]).unwrap();
+        assert!(moved_blocked.blocked_by().contains(&new_ids[1]));
+        assert_eq!(destination.get_item_by_id(new_ids[1]).unwrap().subtasks.len(), 1);
+        let ids: HashSet<usize> = destination.all_items().iter().map(|x| x.id()).collect();
+        assert_eq!(ids.len(), 4);
+    }
 
-        assert!(temp_contents.len() > 0); //for now, we assert anything is in the file; TODO more specific assertions?
-        temp.close().expect("saves_json_to_file() test panicked; temporary file was not deleted afterwards.");
+    #[test]
+    fn moves_to_a_file_keep_the_items_if_the_file_cant_be_saved() {
+        let dir = tempfile::tempdir().expect("moves_to_a_file_keep_the_items_if_the_file_cant_be_saved() test panicked; temporary directory creation failed.");
+        let mut ui = TodoUi::default();
+        ui.open_list(make_three_item_list(), None);
+        let unsaveable = dir.path().join("missing folder").join("other.json");
+        assert!(ui.transfer_to_file(&[0, 2], make_empty_list(), &unsaveable).is_err());
+        assert_eq!(item_order(&ui.loaded_list.as_ref().unwrap().items), vec![0, 1, 2]);
+
+        let path = dir.path().join("other.json");
+        ui.transfer_to_file(&[0, 2], make_empty_list(), &path).unwrap();
+        assert_eq!(item_order(&ui.loaded_list.as_ref().unwrap().items), vec![1]);
+        assert_eq!(TodoList::from_file(&path).unwrap().items.len(), 2);
+        ui.history.undo(ui.loaded_list.as_mut().unwrap());
+        assert_eq!(item_order(&ui.loaded_list.as_ref().unwrap().items), vec![0, 1, 2]);
     }
 
     #[test]
-    fn loads_json_from_file() {
-        let mut temp = tempfile::NamedTempFile::new().expect("loads_json_from_file() test panicked; temporary file creation failed.");
+    fn undoing_a_move_to_a_file_takes_the_items_out_of_it() {
+        let dir = tempfile::tempdir().expect("undoing_a_move_to_a_file_takes_the_items_out_of_it() test panicked; temporary directory creation failed.");
+        let path = dir.path().join("other.json");
+        let mut destination = make_empty_list();
+        destination.add(String::from("Already here"), String::new());
+        destination.save(&path).expect("undoing_a_move_to_a_file_takes_the_items_out_of_it() test panicked; could not save to the temporary directory.");
 
-        let contents = String::from("{\"title\":\"test list\",\"items\":\
-        [{\"id\":0,\"name\":\"Test Item\",\"description\":\"Test Description\",\"date_created\":\"2024-05-22T15:40:04.970459400-04:00\",\"completed\":false}],\
-        \"date_created\":\"2024-05-22T15:40:04.969858100-04:00\"}");
+        let mut ui = TodoUi::default();
+        ui.open_list(make_three_item_list(), None);
+        ui.transfer_to_file(&[0, 2], TodoList::from_file(&path).unwrap(), &path).unwrap();
+        assert_eq!(TodoList::from_file(&path).unwrap().items.len(), 3);
 
-        temp.write(contents.as_bytes()).expect("loads_json_from_file() test panicked; temporary file created, but could not be written to.");
-        let temp_path = temp.path().to_path_buf();
+        ui.history.undo(ui.loaded_list.as_mut().unwrap());
+        assert_eq!(item_order(&ui.loaded_list.as_ref().unwrap().items), vec![0, 1, 2]);
+        let names: Vec<String> = TodoList::from_file(&path).unwrap().items.iter().map(|x| x.name.clone()).collect();
+        assert_eq!(names, vec!["Already here"]);
 
-        let list = TodoList::from_file(&temp_path).expect("loads_json_from_file() test panicked; a temp file was created, but TodoList::from_file could not construct a TodoList.");
+        //redoing moves them again
+        ui.history.redo(ui.loaded_list.as_mut().unwrap());
+        assert_eq!(item_order(&ui.loaded_list.as_ref().unwrap().items), vec![1]);
+        assert_eq!(TodoList::from_file(&path).unwrap().items.len(), 3);
+    }
+
+    #[test]
+    fn moves_between_open_lists_can_be_undone_in_both() {
+        let mut ui = TodoUi::default();
+        ui.open_list(make_empty_list(), None);
+        ui.open_list(make_three_item_list(), None);
+        ui.transfer_to_open_list(&[0, 2], 0);
+        assert_eq!(item_order(&ui.loaded_list.as_ref().unwrap().items), vec![1]);
+        let open = &mut ui.all_lists[0];
+        assert_eq!(open.list.items.len(), 2);
+        assert!(open.dirty);
 
-        assert!(list.items.len() > 0);
+        open.history.undo(&mut open.list);
+        assert!(open.list.items.is_empty());
+        ui.history.undo(ui.loaded_list.as_mut().unwrap());
+        assert_eq!(item_order(&ui.loaded_list.as_ref().unwrap().items), vec![0, 1, 2]);
+    }
 
-        //eprintln!("{}", temp_path.display()); // run with `-- --nocapture` argument to `cargo test` 
-        temp.close().expect("saves_json_to_file() test panicked; temporary file was not deleted afterwards.");
+    #[test]
+    fn imported_items_drop_values_for_unknown_fields() {
+        let mut source = make_one_item_list();
+        source.add_field(FieldDef::new("Cost", FieldKind::Number)).unwrap();
+        source.add_field(FieldDef::new("Ticket", FieldKind::Text)).unwrap();
+        source.set_field_value(0, "Cost", Some(FieldValue::Number(5.0))).unwrap();
+        source.set_field_value(0, "Ticket", Some(FieldValue::Text(String::from("T-1")))).unwrap();
+        let mut destination = make_empty_list();
+        destination.add_field(FieldDef::new("Cost", FieldKind::Text)).unwrap();
+        destination.add_field(FieldDef::new("Ticket", FieldKind::Text)).unwrap();
+
+        let new_ids = source.copy_items_to(&[0], &mut destination);
+        let copy = destination.get_item_by_id(new_ids[0]).unwrap();
+        assert_eq!(copy.field_values().len(), 1);
+        assert!(copy.field_value("Ticket").is_some());
+    }
 
+    #[test]
+    fn templates_are_kept_in_the_workspace_folder_unless_moved() {
+        let mut ui = TodoUi { workspace_dir: Some(PathBuf::from("work")), ..Default::default() };
+        assert_eq!(ui.templates_root(), PathBuf::from("work").join(templates::TEMPLATES_DIR));
+        ui.templates_dir = Some(PathBuf::from("shared templates"));
+        assert_eq!(ui.templates_root(), PathBuf::from("shared templates"));
     }
 }
\ No newline at end of file
@@ -0,0 +1,516 @@
+//a small query language for filtering and sorting a list, e.g.
+//    status:open tag:work due<7d priority>=high "invoice" sort:due
+//terms are separated by spaces and all have to match; a leading - negates a term
+//anything that isn't key:value (or key<value etc.) is searched for in names and descriptions,
+//and quotes keep spaces inside a single term
+//
+//keys:
+//    status:open|done|blocked|actionable|overdue   (open and done match every status of that category)
+//    state:name             one of the list's own statuses, like state:"in progress"
+//    tag:name               (a leading # or @ on either side is ignored)
+//    priority, due, start   with : = < <= > >=
+//    sort:due|start|priority|name|created|status   (a leading - reverses it; priority sorts highest first,
+//                           status in the order of the list's statuses)
+//dates can be YYYY-MM-DD, today, tomorrow, yesterday, or relative to now like 3h, 7d, 2w or -1d
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::*;
+
+use crate::{Priority, TodoList, TodoListItem};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Comparison {
+    Equal, //written : or =
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status {
+    Open,
+    Done,
+    Blocked,
+    Actionable,
+    Overdue,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DateValue {
+    Day(NaiveDate),
+    Relative(Duration), //from now
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DateKey {
+    Due,
+    Start,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortKey {
+    Due,
+    Start,
+    Priority,
+    Name,
+    Created,
+    Status,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Filter {
+    Text(String), //lowercased
+    Status(Status),
+    State(String), //lowercased; one of the list's own statuses, which matches nothing in lists without it
+    Tag(String),
+    Priority(Comparison, Priority),
+    Date(DateKey, Comparison, DateValue),
+    NoDate(DateKey), //due:none
+    Not(Box<Filter>),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Query {
+    pub filters: Vec<Filter>,
+    pub sort: Vec<(SortKey, bool)>, //(key, reversed), most important first
+}
+
+//column is counted in characters from 1, pointing at the start of the term (or quote) that's wrong
+#[derive(Debug, PartialEq, Eq)]
+pub struct QueryError {
+    pub column: usize,
+    pub kind: QueryErrorKind,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum QueryErrorKind {
+    UnterminatedQuote,
+    EmptyTerm,
+    UnknownKey(String),
+    MissingValue(String),
+    InvalidValue { key: String, value: String },
+    UnsupportedComparison { key: String, comparison: String },
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at column {}: ", self.column)?;
+        match &self.kind {
+            QueryErrorKind::UnterminatedQuote => write!(f, "this quote is never closed"),
+            QueryErrorKind::EmptyTerm => write!(f, "there's nothing after the -"),
+            QueryErrorKind::UnknownKey(key) => write!(f, "\"{key}\" isn't something that can be searched on"),
+            QueryErrorKind::MissingValue(key) => write!(f, "{key} needs a value"),
+            QueryErrorKind::InvalidValue { key, value } => write!(f, "\"{value}\" isn't a valid value for {key}"),
+            QueryErrorKind::UnsupportedComparison { key, comparison } => write!(f, "{key} can't be compared with {comparison}"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+//a term as typed, with quotes removed
+struct Token {
+    column: usize,
+    text: String,
+    quoted: bool, //started with a quote, so it's plain text even if it has a colon in it
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut current: Option<Token> = None;
+    let mut open_quote: Option<usize> = None;
+    for (index, c) in input.chars().enumerate() {
+        let column = index + 1;
+        if c == '"' {
+            open_quote = match open_quote {
+                Some(_) => None,
+                None => Some(column),
+            };
+            current.get_or_insert(Token { column, text: String::new(), quoted: true });
+        } else if c.is_whitespace() && open_quote.is_none() {
+            tokens.extend(current.take());
+        } else {
+            current.get_or_insert(Token { column, text: String::new(), quoted: false }).text.push(c);
+        }
+    }
+    if let Some(column) = open_quote {
+        return Err(QueryError { column, kind: QueryErrorKind::UnterminatedQuote });
+    }
+    tokens.extend(current);
+    Ok(tokens)
+}
+
+//splits "due<=7d" into ("due", "<=", "7d"); None if there's no comparison in it
+fn split_term(text: &str) -> Option<(&str, &str, &str)> {
+    let at = text.find([':', '=', '<', '>'])?;
+    let rest = &text[at..];
+    let length = if rest.starts_with("<=") || rest.starts_with(">=") { 2 } else { 1 };
+    Some((&text[..at], &rest[..length], &rest[length..]))
+}
+
+fn parse_comparison(comparison: &str) -> Comparison {
+    match comparison {
+        "<" => Comparison::Less,
+        "<=" => Comparison::LessOrEqual,
+        ">" => Comparison::Greater,
+        ">=" => Comparison::GreaterOrEqual,
+        _ => Comparison::Equal,
+    }
+}
+
+fn parse_date_value(value: &str, today: NaiveDate) -> Option<DateValue> {
+    match value {
+        "today" => return Some(DateValue::Day(today)),
+        "tomorrow" => return today.succ_opt().map(DateValue::Day),
+        "yesterday" => return today.pred_opt().map(DateValue::Day),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(DateValue::Day(date));
+    }
+    let unit = value.chars().last()?;
+    let amount = value[..value.len() - unit.len_utf8()].parse::<i64>().ok()?;
+    let duration = match unit {
+        'h' => Duration::try_hours(amount)?,
+        'd' => Duration::try_days(amount)?,
+        'w' => Duration::try_weeks(amount)?,
+        _ => return None,
+    };
+    //durations that would run off the end of the calendar can't be compared with anything
+    today.and_time(NaiveTime::MIN).checked_add_signed(duration)?;
+    Some(DateValue::Relative(duration))
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim_start_matches(['#', '@']).to_lowercase()
+}
+
+impl Query {
+    //relative dates like 7d are only turned into times when the query is evaluated, so a saved query keeps working,
+    //but today/tomorrow are fixed to the day it's parsed on
+    pub fn parse(input: &str) -> Result<Query, QueryError> {
+        Self::parse_on(input, Local::now().date_naive())
+    }
+
+    pub fn parse_on(input: &str, today: NaiveDate) -> Result<Query, QueryError> {
+        let mut query = Query::default();
+        for token in tokenize(input)? {
+            let column = token.column;
+            let error = |kind| QueryError { column, kind };
+            let (negated, text) = match token.text.strip_prefix('-') {
+                Some(rest) if !token.quoted => (true, rest),
+                _ => (false, token.text.as_str()),
+            };
+            if text.is_empty() {
+                if negated {
+                    return Err(error(QueryErrorKind::EmptyTerm));
+                }
+                continue;
+            }
+
+            let filter = match split_term(text).filter(|_| !token.quoted) {
+                None => Filter::Text(text.to_lowercase()),
+                Some((key, comparison, value)) => {
+                    let key_lower = key.to_lowercase();
+                    let value_lower = value.to_lowercase();
+                    if value.is_empty() {
+                        return Err(error(QueryErrorKind::MissingValue(key_lower)));
+                    }
+                    let invalid = || error(QueryErrorKind::InvalidValue { key: key_lower.clone(), value: String::from(value) });
+                    let only_equal = || -> Result<(), QueryError> {
+                        if comparison == ":" || comparison == "=" {
+                            Ok(())
+                        } else {
+                            Err(error(QueryErrorKind::UnsupportedComparison { key: key_lower.clone(), comparison: String::from(comparison) }))
+                        }
+                    };
+                    match key_lower.as_str() {
+                        "status" | "is" => {
+                            only_equal()?;
+                            Filter::Status(match value_lower.as_str() {
+                                "open" | "todo" => Status::Open,
+                                "done" | "completed" | "closed" => Status::Done,
+                                "blocked" => Status::Blocked,
+                                "actionable" => Status::Actionable,
+                                "overdue" => Status::Overdue,
+                                _ => return Err(invalid()),
+                            })
+                        }
+                        "state" => {
+                            only_equal()?;
+                            Filter::State(value_lower)
+                        }
+                        "tag" => {
+                            only_equal()?;
+                            Filter::Tag(normalize_tag(value))
+                        }
+                        "priority" => {
+                            let priority = Priority::ALL.into_iter()
+                                .find(|x| x.label().to_lowercase() == value_lower || (*x == Priority::None && value_lower == "none"))
+                                .ok_or_else(invalid)?;
+                            Filter::Priority(parse_comparison(comparison), priority)
+                        }
+                        "due" | "start" => {
+                            let date_key = if key_lower == "due" { DateKey::Due } else { DateKey::Start };
+                            if value_lower == "none" {
+                                only_equal()?;
+                                Filter::NoDate(date_key)
+                            } else {
+                                let date = parse_date_value(&value_lower, today).ok_or_else(invalid)?;
+                                Filter::Date(date_key, parse_comparison(comparison), date)
+                            }
+                        }
+                        "sort" => {
+                            only_equal()?;
+                            if negated {
+                                return Err(invalid());
+                            }
+                            let (reversed, name) = match value_lower.strip_prefix('-') {
+                                Some(name) => (true, name),
+                                None => (false, value_lower.as_str()),
+                            };
+                            let sort_key = match name {
+                                "due" => SortKey::Due,
+                                "start" => SortKey::Start,
+                                "priority" => SortKey::Priority,
+                                "name" => SortKey::Name,
+                                "created" => SortKey::Created,
+                                "status" => SortKey::Status,
+                                _ => return Err(invalid()),
+                            };
+                            query.sort.push((sort_key, reversed));
+                            continue;
+                        }
+                        _ => return Err(error(QueryErrorKind::UnknownKey(String::from(key)))),
+                    }
+                }
+            };
+            query.filters.push(if negated { Filter::Not(Box::new(filter)) } else { filter });
+        }
+        Ok(query)
+    }
+
+    //the state: names in the query that aren't statuses of the list, which can't match anything there
+    pub fn unknown_states<'a>(&'a self, list: &TodoList) -> Vec<&'a str> {
+        fn collect<'a>(filter: &'a Filter, list: &TodoList, found: &mut Vec<&'a str>) {
+            match filter {
+                Filter::State(name) if !list.statuses().iter().any(|x| x.name.to_lowercase() == *name) => found.push(name),
+                Filter::Not(filter) => collect(filter, list, found),
+                _ => {}
+            }
+        }
+        let mut found = Vec::new();
+        for filter in &self.filters {
+            collect(filter, list, &mut found);
+        }
+        found
+    }
+
+    pub fn matches(&self, list: &TodoList, item: &TodoListItem, now: DateTime<Local>) -> bool {
+        self.filters.iter().all(|x| x.matches(list, item, now))
+    }
+
+    //every matching item, subtasks included, in list order unless the query sorts them
+    pub fn apply<'a>(&self, list: &'a TodoList, now: DateTime<Local>) -> Vec<&'a TodoListItem> {
+        let mut found: Vec<&TodoListItem> = list.all_items().into_iter().filter(|x| self.matches(list, x, now)).collect();
+        //stable sorts from the least important key up leave the most important one deciding
+        for (key, reversed) in self.sort.iter().rev() {
+            found.sort_by(|a, b| {
+                let order = match key {
+                    //items without the date go last either way
+                    SortKey::Due => compare_dates(a.due, b.due, *reversed),
+                    SortKey::Start => compare_dates(a.start, b.start, *reversed),
+                    SortKey::Priority => b.priority.cmp(&a.priority),
+                    SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                    SortKey::Created => a.date_created.cmp(&b.date_created),
+                    SortKey::Status => status_index(list, a).cmp(&status_index(list, b)),
+                };
+                match key {
+                    SortKey::Due | SortKey::Start => order,
+                    _ if *reversed => order.reverse(),
+                    _ => order,
+                }
+            });
+        }
+        found
+    }
+}
+
+fn status_index(list: &TodoList, item: &TodoListItem) -> Option<usize> {
+    let status = list.status_of(item)?;
+    list.statuses().iter().position(|x| x == status)
+}
+
+fn compare_dates(a: Option<DateTime<Local>>, b: Option<DateTime<Local>>, reversed: bool) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if reversed => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(input: &str) -> Result<Query, QueryError> {
+        Query::parse(input)
+    }
+}
+
+impl Filter {
+    fn matches(&self, list: &TodoList, item: &TodoListItem, now: DateTime<Local>) -> bool {
+        match self {
+            Filter::Text(text) => item.name.to_lowercase().contains(text) || item.description.to_lowercase().contains(text),
+            Filter::Status(status) => match status {
+                Status::Open => !item.completed,
+                Status::Done => item.completed,
+                Status::Blocked => list.is_blocked(item.id),
+                Status::Actionable => !item.completed && !list.is_blocked(item.id),
+                Status::Overdue => item.is_overdue(now),
+            },
+            Filter::State(name) => list.status_of(item).is_some_and(|x| x.name.to_lowercase() == *name),
+            Filter::Tag(tag) => item.tags.iter().any(|x| normalize_tag(x) == *tag),
+            Filter::Priority(comparison, priority) => compare(*comparison, item.priority.cmp(priority)),
+            Filter::Date(key, comparison, value) => {
+                let date = match key {
+                    DateKey::Due => item.due,
+                    DateKey::Start => item.start,
+                };
+                let Some(date) = date else {
+                    return false;
+                };
+                //a day covers from its midnight up to the next one; a relative time is a single instant
+                let (from, to) = match value {
+                    DateValue::Day(day) => {
+                        let midnight = |day: NaiveDate| day.and_time(NaiveTime::MIN).and_local_timezone(Local).earliest();
+                        match (midnight(*day), day.succ_opt().and_then(midnight)) {
+                            (Some(from), Some(to)) => (from, to),
+                            _ => return false,
+                        }
+                    }
+                    DateValue::Relative(duration) => match now.checked_add_signed(*duration) {
+                        Some(time) => (time, time),
+                        None => return false,
+                    },
+                };
+                match comparison {
+                    Comparison::Equal => from <= date && (date < to || date == from),
+                    Comparison::Less => date < from,
+                    Comparison::LessOrEqual => date < to || date == from,
+                    Comparison::Greater => date >= to && date != from,
+                    Comparison::GreaterOrEqual => date >= from,
+                }
+            }
+            Filter::NoDate(key) => match key {
+                DateKey::Due => item.due.is_none(),
+                DateKey::Start => item.start.is_none(),
+            },
+            Filter::Not(filter) => !filter.matches(list, item, now),
+        }
+    }
+}
+
+fn compare(comparison: Comparison, order: std::cmp::Ordering) -> bool {
+    use std::cmp::Ordering;
+    match comparison {
+        Comparison::Equal => order == Ordering::Equal,
+        Comparison::Less => order == Ordering::Less,
+        Comparison::LessOrEqual => order != Ordering::Greater,
+        Comparison::Greater => order == Ordering::Greater,
+        Comparison::GreaterOrEqual => order != Ordering::Less,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_tags;
+
+    fn fixed_now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 22, 12, 0, 0).unwrap()
+    }
+
+    fn make_list() -> TodoList {
+        let now = fixed_now();
+        let mut list = TodoList::new(String::from("test list"));
+        let invoice = list.add(String::from("Send invoice"), String::from("For the May work"));
+        let report = list.add(String::from("Write report"), String::new());
+        let groceries = list.add(String::from("Groceries"), String::new());
+        let old = list.add(String::from("Old invoice"), String::new());
+        list.change_item_tags_by_id(parse_tags("#work"), invoice);
+        list.change_item_tags_by_id(parse_tags("work"), report);
+        list.change_item_tags_by_id(parse_tags("@home"), groceries);
+        list.change_item_priority_by_id(Priority::High, invoice);
+        list.change_item_priority_by_id(Priority::Urgent, report);
+        list.change_item_priority_by_id(Priority::Low, groceries);
+        list.change_item_dates_by_id(Some(now + Duration::days(3)), None, invoice);
+        list.change_item_dates_by_id(Some(now + Duration::days(10)), None, report);
+        list.change_item_dates_by_id(Some(now - Duration::days(1)), None, groceries);
+        list.set_completed_at(old, true, now);
+        list
+    }
+
+    fn names(query: &str) -> Vec<String> {
+        let list = make_list();
+        let query = Query::parse_on(query, fixed_now().date_naive()).unwrap();
+        query.apply(&list, fixed_now()).iter().map(|x| x.name.clone()).collect()
+    }
+
+    #[test]
+    fn combines_terms() {
+        assert_eq!(names("status:open tag:work due<7d priority>=high \"invoice\""), vec!["Send invoice"]);
+        assert_eq!(names("tag:work"), vec!["Send invoice", "Write report"]);
+        assert_eq!(names("invoice"), vec!["Send invoice", "Old invoice"]);
+        assert_eq!(names("-status:done invoice"), vec!["Send invoice"]);
+        assert_eq!(names("status:overdue"), vec!["Groceries"]);
+        assert_eq!(names("due:none"), vec!["Old invoice"]);
+        assert_eq!(names("due:today"), Vec::<String>::new());
+        assert_eq!(names("due<=2024-05-25 due>=yesterday"), vec!["Send invoice", "Groceries"]);
+    }
+
+    #[test]
+    fn sorts_results() {
+        assert_eq!(names("sort:due"), vec!["Groceries", "Send invoice", "Write report", "Old invoice"]);
+        assert_eq!(names("sort:-due"), vec!["Write report", "Send invoice", "Groceries", "Old invoice"]);
+        assert_eq!(names("status:open sort:priority"), vec!["Write report", "Send invoice", "Groceries"]);
+        assert_eq!(names("status:open sort:-name"), vec!["Write report", "Send invoice", "Groceries"]);
+    }
+
+    #[test]
+    fn matches_and_sorts_by_the_lists_statuses() {
+        let mut list = make_list();
+        let report = list.all_items().into_iter().find(|x| x.name == "Write report").unwrap().id;
+        list.set_status(report, "In progress", fixed_now()).unwrap();
+        let names = |query: &str| -> Vec<String> {
+            Query::parse(query).unwrap().apply(&list, fixed_now()).iter().map(|x| x.name.clone()).collect()
+        };
+        assert_eq!(names("state:\"in progress\""), vec!["Write report"]);
+        assert_eq!(names("status:open -state:\"In Progress\""), vec!["Send invoice", "Groceries"]);
+        assert_eq!(names("state:waiting"), Vec::<String>::new());
+        assert_eq!(Query::parse("state:waiting -state:opne").unwrap().unknown_states(&list), vec!["opne"]);
+        assert_eq!(names("sort:-status"), vec!["Old invoice", "Write report", "Send invoice", "Groceries"]);
+    }
+
+    #[test]
+    fn reports_where_a_query_is_wrong() {
+        let error = |query: &str| Query::parse(query).unwrap_err();
+        assert_eq!(error("tag:work \"unclosed"), QueryError { column: 10, kind: QueryErrorKind::UnterminatedQuote });
+        assert_eq!(error("status:open colour:red"), QueryError { column: 13, kind: QueryErrorKind::UnknownKey(String::from("colour")) });
+        assert_eq!(error("due<"), QueryError { column: 1, kind: QueryErrorKind::MissingValue(String::from("due")) });
+        assert_eq!(error("  priority>=extreme").column, 3);
+        assert_eq!(error("due<7y").kind, QueryErrorKind::InvalidValue { key: String::from("due"), value: String::from("7y") });
+        assert_eq!(error("due<100000000w").kind, QueryErrorKind::InvalidValue { key: String::from("due"), value: String::from("100000000w") });
+        assert_eq!(error("tag>work").to_string(), "at column 1: tag can't be compared with >");
+        assert_eq!(error("a -").kind, QueryErrorKind::EmptyTerm);
+        assert_eq!(error("status:opne").kind, QueryErrorKind::InvalidValue { key: String::from("status"), value: String::from("opne") });
+    }
+
+    #[test]
+    fn quoted_terms_are_plain_text() {
+        let query = Query::parse("\"status:open\" \"two words\"").unwrap();
+        assert_eq!(query.filters, vec![Filter::Text(String::from("status:open")), Filter::Text(String::from("two words"))]);
+    }
+}
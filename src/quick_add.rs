@@ -0,0 +1,380 @@
+//turns a single line of text into a new item, e.g.
+//    Pay rent tomorrow 9am #home !high every month
+//these are picked out from anywhere in the line, and everything else (in order) becomes the item's name:
+//    dates       today, tomorrow, friday (today counts), next friday (today doesn't), next week/month/year,
+//                in 3 days/weeks/months/years, in 2 hours/minutes, 2024-05-22, and any of those after "on"
+//    times       9am, 9:30pm, 17:30, noon, and "at 9" (bare hours need the "at")
+//    repeats     every day/week/month/year, every 3 days, every weekday, every monday
+//    tags        #home or @home
+//    priority    !low, !medium, !high or !urgent
+//    list        list:groceries, which names the list to add to (case, spaces and punctuation don't matter)
+//only the first date, time and repeat are taken, so later ones stay in the name; quoting keeps words out of
+//all of this, so "Read Friday" adds an item called Read Friday with no date
+//a date without a time is due at the end of that day, and a time without a date is the next time it comes round
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use chrono::*;
+
+use crate::recurrence::Recurrence;
+use crate::{Priority, TodoListItem};
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct QuickAdd {
+    pub name: String,
+    pub due: Option<DateTime<Local>>,
+    pub recurrence: Option<Recurrence>,
+    pub tags: BTreeSet<String>,
+    pub priority: Priority,
+    pub list: Option<String>, //as typed after list:, see list_name_matches
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum QuickAddError {
+    NoName, //the whole line was dates, tags and so on
+}
+
+impl fmt::Display for QuickAddError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuickAddError::NoName => write!(f, "the item needs a name as well"),
+        }
+    }
+}
+
+impl std::error::Error for QuickAddError {}
+
+//a date, or an exact moment for things like "in 2 hours"
+enum When {
+    Day(NaiveDate),
+    Moment(DateTime<Local>),
+}
+
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+//words split on whitespace, with "quoted text" kept together as one word
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in input.chars() {
+        match c {
+            '"' => {
+                if in_quotes || !current.is_empty() {
+                    tokens.push(Token { text: std::mem::take(&mut current), quoted: in_quotes });
+                }
+                in_quotes = !in_quotes;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(Token { text: std::mem::take(&mut current), quoted: false });
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    //an unclosed quote runs to the end of the line
+    if !current.is_empty() {
+        tokens.push(Token { text: current, quoted: in_quotes });
+    }
+    tokens
+}
+
+//full weekday names only, unless short is true, since "sun" and "wed" are ordinary words too
+fn parse_weekday(word: &str, short: bool) -> Option<Weekday> {
+    const NAMES: [(&str, Weekday); 7] = [
+        ("monday", Weekday::Mon),
+        ("tuesday", Weekday::Tue),
+        ("wednesday", Weekday::Wed),
+        ("thursday", Weekday::Thu),
+        ("friday", Weekday::Fri),
+        ("saturday", Weekday::Sat),
+        ("sunday", Weekday::Sun),
+    ];
+    NAMES.into_iter()
+        .find(|(name, _)| word == *name || (short && word.len() == 3 && name.starts_with(word)))
+        .map(|(_, weekday)| weekday)
+}
+
+//the first day with that weekday, starting from `from`
+fn next_weekday(from: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (7 + weekday.num_days_from_monday() - from.weekday().num_days_from_monday()) % 7;
+    from + Duration::days(i64::from(days))
+}
+
+//9am, 9:30pm, 17:30; a bare hour like 9 only counts if bare_hours is true
+fn parse_time(word: &str, bare_hours: bool) -> Option<NaiveTime> {
+    let (clock, meridiem) = match word.strip_suffix("am") {
+        Some(clock) => (clock, Some(false)),
+        None => match word.strip_suffix("pm") {
+            Some(clock) => (clock, Some(true)),
+            None => (word, None),
+        },
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        Some(_) => return None,
+        None if meridiem.is_some() || bare_hours => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match meridiem {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+//a time at the start of words, and how many words it took up
+fn parse_time_phrase(words: &[&str]) -> Option<(NaiveTime, usize)> {
+    match words {
+        ["at", "noon", ..] => Some((NaiveTime::from_hms_opt(12, 0, 0)?, 2)),
+        ["at", time, "am" | "pm", ..] => Some((parse_time(&format!("{time}{}", words[2]), false)?, 3)),
+        ["at", time, ..] => Some((parse_time(time, true)?, 2)),
+        ["noon", ..] => Some((NaiveTime::from_hms_opt(12, 0, 0)?, 1)),
+        [time, "am" | "pm", ..] => Some((parse_time(&format!("{time}{}", words[1]), false)?, 2)),
+        [time, ..] => Some((parse_time(time, false)?, 1)),
+        [] => None,
+    }
+}
+
+//a date at the start of words, and how many words it took up
+fn parse_date_phrase(words: &[&str], now: DateTime<Local>) -> Option<(When, usize)> {
+    let today = now.date_naive();
+    match words {
+        ["on", rest @ ..] => {
+            let (when, used) = match rest.first().and_then(|x| parse_weekday(x, true)) {
+                Some(weekday) => (When::Day(next_weekday(today, weekday)), 1),
+                None if rest.first() != Some(&"on") => parse_date_phrase(rest, now)?,
+                None => return None,
+            };
+            Some((when, used + 1))
+        }
+        ["today", ..] => Some((When::Day(today), 1)),
+        ["tomorrow", ..] => Some((When::Day(today.succ_opt()?), 1)),
+        ["next", "week", ..] => Some((When::Day(today + Duration::days(7)), 2)),
+        ["next", "month", ..] => Some((When::Day(today.checked_add_months(Months::new(1))?), 2)),
+        ["next", "year", ..] => Some((When::Day(today.checked_add_months(Months::new(12))?), 2)),
+        ["next", weekday, ..] => Some((When::Day(next_weekday(today.succ_opt()?, parse_weekday(weekday, true)?)), 2)),
+        ["in", amount, unit, ..] => {
+            let amount = amount.parse::<u32>().ok()?;
+            let when = match *unit {
+                "minute" | "minutes" | "min" | "mins" => When::Moment(now.checked_add_signed(Duration::try_minutes(amount.into())?)?),
+                "hour" | "hours" => When::Moment(now.checked_add_signed(Duration::try_hours(amount.into())?)?),
+                "day" | "days" => When::Day(today.checked_add_days(Days::new(amount.into()))?),
+                "week" | "weeks" => When::Day(today.checked_add_days(Days::new(u64::from(amount) * 7))?),
+                "month" | "months" => When::Day(today.checked_add_months(Months::new(amount))?),
+                "year" | "years" => When::Day(today.checked_add_months(Months::new(amount.checked_mul(12)?))?),
+                _ => return None,
+            };
+            Some((when, 3))
+        }
+        [word, ..] => {
+            if let Some(weekday) = parse_weekday(word, false) {
+                return Some((When::Day(next_weekday(today, weekday)), 1));
+            }
+            NaiveDate::parse_from_str(word, "%Y-%m-%d").ok().map(|date| (When::Day(date), 1))
+        }
+        [] => None,
+    }
+}
+
+//a repeat rule at the start of words, and how many words it took up
+fn parse_recurrence_phrase(words: &[&str]) -> Option<(Recurrence, usize)> {
+    if words.first() != Some(&"every") {
+        return None;
+    }
+    if let Some(weekday) = words.get(1).and_then(|x| parse_weekday(x, true)) {
+        return Some((Recurrence::weekly_on(&[weekday]), 2));
+    }
+    //"every 3 days" before "every day", so the number isn't left in the name
+    [3, 2].into_iter()
+        .filter(|length| *length <= words.len())
+        .find_map(|length| words[..length].join(" ").parse::<Recurrence>().ok().map(|x| (x, length)))
+}
+
+fn parse_priority(word: &str) -> Option<Priority> {
+    let label = word.strip_prefix('!')?;
+    Priority::ALL.into_iter()
+        .filter(|x| *x != Priority::None)
+        .find(|x| x.label().eq_ignore_ascii_case(label))
+}
+
+//lowercase letters and digits only, so list:big-shop finds a list called "Big shop"
+fn simplify_list_name(name: &str) -> String {
+    name.chars().filter(|x| x.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+//whether what was typed after list: refers to a list with this title (or file name)
+pub fn list_name_matches(typed: &str, name: &str) -> bool {
+    let typed = simplify_list_name(typed);
+    !typed.is_empty() && typed == simplify_list_name(name)
+}
+
+//always the same result for the same text and now, which is what makes it testable
+pub fn parse(input: &str, now: DateTime<Local>) -> Result<QuickAdd, QuickAddError> {
+    let tokens = tokenize(input);
+    let lower: Vec<String> = tokens.iter().map(|x| x.text.to_lowercase()).collect();
+    let mut name_words: Vec<&str> = Vec::new();
+    let mut when = None;
+    let mut time = None;
+    let mut recurrence = None;
+    let mut tags = BTreeSet::new();
+    let mut priority = Priority::None;
+    let mut list = None;
+
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = &tokens[index];
+        //quoted words only ever go in the name, and nothing after them can reach back into them
+        let end = tokens[index..].iter().position(|x| x.quoted).map_or(tokens.len(), |x| index + x);
+        let words: Vec<&str> = lower[index..end].iter().map(|x| x.as_str()).collect();
+        let text = token.text.as_str();
+
+        let mut used = 1;
+        if token.quoted {
+            name_words.push(text);
+        } else if text.len() > 1 && text.starts_with(['#', '@']) {
+            tags.insert(String::from(text));
+        } else if let Some(found) = parse_priority(text) {
+            priority = found;
+        } else if let Some(name) = text.strip_prefix("list:").filter(|x| !x.is_empty()) {
+            list = Some(String::from(name));
+        } else if let Some((found, length)) = parse_recurrence_phrase(&words).filter(|_| recurrence.is_none()) {
+            recurrence = Some(found);
+            used = length;
+        } else if let Some((found, length)) = parse_date_phrase(&words, now).filter(|_| when.is_none()) {
+            when = Some(found);
+            used = length;
+        } else if let Some((found, length)) = parse_time_phrase(&words).filter(|_| time.is_none()) {
+            time = Some(found);
+            used = length;
+        } else {
+            name_words.push(text);
+        }
+        index += used;
+    }
+
+    if name_words.is_empty() {
+        return Err(QuickAddError::NoName);
+    }
+
+    let today = now.date_naive();
+    let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+    let at = |date: NaiveDate, time: NaiveTime| Local.from_local_datetime(&date.and_time(time)).earliest();
+    let due = match (when, time) {
+        (Some(When::Moment(moment)), _) => Some(moment),
+        (Some(When::Day(date)), time) => at(date, time.unwrap_or(end_of_day)),
+        (None, Some(time)) => {
+            let date = if at(today, time).is_some_and(|x| x > now) { today } else { today.succ_opt().unwrap_or(today) };
+            at(date, time)
+        }
+        //a repeat on its own starts from its first day, today included
+        (None, None) => recurrence.as_ref().and_then(|rule: &Recurrence| {
+            let first = if rule.weekdays.is_empty() { Some(today) } else { today.pred_opt().and_then(|x| rule.next_date(x)) };
+            at(first?, end_of_day)
+        }),
+    };
+
+    Ok(QuickAdd { name: name_words.join(" "), due, recurrence, tags, priority, list })
+}
+
+impl QuickAdd {
+    //everything except the list, which is up to whoever picks where the item goes
+    pub fn fill_item(&self, item: &mut TodoListItem) {
+        item.name = self.name.clone();
+        item.due = self.due;
+        item.recurrence = self.recurrence.clone();
+        item.tags = self.tags.clone();
+        item.priority = self.priority;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::recurrence::Frequency;
+
+    fn fixed_now() -> DateTime<Local> {
+        //a Wednesday
+        Local.with_ymd_and_hms(2024, 5, 22, 12, 0, 0).unwrap()
+    }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Option<DateTime<Local>> {
+        Local.with_ymd_and_hms(year, month, day, hour, minute, second).single()
+    }
+
+    #[test]
+    fn parses_a_whole_line() {
+        let parsed = parse("Pay rent tomorrow 9am #home !high every month", fixed_now()).unwrap();
+        assert_eq!(parsed.name, "Pay rent");
+        assert_eq!(parsed.due, at(2024, 5, 23, 9, 0, 0));
+        assert_eq!(parsed.recurrence, Some(Recurrence::new(Frequency::Monthly, 1)));
+        assert_eq!(parsed.tags, BTreeSet::from([String::from("#home")]));
+        assert_eq!(parsed.priority, Priority::High);
+        assert_eq!(parsed.list, None);
+    }
+
+    #[test]
+    fn parses_dates_and_times() {
+        let due = |text| parse(text, fixed_now()).unwrap().due;
+        assert_eq!(due("Call mum"), None);
+        assert_eq!(due("Call mum today"), at(2024, 5, 22, 23, 59, 59));
+        assert_eq!(due("Call mum friday at 5 pm"), at(2024, 5, 24, 17, 0, 0));
+        assert_eq!(due("Call mum wednesday"), at(2024, 5, 22, 23, 59, 59));
+        assert_eq!(due("Call mum next wed"), at(2024, 5, 29, 23, 59, 59));
+        assert_eq!(due("Call mum on 2024-06-01 17:30"), at(2024, 6, 1, 17, 30, 0));
+        assert_eq!(due("Call mum in 3 days"), at(2024, 5, 25, 23, 59, 59));
+        assert_eq!(due("Call mum in 2 hours"), at(2024, 5, 22, 14, 0, 0));
+        assert_eq!(due("Call mum next month"), at(2024, 6, 22, 23, 59, 59));
+        //times already gone today mean tomorrow
+        assert_eq!(due("Call mum 9am"), at(2024, 5, 23, 9, 0, 0));
+        assert_eq!(due("Call mum at noon"), at(2024, 5, 23, 12, 0, 0));
+        assert_eq!(due("Call mum 12:30"), at(2024, 5, 22, 12, 30, 0));
+    }
+
+    #[test]
+    fn repeats_start_on_their_first_day() {
+        let parsed = parse("Team lunch every friday", fixed_now()).unwrap();
+        assert_eq!(parsed.recurrence, Some(Recurrence::weekly_on(&[Weekday::Fri])));
+        assert_eq!(parsed.due, at(2024, 5, 24, 23, 59, 59));
+
+        let parsed = parse("Water plants every 3 days", fixed_now()).unwrap();
+        assert_eq!(parsed.name, "Water plants");
+        assert_eq!(parsed.recurrence, Some(Recurrence::every_n_days(3)));
+        assert_eq!(parsed.due, at(2024, 5, 22, 23, 59, 59));
+    }
+
+    #[test]
+    fn dates_out_of_range_are_left_in_the_name() {
+        let parsed = parse("Call in 4294967295 hours", fixed_now()).unwrap();
+        assert_eq!(parsed.name, "Call in 4294967295 hours");
+        assert_eq!(parsed.due, None);
+        assert_eq!(parse("Call in 4294967295 years", fixed_now()).unwrap().due, None);
+    }
+
+    #[test]
+    fn leaves_ordinary_words_in_the_name() {
+        let parsed = parse("Sit in the sun at the park !nope", fixed_now()).unwrap();
+        assert_eq!(parsed.name, "Sit in the sun at the park !nope");
+        assert_eq!(parsed.due, None);
+
+        let parsed = parse("\"Read Friday\" tomorrow tomorrow", fixed_now()).unwrap();
+        assert_eq!(parsed.name, "Read Friday tomorrow");
+        assert_eq!(parsed.due, at(2024, 5, 23, 23, 59, 59));
+    }
+
+    #[test]
+    fn picks_out_the_target_list() {
+        let parsed = parse("Milk list:big-shop @errand", fixed_now()).unwrap();
+        assert_eq!(parsed.name, "Milk");
+        assert!(list_name_matches(parsed.list.as_deref().unwrap(), "Big Shop"));
+        assert!(!list_name_matches(parsed.list.as_deref().unwrap(), "Big Shopping"));
+        assert_eq!(parse("tomorrow #home", fixed_now()), Err(QuickAddError::NoName));
+    }
+}
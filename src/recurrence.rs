@@ -0,0 +1,544 @@
+//repeat rules for recurring items
+//covers the friendly forms used in the UI ("daily", "every 3 days", "weekly on mon,fri", "monthly on 15")
+//and the FREQ, INTERVAL, BYDAY, BYMONTHDAY, COUNT and UNTIL parts of an RFC 5545 RRULE
+
+use std::fmt;
+use std::str::FromStr;
+
+use ::serde::{Serialize, Deserialize};
+
+use chrono::*;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32, //every `interval` days/weeks/months/years; 0 is treated as 1
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>, //BYDAY; for weekly rules an empty list means the weekday the item is already on
+    #[serde(default)]
+    pub month_day: Option<i32>, //BYMONTHDAY; negative values count back from the end of the month, so -1 is the last day
+    #[serde(default)]
+    pub count: Option<u32>, //total number of occurrences, including the first and any skipped while overdue
+    #[serde(default)]
+    pub until: Option<NaiveDate>, //last date an occurrence may fall on
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RecurrenceError {
+    Empty,
+    UnknownRule(String),
+    MissingFrequency,
+    UnsupportedPart(String),
+    UnsupportedWithFrequency { part: String, frequency: String }, //a part this app only handles with some frequencies
+    InvalidValue { part: String, value: String },
+    SameWeekdayEveryTime { interval: u32 }, //a daily rule whose interval is whole weeks, which BYDAY can't pick days from
+}
+
+impl fmt::Display for RecurrenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecurrenceError::Empty => write!(f, "the repeat rule is empty"),
+            RecurrenceError::UnknownRule(rule) => write!(f, "\"{rule}\" isn't a repeat rule this app understands"),
+            RecurrenceError::MissingFrequency => write!(f, "RRULE needs a FREQ part"),
+            RecurrenceError::UnsupportedPart(part) => write!(f, "the RRULE part \"{part}\" isn't supported"),
+            RecurrenceError::UnsupportedWithFrequency { part, frequency } => write!(f, "the RRULE part \"{part}\" isn't supported with FREQ={frequency}"),
+            RecurrenceError::InvalidValue { part, value } => write!(f, "\"{value}\" isn't a valid value for {part}"),
+            RecurrenceError::SameWeekdayEveryTime { interval } => {
+                write!(f, "every {interval} days always lands on the same weekday, so BYDAY can't be used with it; use FREQ=WEEKLY;INTERVAL={} instead", interval / 7)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecurrenceError {}
+
+//intervals past this are refused when parsing, since nobody means to repeat something every 5000 years
+pub const MAX_INTERVAL: u32 = 1000;
+
+const WEEKDAYS: [Weekday; 5] = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri];
+
+impl Recurrence {
+    pub fn new(frequency: Frequency, interval: u32) -> Recurrence {
+        Recurrence { frequency, interval, weekdays: Vec::new(), month_day: None, count: None, until: None }
+    }
+
+    pub fn daily() -> Recurrence {
+        Recurrence::new(Frequency::Daily, 1)
+    }
+
+    pub fn weekdays() -> Recurrence {
+        Recurrence { weekdays: WEEKDAYS.to_vec(), ..Recurrence::daily() }
+    }
+
+    pub fn every_n_days(days: u32) -> Recurrence {
+        Recurrence::new(Frequency::Daily, days)
+    }
+
+    pub fn weekly_on(weekdays: &[Weekday]) -> Recurrence {
+        Recurrence { weekdays: weekdays.to_vec(), ..Recurrence::new(Frequency::Weekly, 1) }
+    }
+
+    pub fn monthly_on_day(day: i32) -> Recurrence {
+        Recurrence { month_day: Some(day), ..Recurrence::new(Frequency::Monthly, 1) }
+    }
+
+    //the first occurrence on a later day than `current`, at the same time of day, or just after the time if a clock
+    //change skips it that day
+    //None once the rule has run past its UNTIL date (COUNT is tracked by the item, see TodoList::set_completed_at)
+    pub fn next_after<Tz: TimeZone>(&self, current: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let date = self.next_date(current.date_naive())?;
+        let time = date.and_time(current.time());
+        let timezone = current.timezone();
+        //clock changes skip an hour at most in practice, but some zones have skipped a whole day
+        (0..=24 * 4).find_map(|x| timezone.from_local_datetime(&(time + Duration::minutes(15 * x))).earliest())
+    }
+
+    pub fn next_date(&self, current: NaiveDate) -> Option<NaiveDate> {
+        let interval = self.interval.max(1);
+
+        let next = match self.frequency {
+            Frequency::Daily => {
+                //stepping by an interval that isn't a whole number of weeks visits every weekday within seven steps;
+                //from_rrule refuses BYDAY with one that is, since it would only ever land on one weekday
+                let mut date = current;
+                let mut found = None;
+                for _ in 0..7 {
+                    date = date.checked_add_days(Days::new(interval.into()))?;
+                    if self.weekdays.is_empty() || self.weekdays.contains(&date.weekday()) {
+                        found = Some(date);
+                        break;
+                    }
+                }
+                found
+            }
+            Frequency::Weekly => {
+                //the rest of this week, then the week `interval` weeks on
+                let weekdays = if self.weekdays.is_empty() { vec![current.weekday()] } else { self.weekdays.clone() };
+                let first_week = current.week(Weekday::Mon).first_day();
+                let next_week = first_week.checked_add_days(Days::new(7 * u64::from(interval)))?;
+                let rest_of_week = (1..7).filter_map(|x| current.checked_add_days(Days::new(x)))
+                    .take_while(|date| date.week(Weekday::Mon).first_day() == first_week);
+                rest_of_week.chain((0..7).filter_map(|x| next_week.checked_add_days(Days::new(x))))
+                    .find(|date| weekdays.contains(&date.weekday()))
+            }
+            Frequency::Monthly => {
+                //months without the wanted day (the 31st, say) are skipped, like RFC 5545 does
+                let day = self.month_day.unwrap_or(current.day() as i32);
+                let first_month = current.year() * 12 + current.month0() as i32;
+                let interval = i32::try_from(interval).ok()?;
+                (0..48).find_map(|step: i32| {
+                    let month = step.checked_mul(interval)?.checked_add(first_month)?;
+                    day_of_month(month.div_euclid(12), month.rem_euclid(12) as u32 + 1, day)
+                        .filter(|date| *date > current)
+                })
+            }
+            Frequency::Yearly => {
+                //only Feb 29 can be missing, so a few leap cycles is plenty
+                let interval = i32::try_from(interval).ok()?;
+                (1..=8).find_map(|step: i32| {
+                    let year = step.checked_mul(interval)?.checked_add(current.year())?;
+                    NaiveDate::from_ymd_opt(year, current.month(), current.day())
+                })
+            }
+        };
+
+        next.filter(|date| self.until.map_or(true, |until| *date <= until))
+    }
+
+    pub fn to_rrule(&self) -> String {
+        let mut parts = vec![format!("FREQ={}", match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        })];
+        if self.interval > 1 {
+            parts.push(format!("INTERVAL={}", self.interval));
+        }
+        if !self.weekdays.is_empty() {
+            let days: Vec<&str> = self.weekdays.iter().map(|x| rrule_weekday(*x)).collect();
+            parts.push(format!("BYDAY={}", days.join(",")));
+        }
+        if let Some(day) = self.month_day {
+            parts.push(format!("BYMONTHDAY={day}"));
+        }
+        if let Some(count) = self.count {
+            parts.push(format!("COUNT={count}"));
+        }
+        if let Some(until) = self.until {
+            parts.push(format!("UNTIL={}", until.format("%Y%m%d")));
+        }
+        parts.join(";")
+    }
+
+    //accepts an RRULE with or without the "RRULE:" prefix
+    pub fn from_rrule(rule: &str) -> Result<Recurrence, RecurrenceError> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").or_else(|| rule.strip_prefix("rrule:")).unwrap_or(rule);
+        if rule.is_empty() {
+            return Err(RecurrenceError::Empty);
+        }
+
+        let mut frequency = None;
+        let mut recurrence = Recurrence::daily();
+        //BYDAY and BYMONTHDAY only mean something to next_date for some frequencies, so the rest are refused rather
+        //than ignored
+        let mut by_day = None;
+        let mut by_month_day = None;
+        let mut frequency_name = String::new();
+
+        for part in rule.split(';').filter(|x| !x.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| RecurrenceError::UnsupportedPart(String::from(part)))?;
+            let key = key.to_ascii_uppercase();
+            let invalid = || RecurrenceError::InvalidValue { part: key.clone(), value: String::from(value) };
+
+            match key.as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid()),
+                    });
+                    frequency_name = value.to_ascii_uppercase();
+                }
+                "INTERVAL" => {
+                    recurrence.interval = value.parse::<u32>().ok().filter(|x| (1..=MAX_INTERVAL).contains(x)).ok_or_else(invalid)?;
+                }
+                "BYDAY" => {
+                    recurrence.weekdays = value.split(',')
+                        .map(|x| parse_weekday(x).ok_or_else(invalid))
+                        .collect::<Result<Vec<Weekday>, RecurrenceError>>()?;
+                    by_day = Some(part);
+                }
+                "BYMONTHDAY" => {
+                    let day = value.parse::<i32>().map_err(|_| invalid())?;
+                    if day == 0 || !(-31..=31).contains(&day) {
+                        return Err(invalid());
+                    }
+                    recurrence.month_day = Some(day);
+                    by_month_day = Some(part);
+                }
+                "COUNT" => {
+                    recurrence.count = Some(value.parse::<u32>().ok().filter(|x| *x > 0).ok_or_else(invalid)?);
+                }
+                "UNTIL" => {
+                    //date-times are cut down to their date, which is all this app schedules by
+                    let date = value.get(..8).ok_or_else(invalid)?;
+                    recurrence.until = Some(NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| invalid())?);
+                }
+                "WKST" if value.eq_ignore_ascii_case("MO") => {}
+                _ => return Err(RecurrenceError::UnsupportedPart(String::from(part))),
+            }
+        }
+
+        recurrence.frequency = frequency.ok_or(RecurrenceError::MissingFrequency)?;
+        let unsupported = match recurrence.frequency {
+            Frequency::Daily | Frequency::Weekly => by_month_day,
+            Frequency::Monthly => by_day,
+            Frequency::Yearly => by_day.or(by_month_day),
+        };
+        if let Some(part) = unsupported {
+            return Err(RecurrenceError::UnsupportedWithFrequency { part: String::from(part), frequency: frequency_name });
+        }
+        if recurrence.frequency == Frequency::Daily && !recurrence.weekdays.is_empty() && recurrence.interval % 7 == 0 {
+            return Err(RecurrenceError::SameWeekdayEveryTime { interval: recurrence.interval });
+        }
+        Ok(recurrence)
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = RecurrenceError;
+
+    fn from_str(input: &str) -> Result<Recurrence, RecurrenceError> {
+        let text = input.trim();
+        if text.is_empty() {
+            return Err(RecurrenceError::Empty);
+        }
+        let upper = text.to_ascii_uppercase();
+        if upper.starts_with("RRULE:") || upper.starts_with("FREQ=") {
+            return Recurrence::from_rrule(text);
+        }
+
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower.split_whitespace().collect();
+        let unknown = || RecurrenceError::UnknownRule(String::from(text));
+
+        match words.as_slice() {
+            ["daily"] | ["every", "day"] => Ok(Recurrence::daily()),
+            ["weekdays"] | ["every", "weekday"] => Ok(Recurrence::weekdays()),
+            ["weekly"] | ["every", "week"] => Ok(Recurrence::new(Frequency::Weekly, 1)),
+            ["monthly"] | ["every", "month"] => Ok(Recurrence::new(Frequency::Monthly, 1)),
+            ["yearly"] | ["every", "year"] => Ok(Recurrence::new(Frequency::Yearly, 1)),
+            ["every", n, unit] => {
+                let n = n.parse::<u32>().ok().filter(|x| (1..=MAX_INTERVAL).contains(x)).ok_or_else(unknown)?;
+                let frequency = match *unit {
+                    "days" | "day" => Frequency::Daily,
+                    "weeks" | "week" => Frequency::Weekly,
+                    "months" | "month" => Frequency::Monthly,
+                    "years" | "year" => Frequency::Yearly,
+                    _ => return Err(unknown()),
+                };
+                Ok(Recurrence::new(frequency, n))
+            }
+            ["weekly", "on", days @ ..] if !days.is_empty() => {
+                let weekdays = days.join(",").split(',')
+                    .filter(|x| !x.is_empty())
+                    .map(|x| parse_weekday(x).ok_or_else(unknown))
+                    .collect::<Result<Vec<Weekday>, RecurrenceError>>()?;
+                Ok(Recurrence::weekly_on(&weekdays))
+            }
+            ["monthly", "on", "last"] | ["monthly", "on", "the", "last", "day"] => Ok(Recurrence::monthly_on_day(-1)),
+            ["monthly", "on", day] | ["monthly", "on", "the", day] => {
+                let day = day.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+                let day = day.parse::<i32>().ok().filter(|x| (1..=31).contains(x)).ok_or_else(unknown)?;
+                Ok(Recurrence::monthly_on_day(day))
+            }
+            _ => Err(unknown()),
+        }
+    }
+}
+
+//writes the friendly form when there is one, so that parsing the output gives the same rule back
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.count.is_none() && self.until.is_none() {
+            let plain = self.weekdays.is_empty() && self.month_day.is_none();
+            match (self.frequency, self.interval.max(1)) {
+                (Frequency::Daily, 1) if plain => return write!(f, "daily"),
+                (Frequency::Daily, 1) if self.weekdays == WEEKDAYS && self.month_day.is_none() => return write!(f, "weekdays"),
+                (Frequency::Daily, n) if plain => return write!(f, "every {n} days"),
+                (Frequency::Weekly, 1) if plain => return write!(f, "weekly"),
+                (Frequency::Weekly, 1) if self.month_day.is_none() => {
+                    let days: Vec<String> = self.weekdays.iter().map(|x| x.to_string().to_lowercase()).collect();
+                    return write!(f, "weekly on {}", days.join(","));
+                }
+                (Frequency::Weekly, n) if plain => return write!(f, "every {n} weeks"),
+                (Frequency::Monthly, 1) if plain => return write!(f, "monthly"),
+                (Frequency::Monthly, 1) if self.weekdays.is_empty() && self.month_day == Some(-1) => return write!(f, "monthly on last"),
+                (Frequency::Monthly, 1) if self.weekdays.is_empty() && self.month_day.is_some_and(|x| x > 0) => {
+                    return write!(f, "monthly on {}", self.month_day.unwrap_or_default());
+                }
+                (Frequency::Monthly, n) if plain => return write!(f, "every {n} months"),
+                (Frequency::Yearly, 1) if plain => return write!(f, "yearly"),
+                (Frequency::Yearly, n) if plain => return write!(f, "every {n} years"),
+                _ => {}
+            }
+        }
+        write!(f, "{}", self.to_rrule())
+    }
+}
+
+//the date for `day` in the given month, counting back from the end of the month if day is negative
+fn day_of_month(year: i32, month: u32, day: i32) -> Option<NaiveDate> {
+    if day > 0 {
+        return NaiveDate::from_ymd_opt(year, month, day as u32);
+    }
+    let first_of_next = if month == 12 { NaiveDate::from_ymd_opt(year + 1, 1, 1)? } else { NaiveDate::from_ymd_opt(year, month + 1, 1)? };
+    let date = first_of_next.checked_sub_days(Days::new(day.unsigned_abs().into()))?;
+    (date.month() == month).then_some(date)
+}
+
+//accepts RRULE's two letter codes as well as anything chrono reads ("mon", "Monday")
+fn parse_weekday(text: &str) -> Option<Weekday> {
+    let text = text.trim();
+    match text.to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => text.parse::<Weekday>().ok(),
+    }
+}
+
+fn rrule_weekday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn daily_and_every_n_days() {
+        assert_eq!(Recurrence::daily().next_date(date(2024, 5, 31)), Some(date(2024, 6, 1)));
+        assert_eq!(Recurrence::every_n_days(3).next_date(date(2024, 5, 22)), Some(date(2024, 5, 25)));
+    }
+
+    #[test]
+    fn weekdays_skip_the_weekend() {
+        //2024-05-24 is a Friday
+        assert_eq!(Recurrence::weekdays().next_date(date(2024, 5, 24)), Some(date(2024, 5, 27)));
+        assert_eq!(Recurrence::weekdays().next_date(date(2024, 5, 22)), Some(date(2024, 5, 23)));
+    }
+
+    #[test]
+    fn weekly_on_given_days() {
+        let rule = Recurrence::weekly_on(&[Weekday::Mon, Weekday::Thu]);
+        //Wednesday -> Thursday -> next Monday
+        assert_eq!(rule.next_date(date(2024, 5, 22)), Some(date(2024, 5, 23)));
+        assert_eq!(rule.next_date(date(2024, 5, 23)), Some(date(2024, 5, 27)));
+
+        let fortnightly: Recurrence = "FREQ=WEEKLY;INTERVAL=2".parse().unwrap();
+        assert_eq!(fortnightly.next_date(date(2024, 5, 22)), Some(date(2024, 6, 5)));
+    }
+
+    #[test]
+    fn monthly_skips_short_months_and_handles_last_day() {
+        let on_31st = Recurrence::monthly_on_day(31);
+        assert_eq!(on_31st.next_date(date(2024, 5, 31)), Some(date(2024, 7, 31)));
+
+        let last_day = Recurrence::monthly_on_day(-1);
+        assert_eq!(last_day.next_date(date(2024, 1, 31)), Some(date(2024, 2, 29)));
+        assert_eq!(Recurrence::monthly_on_day(15).next_date(date(2024, 5, 10)), Some(date(2024, 5, 15)));
+    }
+
+    #[test]
+    fn stops_after_until() {
+        let rule: Recurrence = "RRULE:FREQ=DAILY;UNTIL=20240523T235959Z".parse().unwrap();
+        assert_eq!(rule.next_date(date(2024, 5, 22)), Some(date(2024, 5, 23)));
+        assert_eq!(rule.next_date(date(2024, 5, 23)), None);
+    }
+
+    #[test]
+    fn parses_friendly_rules() {
+        assert_eq!("every 3 days".parse::<Recurrence>().unwrap(), Recurrence::every_n_days(3));
+        assert_eq!("Weekly on mon, fri".parse::<Recurrence>().unwrap(), Recurrence::weekly_on(&[Weekday::Mon, Weekday::Fri]));
+        assert_eq!("monthly on the 15th".parse::<Recurrence>().unwrap(), Recurrence::monthly_on_day(15));
+        assert_eq!("every month".parse::<Recurrence>().unwrap(), Recurrence::new(Frequency::Monthly, 1));
+        assert!(matches!("sometimes".parse::<Recurrence>(), Err(RecurrenceError::UnknownRule(_))));
+    }
+
+    #[test]
+    fn reports_bad_rrules() {
+        assert_eq!(Recurrence::from_rrule("INTERVAL=2"), Err(RecurrenceError::MissingFrequency));
+        assert_eq!(Recurrence::from_rrule("FREQ=HOURLY"), Err(RecurrenceError::InvalidValue { part: String::from("FREQ"), value: String::from("HOURLY") }));
+        assert_eq!(Recurrence::from_rrule("FREQ=DAILY;BYSETPOS=1"), Err(RecurrenceError::UnsupportedPart(String::from("BYSETPOS=1"))));
+        assert_eq!(Recurrence::from_rrule("FREQ=monthly;BYDAY=MO"), Err(RecurrenceError::UnsupportedWithFrequency { part: String::from("BYDAY=MO"), frequency: String::from("MONTHLY") }));
+        assert_eq!(Recurrence::from_rrule("FREQ=WEEKLY;BYMONTHDAY=3"), Err(RecurrenceError::UnsupportedWithFrequency { part: String::from("BYMONTHDAY=3"), frequency: String::from("WEEKLY") }));
+        assert!(Recurrence::from_rrule("FREQ=DAILY;BYMONTHDAY=3").is_err());
+        assert!(Recurrence::from_rrule("FREQ=YEARLY;BYDAY=FR").is_err());
+        assert!(Recurrence::from_rrule("FREQ=DAILY;BYDAY=MO,TU").is_ok());
+        assert!(Recurrence::from_rrule("FREQ=MONTHLY;BYMONTHDAY=-1").is_ok());
+        assert_eq!(Recurrence::from_rrule("FREQ=MONTHLY;INTERVAL=4000000000"), Err(RecurrenceError::InvalidValue { part: String::from("INTERVAL"), value: String::from("4000000000") }));
+        assert!(matches!("every 100000000 months".parse::<Recurrence>(), Err(RecurrenceError::UnknownRule(_))));
+    }
+
+    #[test]
+    fn refuses_weekdays_on_whole_week_daily_intervals() {
+        //stepping 7 days from a Tuesday never reaches a Monday, which would end the item's recurrence for good
+        assert_eq!(Recurrence::from_rrule("FREQ=DAILY;INTERVAL=7;BYDAY=MO"), Err(RecurrenceError::SameWeekdayEveryTime { interval: 7 }));
+        assert_eq!(Recurrence::from_rrule("FREQ=DAILY;INTERVAL=14;BYDAY=MO,TU"), Err(RecurrenceError::SameWeekdayEveryTime { interval: 14 }));
+        assert!(Recurrence::from_rrule("FREQ=DAILY;INTERVAL=14").is_ok());
+
+        let rule = Recurrence::from_rrule("FREQ=DAILY;INTERVAL=8;BYDAY=MO").unwrap();
+        let next = rule.next_date(date(2024, 5, 21)).unwrap();
+        assert_eq!(next.weekday(), Weekday::Mon);
+    }
+
+    //a zone an hour ahead of UTC that springs forward to two hours ahead at 02:00 local time on 2024-03-31, so the
+    //local times from 02:00 to 03:00 that day never happen
+    #[derive(Clone, Copy, Debug)]
+    struct SpringForward;
+
+    impl SpringForward {
+        fn change() -> NaiveDateTime {
+            date(2024, 3, 31).and_hms_opt(1, 0, 0).unwrap()
+        }
+    }
+
+    impl TimeZone for SpringForward {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> SpringForward {
+            SpringForward
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let winter = FixedOffset::east_opt(3600).unwrap();
+            let summer = FixedOffset::east_opt(7200).unwrap();
+            if *local < SpringForward::change() + Duration::hours(1) {
+                LocalResult::Single(winter)
+            } else if *local < SpringForward::change() + Duration::hours(2) {
+                LocalResult::None
+            } else {
+                LocalResult::Single(summer)
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            FixedOffset::east_opt(if *utc < SpringForward::change() { 3600 } else { 7200 }).unwrap()
+        }
+    }
+
+    #[test]
+    fn occurrences_skipped_by_a_clock_change_happen_just_after_it() {
+        let at = |month: u32, day: u32, hour: u32, minute: u32| SpringForward.from_local_datetime(&date(2024, month, day).and_hms_opt(hour, minute, 0).unwrap()).unwrap();
+        let next = Recurrence::daily().next_after(at(3, 30, 2, 30));
+        assert_eq!(next, Some(at(3, 31, 3, 0)));
+        assert_eq!(Recurrence::daily().next_after(at(3, 31, 3, 0)), Some(at(4, 1, 3, 0)));
+        assert_eq!(Recurrence::daily().next_after(at(3, 30, 9, 0)), Some(at(3, 31, 9, 0)));
+        //the series only ends through UNTIL (or COUNT), not because a clock change got in the way
+        let until = Recurrence { until: Some(date(2024, 3, 31)), ..Recurrence::daily() };
+        assert_eq!(until.next_after(at(3, 30, 2, 30)), Some(at(3, 31, 3, 0)));
+        assert_eq!(until.next_after(at(3, 31, 3, 0)), None);
+    }
+
+    #[test]
+    fn huge_intervals_run_out_instead_of_overflowing() {
+        let start = date(2024, 5, 22);
+        assert_eq!(Recurrence::new(Frequency::Monthly, 4_000_000_000).next_date(start), None);
+        assert_eq!(Recurrence::new(Frequency::Yearly, 100_000_000).next_date(start), None);
+        assert_eq!(Recurrence::new(Frequency::Weekly, u32::MAX).next_date(start), None);
+        assert_eq!(Recurrence::new(Frequency::Weekly, 2).next_date(start), Some(date(2024, 6, 5)));
+    }
+
+    #[test]
+    fn display_parses_back_to_the_same_rule() {
+        let rules = [
+            Recurrence::daily(),
+            Recurrence::weekdays(),
+            Recurrence::every_n_days(4),
+            Recurrence::weekly_on(&[Weekday::Tue, Weekday::Sat]),
+            Recurrence::monthly_on_day(-1),
+            Recurrence::monthly_on_day(9),
+            Recurrence { count: Some(5), ..Recurrence::new(Frequency::Yearly, 2) },
+        ];
+        for rule in rules {
+            assert_eq!(rule.to_string().parse::<Recurrence>().unwrap(), rule);
+        }
+    }
+}
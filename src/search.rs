@@ -0,0 +1,196 @@
+//full-text search over item names and descriptions, across any number of lists
+//every word of the search has to turn up in the item, either as a whole word, the start of a word,
+//or a word with a typo or two in it; whole words score highest, and matches in the name count double
+
+use std::ops::Range;
+
+use crate::{TodoList, TodoListItem};
+
+const EXACT_SCORE: u32 = 30;
+const PREFIX_SCORE: u32 = 20;
+const FUZZY_SCORE: u32 = 10; //less 3 per typo
+const NAME_WEIGHT: u32 = 2;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SearchHit {
+    pub list_index: usize, //which of the searched lists the item is in
+    pub item_id: usize,
+    pub score: u32,
+    pub name_matches: Vec<Range<usize>>, //byte ranges of the matching words, for highlighting
+    pub description_matches: Vec<Range<usize>>,
+}
+
+//the words in text (runs of letters and digits), with where they are
+fn words(text: &str) -> Vec<(Range<usize>, String)> {
+    let mut found = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(from)) => {
+                found.push((from..index, text[from..index].to_lowercase()));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+//how many typos a term of this many characters may have
+fn allowed_typos(length: usize) -> usize {
+    match length {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+//edit distance counting swapped neighbouring letters as one typo (optimal string alignment)
+fn typo_distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+fn word_score(term: &str, word: &str) -> Option<u32> {
+    if word == term {
+        return Some(EXACT_SCORE);
+    }
+    if word.starts_with(term) {
+        return Some(PREFIX_SCORE);
+    }
+    let term_chars: Vec<char> = term.chars().collect();
+    let word_chars: Vec<char> = word.chars().collect();
+    let allowed = allowed_typos(term_chars.len());
+    if allowed == 0 || term_chars.len().abs_diff(word_chars.len()) > allowed {
+        return None;
+    }
+    let typos = typo_distance(&term_chars, &word_chars);
+    (typos <= allowed).then(|| FUZZY_SCORE - 3 * typos as u32)
+}
+
+//the best score the term gets against any of the words, and the ranges of every word it matched
+fn best_match(term: &str, words: &[(Range<usize>, String)]) -> (u32, Vec<Range<usize>>) {
+    let mut best = 0;
+    let mut ranges = Vec::new();
+    for (range, word) in words {
+        if let Some(score) = word_score(term, word) {
+            best = best.max(score);
+            ranges.push(range.clone());
+        }
+    }
+    (best, ranges)
+}
+
+//None unless every term matches the name or the description
+fn score_item(item: &TodoListItem, terms: &[String], list_index: usize) -> Option<SearchHit> {
+    let name_words = words(&item.name);
+    let description_words = words(&item.description);
+    let mut total = 0;
+    let mut name_matches = Vec::new();
+    let mut description_matches = Vec::new();
+    for term in terms {
+        let (name_score, name_ranges) = best_match(term, &name_words);
+        let (description_score, description_ranges) = best_match(term, &description_words);
+        let score = (name_score * NAME_WEIGHT).max(description_score);
+        if score == 0 {
+            return None;
+        }
+        total += score;
+        name_matches.extend(name_ranges);
+        description_matches.extend(description_ranges);
+    }
+    for matches in [&mut name_matches, &mut description_matches] {
+        matches.sort_by_key(|x| x.start);
+        matches.dedup();
+    }
+    Some(SearchHit { list_index, item_id: item.id, score: total, name_matches, description_matches })
+}
+
+//every matching item in the lists, subtasks included, best first; ties keep the order of the lists and items
+pub fn search(lists: &[&TodoList], query: &str) -> Vec<SearchHit> {
+    let terms: Vec<String> = words(query).into_iter().map(|(_, x)| x).collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let mut hits = Vec::new();
+    for (list_index, list) in lists.iter().enumerate() {
+        hits.extend(list.all_items().into_iter().filter_map(|item| score_item(item, &terms, list_index)));
+    }
+    hits.sort_by_key(|x| std::cmp::Reverse(x.score));
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::directory::ListDirectory;
+
+    fn make_list(title: &str, items: &[(&str, &str)]) -> TodoList {
+        let mut list = TodoList::new(String::from(title));
+        for (name, description) in items {
+            list.add(String::from(*name), String::from(*description));
+        }
+        list
+    }
+
+    #[test]
+    fn matches_whole_words_prefixes_and_typos() {
+        let list = make_list("work", &[("Send Invoice", ""), ("Invoices archive", ""), ("Pay rent", "the invioce is due")]);
+        let hits = search(&[&list], "invoice");
+        let ids: Vec<usize> = hits.iter().map(|x| x.item_id).collect();
+        //exact name match, then prefix in a name, then a typo in the description
+        assert_eq!(ids, vec![0, 1, 2]);
+        assert_eq!(hits[0].name_matches, vec![5..12]);
+        assert_eq!(hits[2].description_matches, vec![4..11]);
+
+        assert!(search(&[&list], "xyz").is_empty());
+        assert!(search(&[&list], "").is_empty());
+    }
+
+    #[test]
+    fn every_term_has_to_match() {
+        let list = make_list("home", &[("Buy milk", "from the corner shop"), ("Buy bread", "")]);
+        let ids: Vec<usize> = search(&[&list], "BUY shop").iter().map(|x| x.item_id).collect();
+        assert_eq!(ids, vec![0]);
+    }
+
+    #[test]
+    fn short_terms_need_to_be_exact() {
+        assert_eq!(word_score("cat", "cut"), None);
+        assert_eq!(word_score("cat", "catalog"), Some(PREFIX_SCORE));
+        assert_eq!(word_score("groceries", "grocreies"), Some(FUZZY_SCORE - 3));
+    }
+
+    #[test]
+    fn searches_several_lists_and_a_directory() {
+        let dir = tempfile::tempdir().expect("searches_several_lists_and_a_directory() test panicked; temporary directory creation failed.");
+        let mut first = make_list("first", &[("Call the dentist", "")]);
+        let mut second = make_list("second", &[("Dentist invoice", "")]);
+        first.save(&dir.path().join("a.json")).unwrap();
+        second.save(&dir.path().join("b.json")).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a list").unwrap();
+
+        let directory = ListDirectory::open(dir.path()).unwrap();
+        let loaded: Vec<TodoList> = directory.paths().iter().map(|x| directory.load(x).unwrap()).collect();
+        assert_eq!(loaded.len(), 2);
+        let lists: Vec<&TodoList> = loaded.iter().collect();
+        let found: Vec<usize> = search(&lists, "dentist").iter().map(|x| x.list_index).collect();
+        assert_eq!(found, vec![0, 1]);
+    }
+}
@@ -0,0 +1,97 @@
+//the workflow statuses a list's items move through, such as "In progress" or "Won't do"
+//every item stores the name of its status, and that's what decides whether it's finished: its completed flag is only
+//ever changed along with the status, to match the status's category, so anything that only cares whether an item is
+//finished can keep looking at completed. ticking or unticking an item moves it to the first status of the category
+//a kanban list's columns are statuses too (see TodoList::column_of)
+
+use std::fmt;
+
+use ::serde::{Serialize, Deserialize};
+
+use chrono::*;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct StatusDef {
+    pub name: String,
+    pub done: bool,
+}
+
+//one move from a status to another, kept on the item in TodoListItem::status_log
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct StatusChange {
+    pub from: String,
+    pub to: String,
+    pub at: DateTime<Local>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StatusError {
+    EmptyName,
+    DuplicateStatus(String),
+    UnknownStatus(String),
+    LastOfCategory(String), //removing it would leave no done (or no open) status
+    NeededColumn(String), //removing it would leave a kanban list with too few columns
+    MissingItem(usize),
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatusError::EmptyName => write!(f, "statuses need a name"),
+            StatusError::DuplicateStatus(name) => write!(f, "there is already a status called \"{name}\""),
+            StatusError::UnknownStatus(name) => write!(f, "this list has no status called \"{name}\""),
+            StatusError::LastOfCategory(name) => write!(f, "\"{name}\" can't be removed, a list needs at least one open and one done status"),
+            StatusError::NeededColumn(name) => write!(f, "\"{name}\" can't be removed, a kanban list needs at least two columns"),
+            StatusError::MissingItem(id) => write!(f, "there is no item with id {id} in this list"),
+        }
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+impl StatusDef {
+    pub fn new(name: &str, done: bool) -> StatusDef {
+        StatusDef { name: String::from(name), done }
+    }
+
+    pub fn category_label(&self) -> &'static str {
+        if self.done { "Done" } else { "Open" }
+    }
+}
+
+//what lists start with, and what lists saved before statuses existed get; completed items are "Done", the rest "To do"
+pub fn default_statuses() -> Vec<StatusDef> {
+    vec![
+        StatusDef::new("To do", false),
+        StatusDef::new("In progress", false),
+        StatusDef::new("Waiting", false),
+        StatusDef::new("Done", true),
+        StatusDef::new("Won't do", true),
+    ]
+}
+
+//the first status in the category, which is where items land when they're ticked or unticked
+pub fn default_status(statuses: &[StatusDef], done: bool) -> Option<&StatusDef> {
+    statuses.iter().find(|x| x.done == done)
+}
+
+//what a status stored on an item stands for: the named status if the list has it, else the default for the category
+//which is only needed for items that come from a file or another list
+pub fn resolve<'a>(statuses: &'a [StatusDef], name: Option<&str>, done: bool) -> Option<&'a StatusDef> {
+    name.and_then(|name| statuses.iter().find(|x| x.name == name && x.done == done))
+        .or_else(|| default_status(statuses, done))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_statuses_fall_back_to_the_category_default() {
+        let statuses = default_statuses();
+        assert_eq!(resolve(&statuses, Some("Waiting"), false).unwrap().name, "Waiting");
+        assert_eq!(resolve(&statuses, Some("Waiting"), true).unwrap().name, "Done");
+        assert_eq!(resolve(&statuses, Some("Gone"), false).unwrap().name, "To do");
+        assert_eq!(resolve(&statuses, None, true).unwrap().name, "Done");
+    }
+}
@@ -0,0 +1,217 @@
+//lists and items saved as templates, for checklists and tasks that get made over and over
+//a template is an ordinary list file in the templates directory, stripped of anything that only made sense
+//for the list it was saved from (completion, trash, archive and dates); an item template is one holding a single item
+//names, descriptions and the title can hold placeholders that are filled in when a list is made from the template
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::*;
+
+use crate::{list_file_path, TodoList, TodoListItem};
+
+pub const TEMPLATES_DIR: &str = "templates"; //in the workspace folder, unless another folder is picked
+pub const ITEM_TEMPLATES_DIR: &str = "items"; //in the templates folder
+
+//placeholder, and the chrono format it's replaced with
+const PLACEHOLDERS: [(&str, &str); 5] = [
+    ("{date}", "%Y-%m-%d"),
+    ("{weekday}", "%A"),
+    ("{time}", "%H:%M"),
+    ("{month}", "%B"),
+    ("{year}", "%Y"),
+];
+
+pub fn fill_placeholders(text: &str, now: DateTime<Local>) -> String {
+    let mut filled = String::from(text);
+    for (placeholder, format) in PLACEHOLDERS {
+        if filled.contains(placeholder) {
+            filled = filled.replace(placeholder, &now.format(format).to_string());
+        }
+    }
+    filled
+}
+
+//a copy of the list with every item reopened and its history, dates, trash and archive dropped
+pub fn as_template(list: &TodoList) -> TodoList {
+    let mut template = list.clone();
+    template.trash.clear();
+    template.archive.clear();
+    template.archived_at = None;
+    let open = template.default_status(false);
+    let now = Local::now();
+    template.for_each_item_mut(&mut |item| {
+        //reopening logs the change, which is cleared along with the rest of the history below
+        item.move_to_status(&open, now);
+        item.completion_history.clear();
+        item.skipped_occurrences = 0;
+        item.completion_log.clear();
+        item.status_log.clear();
+        item.due = None;
+        item.start = None;
+        item.carried_days = 0;
+    });
+    template
+}
+
+//a new list from the template, with placeholders filled in and everything created at now
+pub fn instantiate(template: &TodoList, now: DateTime<Local>) -> TodoList {
+    let mut list = as_template(template);
+    list.title = fill_placeholders(&list.title, now);
+    list.date_created = now;
+    list.for_each_item_mut(&mut |item: &mut TodoListItem| {
+        item.name = fill_placeholders(&item.name, now);
+        item.description = fill_placeholders(&item.description, now);
+        item.date_created = now;
+    });
+    list
+}
+
+//the templates in dir as (name, path), sorted by name; a missing directory just means there are none yet
+//the paths are what load_template takes, since a name doesn't always lead back to its file
+pub fn template_names(dir: &Path) -> io::Result<Vec<(String, PathBuf)>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut templates = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|x| x == "json") {
+            if let Some(stem) = path.file_stem() {
+                templates.push((stem.to_string_lossy().into_owned(), path));
+            }
+        }
+    }
+    templates.sort();
+    Ok(templates)
+}
+
+//overwrites any template with the same name, returns where it was saved
+pub fn save_template(dir: &Path, name: &str, list: &TodoList) -> io::Result<PathBuf> {
+    if name.trim().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "templates need a name"));
+    }
+    fs::create_dir_all(dir)?;
+    let path = list_file_path(dir, name);
+    as_template(list).save(&path)?;
+    Ok(path)
+}
+
+pub fn load_template(path: &Path) -> io::Result<TodoList> {
+    TodoList::from_file(&path.to_path_buf())
+}
+
+//item templates are kept apart from list templates, in ITEM_TEMPLATES_DIR inside dir
+//each is a list holding just the item and its subtasks, so it keeps the fields of the list it came from
+pub fn save_item_template(dir: &Path, name: &str, list: &TodoList, id: usize) -> io::Result<PathBuf> {
+    let Some(item) = list.get_item_by_id(id) else {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("there is no item with id {id} in this list")));
+    };
+    let mut template = list.clone();
+    template.title = String::from(name.trim());
+    template.items = vec![item.clone()];
+    save_template(&dir.join(ITEM_TEMPLATES_DIR), name, &template)
+}
+
+//the template's items, with placeholders filled in and fresh ids in the destination list, ready to be inserted there
+pub fn instantiate_items(template: &TodoList, destination: &mut TodoList, now: DateTime<Local>) -> Vec<TodoListItem> {
+    destination.adopt_items(instantiate(template, now).items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_now() -> DateTime<Local> {
+        //a Wednesday
+        Local.with_ymd_and_hms(2024, 5, 22, 9, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        assert_eq!(fill_placeholders("Release {date} ({weekday})", fixed_now()), "Release 2024-05-22 (Wednesday)");
+        assert_eq!(fill_placeholders("{month} {year} at {time}", fixed_now()), "May 2024 at 09:30");
+        assert_eq!(fill_placeholders("{unknown}", fixed_now()), "{unknown}");
+    }
+
+    #[test]
+    fn instantiating_reopens_items_and_fills_placeholders() {
+        let mut list = TodoList::new(String::from("Onboarding {date}"));
+        let first = list.add(String::from("Set up laptop"), String::from("Before {weekday}"));
+        let second = list.add_subtask(first, String::from("Install tools"), String::new()).unwrap();
+        list.set_completed_at(second, true, fixed_now());
+        let removed = list.add(String::from("Removed"), String::new());
+        list.remove_item(removed);
+
+        let made = instantiate(&list, fixed_now());
+        assert_eq!(made.title, "Onboarding 2024-05-22");
+        assert_eq!(made.items[0].description, "Before Wednesday");
+        let subtask = made.get_item_by_id(second).unwrap();
+        assert!(!subtask.completed);
+        assert!(subtask.completion_log().is_empty());
+        assert!(made.trash().is_empty());
+        assert_eq!(made.items[0].date_created(), fixed_now());
+    }
+
+    #[test]
+    fn saves_lists_and_loads_them_by_name() {
+        let dir = tempfile::tempdir().expect("saves_lists_and_loads_them_by_name() test panicked; temporary directory creation failed.");
+        let templates = dir.path().join(TEMPLATES_DIR);
+        assert!(template_names(&templates).unwrap().is_empty());
+
+        let mut list = TodoList::new(String::from("Release {date}"));
+        list.add(String::from("Tag the release"), String::new());
+        save_template(&templates, "Release", &list).unwrap();
+        save_template(&templates, "../Onboarding", &list).unwrap();
+        assert!(save_template(&templates, " ", &list).is_err());
+
+        let found = template_names(&templates).unwrap();
+        let names: Vec<&str> = found.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["Release", "___Onboarding"]);
+        let loaded = load_template(&found[0].1).unwrap();
+        assert_eq!(loaded.title, "Release {date}");
+        assert_eq!(loaded.items[0].name, "Tag the release");
+    }
+
+    #[test]
+    fn loads_templates_whose_names_dont_survive_sanitizing() {
+        let dir = tempfile::tempdir().expect("loads_templates_whose_names_dont_survive_sanitizing() test panicked; temporary directory creation failed.");
+        //put there by hand, so the name has characters save_template would have replaced
+        TodoList::new(String::from("Hand made")).save(&dir.path().join("Q1: review.json")).unwrap();
+
+        let templates = template_names(dir.path()).unwrap();
+        assert_eq!(templates[0].0, "Q1: review");
+        assert_eq!(load_template(&templates[0].1).unwrap().title, "Hand made");
+    }
+
+    #[test]
+    fn item_templates_are_inserted_with_fresh_ids() {
+        let dir = tempfile::tempdir().expect("item_templates_are_inserted_with_fresh_ids() test panicked; temporary directory creation failed.");
+        let mut list = TodoList::new(String::from("Releases"));
+        let other = list.add(String::from("Unrelated"), String::new());
+        let release = list.add(String::from("Release {date}"), String::new());
+        let tag = list.add_subtask(release, String::from("Tag it"), String::new()).unwrap();
+        list.set_completed_at(tag, true, fixed_now());
+        list.add_dependency(release, other).unwrap();
+
+        save_item_template(dir.path(), "Release", &list, release).unwrap();
+        assert!(save_item_template(dir.path(), "Missing", &list, 100).is_err());
+        assert!(template_names(dir.path()).unwrap().is_empty());
+        let (name, path) = &template_names(&dir.path().join(ITEM_TEMPLATES_DIR)).unwrap()[0];
+        assert_eq!(name, "Release");
+        let template = load_template(path).unwrap();
+        assert_eq!(template.items.len(), 1);
+
+        let items = instantiate_items(&template, &mut list, fixed_now());
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "Release 2024-05-22");
+        assert!(items[0].id() > tag);
+        assert!(!items[0].subtasks[0].completed);
+        assert_ne!(items[0].subtasks[0].id(), tag);
+        //the blocker was outside the template, so it doesn't come along
+        assert!(items[0].blocked_by.is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//built-in views that gather items from every list at once, by when they're due or when they were done
+//a view doesn't hold any items itself, it only says which item in which list to show, so edits made from
+//a view go straight to the list the item belongs to
+
+use ::serde::{Serialize, Deserialize};
+
+use chrono::*;
+
+use crate::{TodoList, TodoListItem};
+
+pub const DEFAULT_COMPLETED_DAYS: u32 = 7;
+const UPCOMING_DAYS: i64 = 7;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SmartView {
+    Today, //open items due today, including ones that went overdue earlier today
+    Overdue,
+    NextWeek, //open items due after today and within the next 7 days
+    NoDueDate,
+    Completed { days: u32 }, //items completed in the last `days` days
+}
+
+//an item shown in a view
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ViewEntry {
+    pub list_index: usize, //which of the gathered lists the item is in
+    pub item_id: usize,
+}
+
+impl SmartView {
+    pub fn label(&self) -> String {
+        match self {
+            SmartView::Today => String::from("Today"),
+            SmartView::Overdue => String::from("Overdue"),
+            SmartView::NextWeek => format!("Next {UPCOMING_DAYS} days"),
+            SmartView::NoDueDate => String::from("No due date"),
+            SmartView::Completed { days: 1 } => String::from("Completed in the last day"),
+            SmartView::Completed { days } => format!("Completed in the last {days} days"),
+        }
+    }
+
+    //the items in one list that belong in this view, subtasks included
+    pub fn items<'a>(&self, list: &'a TodoList, now: DateTime<Local>) -> Vec<&'a TodoListItem> {
+        match self {
+            SmartView::Today => list.due_today_items(now),
+            SmartView::Overdue => list.overdue_items(now),
+            SmartView::NextWeek => list.upcoming_items(now, UPCOMING_DAYS),
+            SmartView::NoDueDate => list.all_items().into_iter().filter(|x| !x.completed && x.due.is_none()).collect(),
+            SmartView::Completed { days } => list.completed_between(now - Duration::days(i64::from(*days)), now),
+        }
+    }
+}
+
+//every item in the lists that belongs in the view; due dates come soonest first and completed items most recent first,
+//with ties (and items without a due date) kept in the order of the lists
+pub fn gather(lists: &[&TodoList], view: SmartView, now: DateTime<Local>) -> Vec<ViewEntry> {
+    let mut found: Vec<(usize, &TodoListItem)> = Vec::new();
+    for (list_index, list) in lists.iter().enumerate() {
+        found.extend(view.items(list, now).into_iter().map(|item| (list_index, item)));
+    }
+    match view {
+        SmartView::NoDueDate => {}
+        SmartView::Completed { .. } => found.sort_by_key(|(_, x)| std::cmp::Reverse(x.completed_at)),
+        _ => found.sort_by_key(|(_, x)| x.due),
+    }
+    found.into_iter().map(|(list_index, item)| ViewEntry { list_index, item_id: item.id }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 22, 12, 0, 0).unwrap()
+    }
+
+    fn add_due(list: &mut TodoList, name: &str, due: Option<DateTime<Local>>) -> usize {
+        let id = list.add(String::from(name), String::new());
+        list.change_item_dates_by_id(due, None, id);
+        id
+    }
+
+    #[test]
+    fn gathers_due_items_from_every_list_soonest_first() {
+        let now = fixed_now();
+        let mut home = TodoList::new(String::from("home"));
+        let mut work = TodoList::new(String::from("work"));
+        let late = add_due(&mut home, "Late", Some(now - Duration::days(2)));
+        let tonight = add_due(&mut home, "Tonight", Some(now + Duration::hours(6)));
+        let this_morning = add_due(&mut work, "This morning", Some(now - Duration::hours(3)));
+        let friday = add_due(&mut work, "Friday", Some(now + Duration::days(2)));
+        add_due(&mut work, "Next month", Some(now + Duration::days(30)));
+        let someday = add_due(&mut home, "Someday", None);
+        let lists = [&home, &work];
+
+        let entries = |view| -> Vec<(usize, usize)> {
+            gather(&lists, view, now).into_iter().map(|x| (x.list_index, x.item_id)).collect()
+        };
+        assert_eq!(entries(SmartView::Today), vec![(1, this_morning), (0, tonight)]);
+        assert_eq!(entries(SmartView::Overdue), vec![(0, late), (1, this_morning)]);
+        assert_eq!(entries(SmartView::NextWeek), vec![(1, friday)]);
+        assert_eq!(entries(SmartView::NoDueDate), vec![(0, someday)]);
+    }
+
+    #[test]
+    fn gathers_recently_completed_items_most_recent_first() {
+        let now = fixed_now();
+        let mut home = TodoList::new(String::from("home"));
+        let mut work = TodoList::new(String::from("work"));
+        let old = home.add(String::from("Old"), String::new());
+        let yesterday = home.add(String::from("Yesterday"), String::new());
+        let just_now = work.add(String::from("Just now"), String::new());
+        work.add(String::from("Still open"), String::new());
+        home.set_completed_at(old, true, now - Duration::days(10));
+        home.set_completed_at(yesterday, true, now - Duration::days(1));
+        work.set_completed_at(just_now, true, now - Duration::minutes(5));
+
+        let week: Vec<usize> = gather(&[&home, &work], SmartView::Completed { days: 7 }, now).into_iter().map(|x| x.item_id).collect();
+        assert_eq!(week, vec![just_now, yesterday]);
+        assert_eq!(gather(&[&home, &work], SmartView::Completed { days: 30 }, now).len(), 3);
+        assert_eq!(SmartView::Completed { days: 30 }.label(), "Completed in the last 30 days");
+    }
+}